@@ -581,3 +581,23 @@ pub fn clone_fat_val_100_and_remove_half(b: &mut Bencher) {
         map
     })
 }
+
+fn bench_append(b: &mut Bencher, n: usize) {
+    let evens = (0..n).map(|i| (i * 2, i)).collect::<BTreeMap<_, _>>();
+    let odds = (0..n).map(|i| (i * 2 + 1, i)).collect::<BTreeMap<_, _>>();
+    b.iter(|| {
+        let mut map = evens.clone();
+        map.append(&mut odds.clone());
+        map
+    })
+}
+
+#[bench]
+pub fn append_interleaved_100(b: &mut Bencher) {
+    bench_append(b, 100);
+}
+
+#[bench]
+pub fn append_interleaved_10k(b: &mut Bencher) {
+    bench_append(b, 10_000);
+}
@@ -1,7 +1,9 @@
+use super::map::MIN_LEN;
 use super::merge_iter::MergeIterInner;
-use super::node::{self, Root};
+use super::node::{self, ForceResult::*, Root};
 use core::alloc::Allocator;
 use core::iter::FusedIterator;
+use core::mem;
 
 impl<K, V> Root<K, V> {
     /// Appends all key-value pairs from the union of two ascending iterators,
@@ -24,14 +26,102 @@ pub fn append_from_sorted_iters<I, A: Allocator + Clone>(
     ) where
         K: Ord,
         I: Iterator<Item = (K, V)> + FusedIterator,
+    {
+        self.merge_from_sorted_iters(left, right, |a_next, b_next| b_next.or(a_next), length, alloc)
+    }
+
+    /// Appends the key-value pairs that `combine` picks or makes for each key in
+    /// the union of two ascending iterators, incrementing a `length` variable
+    /// along the way, like `append_from_sorted_iters`.
+    ///
+    /// `combine` receives the pair from each iterator that produces the key, and
+    /// `None` from the other one. If it returns `None`, nothing is appended for
+    /// that key. To keep the tree in strictly ascending order, it should only
+    /// return pairs with the key it received.
+    pub fn merge_from_sorted_iters<I, F, A: Allocator + Clone>(
+        &mut self,
+        left: I,
+        right: I,
+        combine: F,
+        length: &mut usize,
+        alloc: A,
+    ) where
+        K: Ord,
+        I: Iterator<Item = (K, V)> + FusedIterator,
+        F: FnMut(Option<(K, V)>, Option<(K, V)>) -> Option<(K, V)>,
     {
         // We prepare to merge `left` and `right` into a sorted sequence in linear time.
-        let iter = MergeIter(MergeIterInner::new(left, right));
+        let iter = MergeIter(MergeIterInner::new(left, right), combine);
 
         // Meanwhile, we build a tree from the sorted sequence in linear time.
         self.bulk_push(iter, length, alloc)
     }
 
+    /// Appends a key-value pair and then all key-value pairs of the tree `right`,
+    /// moving the nodes of the shorter tree into the taller one as a whole,
+    /// in time logarithmic in the length of the taller tree.
+    ///
+    /// The result is meaningful only if the tree is ordered by key and `key`
+    /// lies between the keys in `self` and those in `right`. If both trees
+    /// respect all `BTreeMap` tree invariants, then so does the result, and
+    /// its subtree lengths are exact if they were exact in both trees.
+    pub fn append_tree<A: Allocator + Clone>(
+        &mut self,
+        key: K,
+        val: V,
+        mut right: Root<K, V>,
+        alloc: A,
+    ) {
+        if self.height() == right.height() {
+            // Put both trees under a new root, and balance its two children,
+            // of which at most one can be underfull since both are roots.
+            let mut root = self.push_internal_level(alloc.clone());
+            root.push(key, val, right);
+            let mut last_kv = root.last_kv().consider_for_balancing();
+            let (left_len, right_len) = (last_kv.left_child_len(), last_kv.right_child_len());
+            if last_kv.can_merge() {
+                last_kv.merge_tracking_parent(alloc.clone());
+                self.fix_top(alloc);
+            } else if left_len < MIN_LEN {
+                last_kv.bulk_steal_right(MIN_LEN - left_len);
+            } else if right_len < MIN_LEN {
+                last_kv.bulk_steal_left(MIN_LEN - right_len);
+            }
+            return;
+        }
+
+        // Hang the shorter tree from the border of the taller tree that faces it.
+        let edge_on_left = self.height() < right.height();
+        if edge_on_left {
+            mem::swap(self, &mut right);
+        }
+        let subtree = right;
+        let mut node = self.borrow_mut();
+        while node.height() > subtree.height() + 1 {
+            node = match node.force() {
+                Internal(internal) if edge_on_left => internal.first_edge().descend(),
+                Internal(internal) => internal.last_edge().descend(),
+                Leaf(_) => unreachable!(),
+            };
+        }
+        let Internal(node) = node.force() else { unreachable!() };
+        let edge = if edge_on_left { node.first_edge() } else { node.last_edge() };
+        if let Some(split) =
+            edge.insert_subtree_recursing(key, val, subtree, edge_on_left, alloc.clone())
+        {
+            let (kv, right) = (split.kv, split.right);
+            self.push_internal_level(alloc.clone()).push(kv.0, kv.1, right);
+        }
+
+        // The root of the shorter tree, now on the border, is the only node
+        // that may be underfull.
+        if edge_on_left {
+            self.fix_left_border(alloc);
+        } else {
+            self.fix_right_border(alloc);
+        }
+    }
+
     /// Pushes all key-value pairs to the end of the tree, incrementing a
     /// `length` variable along the way. The latter makes it easier for the
     /// caller to avoid a leak when the iterator panicks.
@@ -39,7 +129,20 @@ pub fn bulk_push<I, A: Allocator + Clone>(&mut self, iter: I, length: &mut usize
     where
         I: Iterator<Item = (K, V)>,
     {
-        let mut cur_node = self.borrow_mut().last_leaf_edge().into_node();
+        // Settles the right border once pushing stops, even if it stops
+        // because the iterator panics, so that the tree stays valid.
+        struct FixRightBorder<'a, K, V>(&'a mut Root<K, V>);
+
+        impl<K, V> Drop for FixRightBorder<'_, K, V> {
+            fn drop(&mut self) {
+                self.0.borrow_mut().last_leaf_edge().into_node().recalc_ancestor_subtree_lens();
+                self.0.fix_right_border_of_plentiful();
+            }
+        }
+
+        let guard = FixRightBorder(self);
+        let this = &mut *guard.0;
+        let mut cur_node = this.borrow_mut().last_leaf_edge().into_node();
         // Iterate through all key-value pairs, pushing them into nodes at the right level.
         for (key, value) in iter {
             // Try to push key-value pair into the current leaf node.
@@ -50,6 +153,9 @@ pub fn bulk_push<I, A: Allocator + Clone>(&mut self, iter: I, length: &mut usize
                 let mut open_node;
                 let mut test_node = cur_node.forget_type();
                 loop {
+                    // Nothing more will be pushed below `test_node`, so its
+                    // subtree length can be settled now.
+                    test_node.recalc_subtree_len();
                     match test_node.ascend() {
                         Ok(parent) => {
                             let parent = parent.into_node();
@@ -64,7 +170,7 @@ pub fn bulk_push<I, A: Allocator + Clone>(&mut self, iter: I, length: &mut usize
                         }
                         Err(_) => {
                             // We are at the top, create a new root node and push there.
-                            open_node = self.push_internal_level(alloc.clone());
+                            open_node = this.push_internal_level(alloc.clone());
                             break;
                         }
                     }
@@ -86,22 +192,29 @@ pub fn bulk_push<I, A: Allocator + Clone>(&mut self, iter: I, length: &mut usize
             // the appended elements even if advancing the iterator panicks.
             *length += 1;
         }
-        self.fix_right_border_of_plentiful();
     }
 }
 
 // An iterator for merging two sorted sequences into one
-struct MergeIter<K, V, I: Iterator<Item = (K, V)>>(MergeIterInner<I>);
+struct MergeIter<K, V, I: Iterator<Item = (K, V)>, F>(MergeIterInner<I>, F);
 
-impl<K: Ord, V, I> Iterator for MergeIter<K, V, I>
+impl<K: Ord, V, I, F> Iterator for MergeIter<K, V, I, F>
 where
     I: Iterator<Item = (K, V)> + FusedIterator,
+    F: FnMut(Option<(K, V)>, Option<(K, V)>) -> Option<(K, V)>,
 {
     type Item = (K, V);
 
-    /// If two keys are equal, returns the key-value pair from the right source.
+    /// Skips any keys for which the combining function produces nothing.
     fn next(&mut self) -> Option<(K, V)> {
-        let (a_next, b_next) = self.0.nexts(|a: &(K, V), b: &(K, V)| K::cmp(&a.0, &b.0));
-        b_next.or(a_next)
+        loop {
+            let (a_next, b_next) = self.0.nexts(|a: &(K, V), b: &(K, V)| K::cmp(&a.0, &b.0));
+            if a_next.is_none() && b_next.is_none() {
+                return None;
+            }
+            if let Some(next) = (self.1)(a_next, b_next) {
+                return Some(next);
+            }
+        }
     }
 }
@@ -0,0 +1,52 @@
+use core::iter::Peekable;
+
+/// An iterator for checking that the keys of an iterator are strictly ascending.
+/// When encountering a key that is not greater than the previous one, it stops
+/// yielding and remembers that it did so.
+///
+/// Used by [`BTreeMap::from_sorted_iter`][1].
+///
+/// [1]: crate::collections::BTreeMap::from_sorted_iter
+pub struct CheckSortedIter<K, V, I>
+where
+    I: Iterator<Item = (K, V)>,
+{
+    iter: Peekable<I>,
+    unordered: bool,
+}
+
+impl<K, V, I> CheckSortedIter<K, V, I>
+where
+    I: Iterator<Item = (K, V)>,
+{
+    pub fn new(iter: I) -> Self {
+        Self { iter: iter.peekable(), unordered: false }
+    }
+
+    /// Returns `true` if iteration stopped because of an out-of-order key.
+    pub fn is_unordered(&self) -> bool {
+        self.unordered
+    }
+}
+
+impl<K, V, I> Iterator for CheckSortedIter<K, V, I>
+where
+    K: Ord,
+    I: Iterator<Item = (K, V)>,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        if self.unordered {
+            return None;
+        }
+
+        let next = self.iter.next()?;
+        if let Some(peeked) = self.iter.peek() {
+            if next.0 >= peeked.0 {
+                self.unordered = true;
+            }
+        }
+        Some(next)
+    }
+}
@@ -13,6 +13,7 @@
 use crate::alloc::{Allocator, Global};
 
 use super::borrow::DormantMutRef;
+use super::check_sorted_iter::CheckSortedIter;
 use super::dedup_sorted_iter::DedupSortedIter;
 use super::navigate::{LazyLeafRange, LeafRange};
 use super::node::{self, marker, ForceResult::*, Handle, NodeRef, Root};
@@ -177,6 +178,8 @@ pub struct BTreeMap<
 > {
     root: Option<Root<K, V>>,
     length: usize,
+    /// Whether the subtree lengths in the tree are kept exact, see `track_ranks`.
+    track_ranks: bool,
     /// `ManuallyDrop` to control drop order (needs to be dropped after all the nodes).
     pub(super) alloc: ManuallyDrop<A>,
     // For dropck; the `Box` avoids making the `Unpin` impl more strict than before
@@ -219,6 +222,7 @@ fn clone_subtree<'a, K: Clone, V: Clone, A: Allocator + Clone>(
                     let mut out_tree = BTreeMap {
                         root: Some(Root::new(alloc.clone())),
                         length: 0,
+                        track_ranks: false,
                         alloc: ManuallyDrop::new(alloc),
                         _marker: PhantomData,
                     };
@@ -281,11 +285,14 @@ fn clone_subtree<'a, K: Clone, V: Clone, A: Allocator + Clone>(
             }
         }
 
-        if self.is_empty() {
+        let mut out_tree = if self.is_empty() {
             BTreeMap::new_in((*self.alloc).clone())
         } else {
             clone_subtree(self.root.as_ref().unwrap().reborrow(), (*self.alloc).clone()) // unwrap succeeds because not empty
-        }
+        };
+        // The clone is built from the leaves up, so its subtree lengths are exact.
+        out_tree.track_ranks = self.track_ranks;
+        out_tree
     }
 }
 
@@ -627,7 +634,98 @@ impl<K, V> BTreeMap<K, V> {
     #[inline]
     #[must_use]
     pub const fn new() -> BTreeMap<K, V> {
-        BTreeMap { root: None, length: 0, alloc: ManuallyDrop::new(Global), _marker: PhantomData }
+        BTreeMap {
+            root: None,
+            length: 0,
+            track_ranks: false,
+            alloc: ManuallyDrop::new(Global),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Makes a `BTreeMap` from an iterator whose keys are in strictly ascending
+    /// order, in linear time.
+    ///
+    /// Unlike collecting into a map, this neither buffers nor sorts the
+    /// elements, and only ever compares a key with the key before it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`UnorderedKeyError`] if a key is not greater than the key
+    /// before it. The elements consumed so far and the remainder of the
+    /// iterator are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_from_sorted_iter)]
+    /// use std::collections::BTreeMap;
+    ///
+    /// let map = BTreeMap::from_sorted_iter([(1, "a"), (2, "b"), (3, "c")]).unwrap();
+    /// assert_eq!(map.len(), 3);
+    /// assert_eq!(map[&2], "b");
+    ///
+    /// assert!(BTreeMap::from_sorted_iter([(1, "a"), (3, "c"), (2, "b")]).is_err());
+    /// assert!(BTreeMap::from_sorted_iter([(1, "a"), (1, "b")]).is_err());
+    /// ```
+    #[unstable(feature = "btree_from_sorted_iter", issue = "none")]
+    pub fn from_sorted_iter<I>(iter: I) -> Result<BTreeMap<K, V>, UnorderedKeyError>
+    where
+        K: Ord,
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut root = Root::new(Global);
+        let mut length = 0;
+        let mut iter = CheckSortedIter::new(iter.into_iter());
+        root.bulk_push(&mut iter, &mut length, Global);
+        // Drops whatever was consumed before the unordered key, if there was one.
+        let map = BTreeMap::from_sorted_root(root, length);
+        if iter.is_unordered() {
+            return Err(UnorderedKeyError {});
+        }
+        Ok(map)
+    }
+
+    /// Makes a `BTreeMap` from an iterator whose keys are in strictly ascending
+    /// order, in linear time, without checking the order.
+    ///
+    /// # Safety
+    ///
+    /// You must ensure that the `BTreeMap` invariants are maintained.
+    /// Specifically, the iterator must yield keys in strictly ascending order,
+    /// which also makes them unique.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_from_sorted_iter)]
+    /// use std::collections::BTreeMap;
+    ///
+    /// // SAFETY: the keys are strictly ascending.
+    /// let map = unsafe { BTreeMap::from_sorted_iter_unchecked((0..100).map(|i| (i, i * i))) };
+    /// assert_eq!(map.len(), 100);
+    /// assert_eq!(map[&9], 81);
+    /// ```
+    #[unstable(feature = "btree_from_sorted_iter", issue = "none")]
+    pub unsafe fn from_sorted_iter_unchecked<I>(iter: I) -> BTreeMap<K, V>
+    where
+        K: Ord,
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut root = Root::new(Global);
+        let mut length = 0;
+        root.bulk_push(iter.into_iter(), &mut length, Global);
+        BTreeMap::from_sorted_root(root, length)
+    }
+
+    fn from_sorted_root(root: Root<K, V>, length: usize) -> BTreeMap<K, V> {
+        BTreeMap {
+            root: Some(root),
+            length,
+            track_ranks: false,
+            alloc: ManuallyDrop::new(Global),
+            _marker: PhantomData,
+        }
     }
 }
 
@@ -650,6 +748,7 @@ pub fn clear(&mut self) {
         drop(BTreeMap {
             root: mem::replace(&mut self.root, None),
             length: mem::replace(&mut self.length, 0),
+            track_ranks: false,
             alloc: self.alloc.clone(),
             _marker: PhantomData,
         });
@@ -672,7 +771,13 @@ pub fn clear(&mut self) {
     /// ```
     #[unstable(feature = "btreemap_alloc", issue = "32838")]
     pub const fn new_in(alloc: A) -> BTreeMap<K, V, A> {
-        BTreeMap { root: None, length: 0, alloc: ManuallyDrop::new(alloc), _marker: PhantomData }
+        BTreeMap {
+            root: None,
+            length: 0,
+            track_ranks: false,
+            alloc: ManuallyDrop::new(alloc),
+            _marker: PhantomData,
+        }
     }
 }
 
@@ -1160,11 +1265,15 @@ pub fn append(&mut self, other: &mut Self)
         // We can just swap `self` and `other` if `self` is empty.
         if self.is_empty() {
             mem::swap(self, other);
+            self.keep_track_ranks_after_swap(other);
             return;
         }
 
+        let track_ranks = self.track_ranks;
         let self_iter = mem::replace(self, Self::new_in((*self.alloc).clone())).into_iter();
         let other_iter = mem::replace(other, Self::new_in((*self.alloc).clone())).into_iter();
+        // The tree is built anew from the leaves up, so its subtree lengths are exact.
+        self.track_ranks = track_ranks;
         let root = self.root.get_or_insert_with(|| Root::new((*self.alloc).clone()));
         root.append_from_sorted_iters(
             self_iter,
@@ -1174,6 +1283,196 @@ pub fn append(&mut self, other: &mut Self)
         )
     }
 
+    /// Moves all elements from `other` into `self`, calling `conflict` to
+    /// combine the values of keys present in both maps.
+    ///
+    /// `conflict` receives the key, the value from `self` and the value from
+    /// `other`, and returns the value to keep. The key from `self` is kept and
+    /// the key from `other` is dropped.
+    ///
+    /// Only the range of keys spanned by both maps is walked in order and built
+    /// anew, in time linear in the number of elements in that range, instead of
+    /// searching `self` for every element of `other`. Elements outside that
+    /// range stay in their nodes, which are moved into `self` as whole subtrees,
+    /// so merging maps whose keys don't overlap takes logarithmic time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_merge)]
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut a = BTreeMap::from([(1, 10), (2, 20), (3, 30)]);
+    /// let b = BTreeMap::from([(3, 3), (4, 4)]);
+    ///
+    /// a.merge_with(b, |_key, a, b| a + b);
+    /// assert_eq!(Vec::from_iter(a), [(1, 10), (2, 20), (3, 33), (4, 4)]);
+    /// ```
+    #[unstable(feature = "btree_merge", issue = "none")]
+    pub fn merge_with<F>(&mut self, mut other: Self, mut conflict: F)
+    where
+        K: Ord,
+        F: FnMut(&K, V, V) -> V,
+    {
+        // Do we have to merge anything at all?
+        if other.is_empty() {
+            return;
+        }
+
+        // We can just swap `self` and `other` if `self` is empty.
+        if self.is_empty() {
+            mem::swap(self, &mut other);
+            self.keep_track_ranks_after_swap(&mut other);
+            return;
+        }
+
+        self.merge_overlap(other, true, |self_next, other_next| match (self_next, other_next) {
+            (Some((key, self_val)), Some((_, other_val))) => {
+                let val = conflict(&key, self_val, other_val);
+                Some((key, val))
+            }
+            (self_next, other_next) => self_next.or(other_next),
+        })
+    }
+
+    /// Merges two non-empty maps like `merge_sorted`, but only walks the range
+    /// of keys that both maps span. The elements below and above that range
+    /// are moved into `self` as whole subtrees if `keep_outer`, and are
+    /// dropped otherwise.
+    pub(super) fn merge_overlap<F>(&mut self, mut other: Self, keep_outer: bool, combine: F)
+    where
+        K: Ord,
+        F: FnMut(Option<(K, V)>, Option<(K, V)>) -> Option<(K, V)>,
+    {
+        let self_first = self.first_key_value().unwrap().0;
+        let self_last = self.last_key_value().unwrap().0;
+        let other_first = other.first_key_value().unwrap().0;
+        let other_last = other.last_key_value().unwrap().0;
+        if self_last < other_first || other_last < self_first {
+            if !keep_outer {
+                self.clear();
+            } else if self_last < other_first {
+                self.graft(other, true);
+            } else {
+                self.graft(other, false);
+            }
+            return;
+        }
+
+        // Split off the elements below the keys of the other map.
+        let lower = match self_first.cmp(other_first) {
+            Ordering::Less => {
+                let rest = self.split_off(other_first);
+                Some(mem::replace(self, rest))
+            }
+            Ordering::Greater => {
+                let rest = other.split_off(self_first);
+                Some(mem::replace(&mut other, rest))
+            }
+            Ordering::Equal => None,
+        };
+
+        // Split off the elements above the keys of the other map. A key equal
+        // to the last key of the other map goes back, as it's in both maps.
+        let self_last = self.last_key_value().unwrap().0;
+        let other_last = other.last_key_value().unwrap().0;
+        let upper = match self_last.cmp(other_last) {
+            Ordering::Less => {
+                let mut upper = other.split_off(self_last);
+                if upper.first_key_value().unwrap().0 == self_last {
+                    let (key, val) = upper.pop_first().unwrap();
+                    other.insert(key, val);
+                }
+                Some(upper)
+            }
+            Ordering::Greater => {
+                let mut upper = self.split_off(other_last);
+                if upper.first_key_value().unwrap().0 == other_last {
+                    let (key, val) = upper.pop_first().unwrap();
+                    self.insert(key, val);
+                }
+                Some(upper)
+            }
+            Ordering::Equal => None,
+        };
+
+        self.merge_sorted(other, combine);
+        if keep_outer {
+            if let Some(lower) = lower {
+                self.graft(lower, false);
+            }
+            if let Some(upper) = upper {
+                self.graft(upper, true);
+            }
+        }
+    }
+
+    /// Moves all elements of `other` into `self`, where all keys of `other`
+    /// are greater than those in `self` if `other_is_greater`, and less than
+    /// those in `self` otherwise. The nodes of the smaller map are moved over
+    /// as a whole, in time logarithmic in the length of the larger map.
+    fn graft(&mut self, mut other: Self, other_is_greater: bool)
+    where
+        K: Ord,
+    {
+        let kv = if other_is_greater { other.pop_first() } else { other.pop_last() };
+        let Some((key, val)) = kv else { return };
+        if self.is_empty() {
+            mem::swap(self, &mut other);
+            self.keep_track_ranks_after_swap(&mut other);
+            self.insert(key, val);
+            return;
+        }
+        if self.track_ranks && !other.track_ranks {
+            other.recalc_subtree_lens();
+        }
+
+        let other_len = mem::take(&mut other.length);
+        let other_root = other.root.take().unwrap();
+        let root = self.root.as_mut().unwrap();
+        if other_is_greater {
+            root.append_tree(key, val, other_root, (*self.alloc).clone());
+        } else {
+            let mut left_root = other_root;
+            mem::swap(root, &mut left_root);
+            root.append_tree(key, val, left_root, (*self.alloc).clone());
+        }
+        self.length += other_len + 1;
+    }
+
+    /// Rebuilds `self` from the elements of both `self` and `other`, walking
+    /// both in order. For each key, `combine` receives the element from each
+    /// map holding the key, and returns the element to keep, if any.
+    fn merge_sorted<F>(&mut self, other: Self, combine: F)
+    where
+        K: Ord,
+        F: FnMut(Option<(K, V)>, Option<(K, V)>) -> Option<(K, V)>,
+    {
+        let track_ranks = self.track_ranks;
+        let self_iter = mem::replace(self, Self::new_in((*self.alloc).clone())).into_iter();
+        let other_iter = other.into_iter();
+        // The tree is built anew from the leaves up, so its subtree lengths are exact.
+        self.track_ranks = track_ranks;
+        let root = self.root.get_or_insert_with(|| Root::new((*self.alloc).clone()));
+        root.merge_from_sorted_iters(
+            self_iter,
+            other_iter,
+            combine,
+            &mut self.length,
+            (*self.alloc).clone(),
+        )
+    }
+
+    /// Swaps back whether `self` and `other` track ranks, after swapping the
+    /// maps, and makes the subtree lengths that `self` got from `other` exact
+    /// if need be.
+    fn keep_track_ranks_after_swap(&mut self, other: &mut Self) {
+        mem::swap(&mut self.track_ranks, &mut other.track_ranks);
+        if self.track_ranks && !other.track_ranks {
+            self.recalc_subtree_lens();
+        }
+    }
+
     /// Constructs a double-ended iterator over a sub-range of elements in the map.
     /// The simplest way is to use the range syntax `min..max`, thus `range(min..max)` will
     /// yield elements from min (inclusive) to max (exclusive).
@@ -1255,6 +1554,153 @@ pub fn range_mut<T: ?Sized, R>(&mut self, range: R) -> RangeMut<'_, K, V>
         }
     }
 
+    /// Makes the map keep track of the position of each of its elements, so
+    /// that [`nth_key_value`], [`rank`] and [`range_len`] take time logarithmic
+    /// in the length of the map, instead of linear.
+    ///
+    /// The first call takes time linear in the length of the map. From then
+    /// on, every insertion and removal updates a count in each node on the
+    /// path to the root, which makes them somewhat slower. Maps that never
+    /// call this method skip those updates. Clones of the map, and maps split
+    /// off from it, track ranks as well.
+    ///
+    /// [`nth_key_value`]: BTreeMap::nth_key_value
+    /// [`rank`]: BTreeMap::rank
+    /// [`range_len`]: BTreeMap::range_len
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_rank)]
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut scores = BTreeMap::new();
+    /// scores.track_ranks();
+    /// for (score, player) in [(70, "a"), (85, "b"), (40, "c"), (95, "d")] {
+    ///     scores.insert(score, player);
+    /// }
+    /// assert_eq!(scores.rank(&85), 2);
+    /// assert_eq!(scores.nth_key_value(1), Some((&70, &"a")));
+    /// ```
+    #[unstable(feature = "btree_rank", issue = "none")]
+    pub fn track_ranks(&mut self) {
+        if !self.track_ranks {
+            self.recalc_subtree_lens();
+            self.track_ranks = true;
+        }
+    }
+
+    fn recalc_subtree_lens(&mut self) {
+        if let Some(root) = &mut self.root {
+            root.borrow_mut().recalc_all_subtree_lens();
+        }
+    }
+
+    /// Returns the key-value pair at the given position in the map, i.e. the
+    /// pair whose key is preceded by exactly `index` smaller keys.
+    ///
+    /// This takes time logarithmic in the length of the map if it
+    /// [tracks ranks](BTreeMap::track_ranks), like `map.iter().nth(index)`
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_rank)]
+    /// use std::collections::BTreeMap;
+    ///
+    /// let map = BTreeMap::from([(30, "c"), (10, "a"), (20, "b")]);
+    /// assert_eq!(map.nth_key_value(0), Some((&10, &"a")));
+    /// assert_eq!(map.nth_key_value(2), Some((&30, &"c")));
+    /// assert_eq!(map.nth_key_value(3), None);
+    /// ```
+    #[unstable(feature = "btree_rank", issue = "none")]
+    pub fn nth_key_value(&self, index: usize) -> Option<(&K, &V)> {
+        if !self.track_ranks {
+            return self.iter().nth(index);
+        }
+        let root_node = self.root.as_ref()?.reborrow();
+        root_node.select_kv(index).map(Handle::into_kv)
+    }
+
+    /// Returns the number of keys in the map that are smaller than the given key.
+    ///
+    /// If the map contains the key, this is its position, as understood by
+    /// [`nth_key_value`]. Otherwise, it is the position the key would get if it
+    /// were inserted. This takes time logarithmic in the length of the map if
+    /// it [tracks ranks](BTreeMap::track_ranks), and linear otherwise.
+    ///
+    /// The key may be any borrowed form of the map's key type, but the ordering
+    /// on the borrowed form *must* match the ordering on the key type.
+    ///
+    /// [`nth_key_value`]: BTreeMap::nth_key_value
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_rank)]
+    /// use std::collections::BTreeMap;
+    ///
+    /// let map = BTreeMap::from([(30, "c"), (10, "a"), (20, "b")]);
+    /// assert_eq!(map.rank(&20), 1);
+    /// assert_eq!(map.rank(&25), 2);
+    /// assert_eq!(map.rank(&5), 0);
+    /// assert_eq!(map.nth_key_value(map.rank(&30)), Some((&30, &"c")));
+    /// ```
+    #[unstable(feature = "btree_rank", issue = "none")]
+    pub fn rank<Q: ?Sized>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q> + Ord,
+        Q: Ord,
+    {
+        if !self.track_ranks {
+            return self.range((Bound::Unbounded, Bound::Excluded(key))).count();
+        }
+        match &self.root {
+            Some(root) => root.reborrow().lower_bound(SearchBound::Included(key)).rank(),
+            None => 0,
+        }
+    }
+
+    /// Returns the number of elements in a sub-range of the map.
+    ///
+    /// This gives the same result as `map.range(range).count()`, but takes time
+    /// logarithmic in the length of the map instead of linear in the length of
+    /// the range, if the map [tracks ranks](BTreeMap::track_ranks).
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_rank)]
+    /// use std::collections::BTreeMap;
+    ///
+    /// let map: BTreeMap<i32, ()> = (0..100).map(|i| (i * 10, ())).collect();
+    /// assert_eq!(map.range_len(..), 100);
+    /// assert_eq!(map.range_len(250..=500), 26);
+    /// assert_eq!(map.range_len(991..), 0);
+    /// ```
+    #[unstable(feature = "btree_rank", issue = "none")]
+    pub fn range_len<T: ?Sized, R>(&self, range: R) -> usize
+    where
+        T: Ord,
+        K: Borrow<T> + Ord,
+        R: RangeBounds<T>,
+    {
+        if !self.track_ranks {
+            return self.range(range).count();
+        }
+        if let Some(root) = &self.root {
+            root.reborrow().range_search(range).len()
+        } else {
+            0
+        }
+    }
+
     /// Gets the given key's corresponding entry in the map for in-place manipulation.
     ///
     /// # Examples
@@ -1353,6 +1799,7 @@ pub fn split_off<Q: ?Sized + Ord>(&mut self, key: &Q) -> Self
         BTreeMap {
             root: Some(right_root),
             length: right_len,
+            track_ranks: self.track_ranks,
             alloc: self.alloc.clone(),
             _marker: PhantomData,
         }
@@ -1407,6 +1854,7 @@ pub(super) fn extract_if_inner(&mut self) -> (ExtractIfInner<'_, K, V>, A)
             (
                 ExtractIfInner {
                     length: &mut self.length,
+                    track_ranks: self.track_ranks,
                     dormant_root: Some(dormant_root),
                     cur_leaf_edge: Some(front),
                 },
@@ -1416,6 +1864,7 @@ pub(super) fn extract_if_inner(&mut self) -> (ExtractIfInner<'_, K, V>, A)
             (
                 ExtractIfInner {
                     length: &mut self.length,
+                    track_ranks: self.track_ranks,
                     dormant_root: None,
                     cur_leaf_edge: None,
                 },
@@ -1477,7 +1926,13 @@ pub(crate) fn bulk_build_from_sorted_iter<I>(iter: I, alloc: A) -> Self
         let mut root = Root::new(alloc.clone());
         let mut length = 0;
         root.bulk_push(DedupSortedIter::new(iter.into_iter()), &mut length, alloc.clone());
-        BTreeMap { root: Some(root), length, alloc: ManuallyDrop::new(alloc), _marker: PhantomData }
+        BTreeMap {
+            root: Some(root),
+            length,
+            track_ranks: false,
+            alloc: ManuallyDrop::new(alloc),
+            _marker: PhantomData,
+        }
     }
 }
 
@@ -1891,6 +2346,8 @@ pub struct ExtractIf<
 pub(super) struct ExtractIfInner<'a, K, V> {
     /// Reference to the length field in the borrowed map, updated live.
     length: &'a mut usize,
+    /// Whether the borrowed map tracks ranks.
+    track_ranks: bool,
     /// Buried reference to the root field in the borrowed map.
     /// Wrapped in `Option` to allow drop handler to `take` it.
     dormant_root: Option<DormantMutRef<'a, Root<K, V>>>,
@@ -1952,6 +2409,7 @@ pub(super) fn next<F, A: Allocator + Clone>(&mut self, pred: &mut F, alloc: A) -
                         root.pop_internal_level(alloc.clone());
                         self.dormant_root = Some(DormantMutRef::new(root).1);
                     },
+                    self.track_ranks,
                     alloc.clone(),
                 );
                 self.cur_leaf_edge = Some(pos);
@@ -2633,6 +3091,7 @@ pub fn lower_bound_mut<Q: ?Sized>(&mut self, bound: Bound<&Q>) -> CursorMut<'_,
                         current: None,
                         root: dormant_root,
                         length: &mut self.length,
+                        track_ranks: self.track_ranks,
                         alloc: &mut *self.alloc,
                     },
                 };
@@ -2645,6 +3104,7 @@ pub fn lower_bound_mut<Q: ?Sized>(&mut self, bound: Bound<&Q>) -> CursorMut<'_,
                 current: Some(edge),
                 root: dormant_root,
                 length: &mut self.length,
+                track_ranks: self.track_ranks,
                 alloc: &mut *self.alloc,
             },
         }
@@ -2756,6 +3216,7 @@ pub fn upper_bound_mut<Q: ?Sized>(&mut self, bound: Bound<&Q>) -> CursorMut<'_,
                         current: None,
                         root: dormant_root,
                         length: &mut self.length,
+                        track_ranks: self.track_ranks,
                         alloc: &mut *self.alloc,
                     },
                 };
@@ -2768,6 +3229,7 @@ pub fn upper_bound_mut<Q: ?Sized>(&mut self, bound: Bound<&Q>) -> CursorMut<'_,
                 current: Some(edge),
                 root: dormant_root,
                 length: &mut self.length,
+                track_ranks: self.track_ranks,
                 alloc: &mut *self.alloc,
             },
         }
@@ -2865,6 +3327,7 @@ pub struct CursorMutKey<
     current: Option<Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge>>,
     root: DormantMutRef<'a, Option<node::Root<K, V>>>,
     length: &'a mut usize,
+    track_ranks: bool,
     alloc: &'a mut A,
 }
 
@@ -3132,13 +3595,14 @@ pub unsafe fn insert_after_unchecked(&mut self, key: K, value: V) {
             Some(current) => current,
         };
 
-        let handle = edge.insert_recursing(key, value, self.alloc.clone(), |ins| {
-            drop(ins.left);
-            // SAFETY: The handle to the newly inserted value is always on a
-            // leaf node, so adding a new root node doesn't invalidate it.
-            let root = unsafe { self.root.reborrow().as_mut().unwrap() };
-            root.push_internal_level(self.alloc.clone()).push(ins.kv.0, ins.kv.1, ins.right)
-        });
+        let handle =
+            edge.insert_recursing(key, value, self.track_ranks, self.alloc.clone(), |ins| {
+                drop(ins.left);
+                // SAFETY: The handle to the newly inserted value is always on a
+                // leaf node, so adding a new root node doesn't invalidate it.
+                let root = unsafe { self.root.reborrow().as_mut().unwrap() };
+                root.push_internal_level(self.alloc.clone()).push(ins.kv.0, ins.kv.1, ins.right)
+            });
         self.current = Some(handle.left_edge());
         *self.length += 1;
     }
@@ -3178,13 +3642,14 @@ pub unsafe fn insert_before_unchecked(&mut self, key: K, value: V) {
             Some(current) => current,
         };
 
-        let handle = edge.insert_recursing(key, value, self.alloc.clone(), |ins| {
-            drop(ins.left);
-            // SAFETY: The handle to the newly inserted value is always on a
-            // leaf node, so adding a new root node doesn't invalidate it.
-            let root = unsafe { self.root.reborrow().as_mut().unwrap() };
-            root.push_internal_level(self.alloc.clone()).push(ins.kv.0, ins.kv.1, ins.right)
-        });
+        let handle =
+            edge.insert_recursing(key, value, self.track_ranks, self.alloc.clone(), |ins| {
+                drop(ins.left);
+                // SAFETY: The handle to the newly inserted value is always on a
+                // leaf node, so adding a new root node doesn't invalidate it.
+                let root = unsafe { self.root.reborrow().as_mut().unwrap() };
+                root.push_internal_level(self.alloc.clone()).push(ins.kv.0, ins.kv.1, ins.right)
+            });
         self.current = Some(handle.right_edge());
         *self.length += 1;
     }
@@ -3262,7 +3727,11 @@ pub fn remove_next(&mut self) -> Option<(K, V)> {
             // This should be unwrap(), but that doesn't work because NodeRef
             // doesn't implement Debug. The condition is checked above.
             .ok()?
-            .remove_kv_tracking(|| emptied_internal_root = true, self.alloc.clone());
+            .remove_kv_tracking(
+                || emptied_internal_root = true,
+                self.track_ranks,
+                self.alloc.clone(),
+            );
         self.current = Some(pos);
         *self.length -= 1;
         if emptied_internal_root {
@@ -3291,7 +3760,11 @@ pub fn remove_prev(&mut self) -> Option<(K, V)> {
             // This should be unwrap(), but that doesn't work because NodeRef
             // doesn't implement Debug. The condition is checked above.
             .ok()?
-            .remove_kv_tracking(|| emptied_internal_root = true, self.alloc.clone());
+            .remove_kv_tracking(
+                || emptied_internal_root = true,
+                self.track_ranks,
+                self.alloc.clone(),
+            );
         self.current = Some(pos);
         *self.length -= 1;
         if emptied_internal_root {
@@ -3392,7 +3865,8 @@ pub fn remove_prev(&mut self) -> Option<(K, V)> {
 
 /// Error type returned by [`CursorMut::insert_before`] and
 /// [`CursorMut::insert_after`] if the key being inserted is not properly
-/// ordered with regards to adjacent keys.
+/// ordered with regards to adjacent keys, and by [`BTreeMap::from_sorted_iter`]
+/// if the keys are not in strictly ascending order.
 #[derive(Clone, PartialEq, Eq, Debug)]
 #[unstable(feature = "btree_cursors", issue = "107540")]
 pub struct UnorderedKeyError {}
@@ -360,15 +360,22 @@ pub fn insert(mut self, value: V) -> &'a mut V {
                 val_ptr
             }
             Some(handle) => {
-                let new_handle =
-                    handle.insert_recursing(self.key, value, self.alloc.clone(), |ins| {
+                // SAFETY: We only read a field of the map, which `handle` doesn't point into.
+                let track_ranks = unsafe { self.dormant_map.reborrow_shared() }.track_ranks;
+                let new_handle = handle.insert_recursing(
+                    self.key,
+                    value,
+                    track_ranks,
+                    self.alloc.clone(),
+                    |ins| {
                         drop(ins.left);
                         // SAFETY: Pushing a new root node doesn't invalidate
                         // handles to existing nodes.
                         let map = unsafe { self.dormant_map.reborrow() };
                         let root = map.root.as_mut().unwrap(); // same as ins.left
                         root.push_internal_level(self.alloc).push(ins.kv.0, ins.kv.1, ins.right)
-                    });
+                    },
+                );
 
                 // Get the pointer to the value
                 let val_ptr = new_handle.into_val_mut();
@@ -556,8 +563,13 @@ pub fn remove(self) -> V {
     // Body of `remove_entry`, probably separate because the name reflects the returned pair.
     pub(super) fn remove_kv(self) -> (K, V) {
         let mut emptied_internal_root = false;
-        let (old_kv, _) =
-            self.handle.remove_kv_tracking(|| emptied_internal_root = true, self.alloc.clone());
+        // SAFETY: We only read a field of the map, which `self.handle` doesn't point into.
+        let track_ranks = unsafe { self.dormant_map.reborrow_shared() }.track_ranks;
+        let (old_kv, _) = self.handle.remove_kv_tracking(
+            || emptied_internal_root = true,
+            track_ranks,
+            self.alloc.clone(),
+        );
         // SAFETY: we consumed the intermediate root borrow, `self.handle`.
         let map = unsafe { self.dormant_map.awaken() };
         map.length -= 1;
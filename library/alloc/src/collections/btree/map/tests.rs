@@ -50,6 +50,11 @@ fn check_invariants(&self) {
             // Check consistency of `length` with what navigation code encounters.
             assert_eq!(self.length, root_node.calc_length());
 
+            // Check the subtree lengths that positional lookups rely on.
+            if self.track_ranks {
+                assert_eq!(self.length, root_node.assert_subtree_lens());
+            }
+
             // Lastly, check the invariant causing the least harm.
             root_node.assert_min_len(if root_node.height() > 0 { 1 } else { 0 });
         } else {
@@ -925,6 +930,116 @@ fn test_range_panic_3() {
     let _invalid_range = map.range((Excluded(&5), Excluded(&5)));
 }
 
+#[test]
+fn test_nth_key_value_and_rank() {
+    for size in [0, 1, node::CAPACITY, MIN_INSERTS_HEIGHT_1, MIN_INSERTS_HEIGHT_2, 1000] {
+        let untracked = BTreeMap::from_iter((0..size).map(|i| (i * 2, i)));
+        let mut map = untracked.clone();
+        map.track_ranks();
+        map.check();
+        for i in 0..size {
+            assert_eq!(untracked.nth_key_value(i), Some((&(i * 2), &i)));
+            assert_eq!(untracked.rank(&(i * 2 + 1)), i + 1);
+            assert_eq!(map.nth_key_value(i), Some((&(i * 2), &i)));
+            assert_eq!(map.rank(&(i * 2)), i);
+            assert_eq!(map.rank(&(i * 2 + 1)), i + 1);
+        }
+        assert_eq!(map.nth_key_value(size), None);
+        assert_eq!(map.nth_key_value(usize::MAX), None);
+        assert_eq!(map.rank(&usize::MAX), size);
+    }
+}
+
+#[test]
+fn test_nth_key_value_and_rank_after_mutation() {
+    // Miri is too slow
+    let size = if cfg!(miri) { MIN_INSERTS_HEIGHT_2 } else { 1000 };
+    let mut rng = DeterministicRng::new();
+    let mut map = BTreeMap::new();
+    map.track_ranks();
+    for _ in 0..size {
+        map.insert(rng.next() % 2000, ());
+    }
+    for _ in 0..size / 2 {
+        map.remove(&(rng.next() % 2000));
+    }
+    let mut other = map.split_off(&1000);
+    other.retain(|k, _| k % 3 != 0);
+    map.append(&mut other.clone());
+    map.check();
+
+    let mut cursor = map.lower_bound_mut(Bound::Included(&500));
+    cursor.remove_next();
+    cursor.insert_before(500, ()).unwrap();
+    cursor.remove_prev();
+    map.pop_first();
+    map.merge_with(other, |_, _, _| ());
+    map.check();
+
+    for (i, k) in map.keys().enumerate() {
+        assert_eq!(map.nth_key_value(i), Some((k, &())));
+        assert_eq!(map.rank(k), i);
+    }
+    assert_eq!(map.nth_key_value(map.len()), None);
+}
+
+#[test]
+fn test_range_len() {
+    let size = 200;
+    // Miri is too slow
+    let step = if cfg!(miri) { 66 } else { 1 };
+    let untracked = BTreeMap::from_iter((0..size).map(|i| (i, i)));
+    let mut map = untracked.clone();
+    map.track_ranks();
+
+    for i in (0..size).step_by(step) {
+        for j in (i..size).step_by(step) {
+            assert_eq!(untracked.range_len(i..=j), j - i + 1);
+            assert_eq!(map.range_len(i..=j), j - i + 1);
+            assert_eq!(map.range_len(i..j), j - i);
+            assert_eq!(map.range_len((Excluded(i), Unbounded)), size - i - 1);
+        }
+        assert_eq!(map.range_len(..i), i);
+    }
+    assert_eq!(map.range_len(size..), 0);
+    assert_eq!(BTreeMap::<i32, ()>::new().range_len(..), 0);
+}
+
+#[should_panic(expected = "range start is greater than range end in BTreeMap")]
+#[test]
+fn test_range_len_panic() {
+    let map = BTreeMap::from([(3, "a"), (5, "b"), (8, "c")]);
+    let _ = map.range_len((Included(&8), Included(&3)));
+}
+
+#[test]
+fn test_track_ranks_late() {
+    // Miri is too slow
+    let size = if cfg!(miri) { MIN_INSERTS_HEIGHT_2 } else { 1000 };
+    let mut map = BTreeMap::from_iter((0..size).map(|i| (i, ())));
+    for i in (0..size).step_by(3) {
+        map.remove(&i);
+        map.insert(size + i, ());
+    }
+
+    // Subtree lengths that drifted while nothing tracked them are put right.
+    map.track_ranks();
+    map.check();
+    for (i, k) in map.keys().enumerate() {
+        assert_eq!(map.rank(k), i);
+    }
+
+    // Swapping in the elements of an untracked map keeps tracking them.
+    let mut empty = BTreeMap::new();
+    empty.track_ranks();
+    let mut untracked = BTreeMap::from_iter((0..size).map(|i| (i, ())));
+    untracked.remove(&0);
+    empty.append(&mut untracked);
+    empty.check();
+    assert_eq!(empty.rank(&1), 0);
+    assert!(untracked.is_empty() && !untracked.track_ranks);
+}
+
 #[test]
 fn test_retain() {
     let mut map = BTreeMap::from_iter((0..100).map(|x| (x, x * 10)));
@@ -2143,6 +2258,152 @@ fn test_append_ord_chaos() {
     map2.check();
 }
 
+#[test]
+fn test_from_sorted_iter() {
+    for len in [0, 1, node::CAPACITY, node::CAPACITY + 1, 1000] {
+        let map = BTreeMap::from_sorted_iter((0..len).map(|i| (i, i * 2))).unwrap();
+        map.check();
+        assert_eq!(map.len(), len);
+        assert!(map.into_iter().eq((0..len).map(|i| (i, i * 2))));
+
+        let map = unsafe { BTreeMap::from_sorted_iter_unchecked((0..len).map(|i| (i, i * 2))) };
+        map.check();
+        assert_eq!(map.len(), len);
+    }
+}
+
+#[test]
+fn test_from_sorted_iter_unordered() {
+    let a = CrashTestDummy::new(0);
+    let b = CrashTestDummy::new(1);
+    let c = CrashTestDummy::new(2);
+    let d = CrashTestDummy::new(3);
+    let iter = [a.spawn(Panic::Never), c.spawn(Panic::Never), b.spawn(Panic::Never)]
+        .into_iter()
+        .chain([d.spawn(Panic::Never)])
+        .map(|k| (k, ()));
+    assert_eq!(BTreeMap::from_sorted_iter(iter).unwrap_err(), UnorderedKeyError {});
+    assert_eq!(a.dropped(), 1);
+    assert_eq!(b.dropped(), 1);
+    assert_eq!(c.dropped(), 1);
+    assert_eq!(d.dropped(), 1);
+
+    assert!(BTreeMap::from_sorted_iter([(0, 'a'), (0, 'b')]).is_err());
+    assert!(BTreeMap::from_sorted_iter((0..1000).chain(999..1000).map(|i| (i, ()))).is_err());
+}
+
+#[test]
+fn test_merge_with() {
+    let mut a = BTreeMap::from_iter((0..300).step_by(2).map(|i| (i, i)));
+    let b = BTreeMap::from_iter((0..300).step_by(3).map(|i| (i, i)));
+    a.merge_with(b, |&k, a, b| {
+        assert_eq!((a, b), (k, k));
+        assert_eq!(k % 6, 0);
+        0
+    });
+    a.check();
+    assert_eq!(a.len(), 200);
+    for (k, v) in a {
+        assert!(k % 2 == 0 || k % 3 == 0);
+        assert_eq!(v, if k % 6 == 0 { 0 } else { k });
+    }
+
+    let mut a = BTreeMap::new();
+    a.merge_with(BTreeMap::from([(1, 'b')]), |_, _, _| unreachable!());
+    assert_eq!(Vec::from_iter(&a), [(&1, &'b')]);
+    a.merge_with(BTreeMap::new(), |_, _, _| unreachable!());
+    assert_eq!(Vec::from_iter(&a), [(&1, &'b')]);
+}
+
+#[test]
+fn test_merge_with_grafts() {
+    for (track_self, track_other) in [(false, false), (true, false), (false, true), (true, true)] {
+        for self_len in [0, 1, 5, 11, 12, 100, 1000] {
+            for other_len in [1, 5, 11, 12, 100, 1000] {
+                // From entirely below to entirely above the keys of `self`,
+                // including overlapping by only their first or last key.
+                let self_range = 1000..1000 + self_len;
+                for start in [0, 1000 - other_len, 1000 - other_len + 1, 1000, 1000 + self_len] {
+                    let mut map = BTreeMap::from_iter(self_range.clone().map(|k| (k, 0)));
+                    let mut other = BTreeMap::from_iter((start..start + other_len).map(|k| (k, 1)));
+                    if track_self {
+                        map.track_ranks();
+                    }
+                    if track_other {
+                        other.track_ranks();
+                    }
+                    map.merge_with(other, |_, a, b| a + b);
+                    map.check();
+
+                    let mut expected = BTreeMap::from_iter(self_range.clone().map(|k| (k, 0)));
+                    for k in start..start + other_len {
+                        *expected.entry(k).or_insert(0) += 1;
+                    }
+                    assert!(map.iter().eq(expected.iter()));
+                    if track_self {
+                        for (i, k) in map.keys().enumerate() {
+                            assert_eq!(map.rank(k), i);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_merge_with_keeps_own_keys() {
+    let a = CrashTestDummy::new(0);
+    let mut left = BTreeMap::new();
+    left.insert(a.spawn(Panic::Never), 1);
+    let mut right = BTreeMap::new();
+    right.insert(a.spawn(Panic::Never), 2);
+
+    left.merge_with(right, |_, l, r| l + r);
+    assert_eq!(a.dropped(), 1);
+    assert_eq!(left.len(), 1);
+    assert_eq!(left.first_key_value().unwrap().1, &3);
+}
+
+#[test]
+#[cfg_attr(not(panic = "unwind"), ignore = "test requires unwinding support")]
+fn test_merge_with_panic() {
+    let a = CrashTestDummy::new(0);
+    let b = CrashTestDummy::new(1);
+    let c = CrashTestDummy::new(2);
+    let mut left = BTreeMap::new();
+    left.insert(a.spawn(Panic::Never), ());
+    left.insert(b.spawn(Panic::Never), ());
+    left.insert(c.spawn(Panic::Never), ());
+    let mut right = BTreeMap::new();
+    right.insert(b.spawn(Panic::Never), ());
+    right.insert(c.spawn(Panic::Never), ());
+
+    catch_unwind(move || left.merge_with(right, |_, _, _| panic!("conflict"))).unwrap_err();
+    assert_eq!(a.dropped(), 1);
+    assert_eq!(b.dropped(), 2);
+    assert_eq!(c.dropped(), 2);
+}
+
+#[test]
+fn test_merge_with_panic_leaves_map_usable() {
+    let mut map = BTreeMap::from_iter((0..1000).map(|i| (i * 2, i)));
+    let other = BTreeMap::from_iter((0..1000).map(|i| (i * 3, i)));
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        map.merge_with(other, |&k, _, _| if k > 600 { panic!("conflict") } else { k })
+    }));
+    result.unwrap_err();
+    map.check();
+    assert!(map.keys().copied().eq((0..606).filter(|k| k % 2 == 0 || k % 3 == 0)));
+
+    map.insert(1, 1);
+    map.remove(&0);
+    map.check();
+    assert_eq!(map.first_key_value(), Some((&1, &1)));
+    assert_eq!(map.last_key_value(), Some((&604, &302)));
+}
+
 fn rand_data(len: usize) -> Vec<(u32, u32)> {
     let mut rng = DeterministicRng::new();
     Vec::from_iter((0..len).map(|_| (rng.next(), rng.next())))
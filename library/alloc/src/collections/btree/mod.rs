@@ -1,5 +1,6 @@
 mod append;
 mod borrow;
+mod check_sorted_iter;
 mod dedup_sorted_iter;
 mod fix;
 pub mod map;
@@ -7,6 +8,7 @@
 mod merge_iter;
 mod navigate;
 mod node;
+mod rank;
 mod remove;
 mod search;
 pub mod set;
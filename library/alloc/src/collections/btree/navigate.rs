@@ -53,6 +53,14 @@ pub fn next_checked(&mut self) -> Option<(&'a K, &'a V)> {
     pub fn next_back_checked(&mut self) -> Option<(&'a K, &'a V)> {
         self.perform_next_back_checked(|kv| kv.into_kv())
     }
+
+    /// Counts the key-value pairs in the range without visiting them.
+    pub fn len(&self) -> usize {
+        match (self.front, self.back) {
+            (Some(front), Some(back)) => back.rank() - front.rank(),
+            _ => 0,
+        }
+    }
 }
 
 impl<'a, K, V> LeafRange<marker::ValMut<'a>, K, V> {
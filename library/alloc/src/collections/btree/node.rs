@@ -30,6 +30,10 @@
 //   For a leaf node, "having an edge" only means we can identify a position in the node,
 //   since leaf edges are empty and need no data representation. In an internal node,
 //   an edge both identifies a position and contains a pointer to a child node.
+// - An internal node knows the number of key-value pairs in its subtree. Methods that
+//   move key-value pairs or edges between nodes keep this count correct for the nodes
+//   they touch, but changes to the total number of key-value pairs in the tree must be
+//   propagated to the ancestors by the caller.
 
 use core::marker::PhantomData;
 use core::mem::{self, MaybeUninit};
@@ -101,6 +105,16 @@ struct InternalNode<K, V> {
     /// initialized and valid, except that near the end, while the tree is held
     /// through borrow type `Dying`, some of these pointers are dangling.
     edges: [MaybeUninit<BoxedNode<K, V>>; 2 * B],
+
+    /// The number of key-value pairs stored in this node and all of its
+    /// descendants. This lets us find a key-value pair by its position in the
+    /// tree, or the position of a key-value pair, without visiting every node.
+    ///
+    /// Only trees that track ranks keep this exact on every insertion and
+    /// removal. In other trees it drifts away from the actual number, so it is
+    /// only ever updated with wrapping arithmetic, and recalculated from the
+    /// leaves up once the tree starts tracking ranks.
+    subtree_len: usize,
 }
 
 impl<K, V> InternalNode<K, V> {
@@ -113,8 +127,10 @@ impl<K, V> InternalNode<K, V> {
     unsafe fn new<A: Allocator + Clone>(alloc: A) -> Box<Self, A> {
         unsafe {
             let mut node = Box::<Self, _>::new_uninit_in(alloc);
-            // We only need to initialize the data; the edges are MaybeUninit.
+            // We only need to initialize the data and the subtree length;
+            // the edges are MaybeUninit.
             LeafNode::init(ptr::addr_of_mut!((*node.as_mut_ptr()).data));
+            ptr::addr_of_mut!((*node.as_mut_ptr()).subtree_len).write(0);
             node.assume_init()
         }
     }
@@ -225,6 +241,7 @@ fn from_new_leaf<A: Allocator + Clone>(leaf: Box<LeafNode<K, V>, A>) -> Self {
 impl<K, V> NodeRef<marker::Owned, K, V, marker::Internal> {
     fn new_internal<A: Allocator + Clone>(child: Root<K, V>, alloc: A) -> Self {
         let mut new_node = unsafe { InternalNode::new(alloc) };
+        new_node.subtree_len = child.subtree_len();
         new_node.edges[0].write(child.node);
         unsafe { NodeRef::from_new_internal(new_node, child.height + 1) }
     }
@@ -280,6 +297,19 @@ pub fn len(&self) -> usize {
         unsafe { usize::from((*Self::as_leaf_ptr(self)).len) }
     }
 
+    /// Finds the number of key-value pairs stored in the node and all of its
+    /// descendants. For a leaf, this is the same as `len()`.
+    pub fn subtree_len(&self) -> usize {
+        if self.height == 0 {
+            self.len()
+        } else {
+            // As in `len`, we only access the field we need, because there might
+            // be outstanding mutable references to values that we must not invalidate.
+            let internal = self.node.as_ptr() as *const InternalNode<K, V>;
+            unsafe { (*internal).subtree_len }
+        }
+    }
+
     /// Returns the number of levels that the node and leaves are apart. Zero
     /// height means the node is a leaf itself. If you picture trees with the
     /// root on top, the number says at which elevation the node appears.
@@ -541,6 +571,98 @@ impl<'a, K: 'a, V: 'a, Type> NodeRef<marker::Mut<'a>, K, V, Type> {
     pub fn len_mut(&mut self) -> &mut u16 {
         &mut self.as_leaf_mut().len
     }
+
+    /// Applies `f` to every ancestor of this node, working upwards to the root.
+    fn for_each_ancestor<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut NodeRef<marker::Mut<'_>, K, V, marker::Internal>),
+    {
+        let mut parent = unsafe { self.reborrow_mut() }.ascend().ok();
+        while let Some(edge) = parent {
+            let mut node = edge.into_node();
+            f(&mut node);
+            parent = node.forget_type().ascend().ok();
+        }
+    }
+
+    /// Accounts for a key-value pair added to this node in the subtree
+    /// length of every ancestor. Only for trees that track ranks.
+    pub fn increment_ancestor_subtree_lens(&mut self) {
+        self.for_each_ancestor(|node| *node.subtree_len_mut() += 1);
+    }
+
+    /// Accounts for a key-value pair removed from this node in the subtree
+    /// length of every ancestor. Only for trees that track ranks.
+    pub fn decrement_ancestor_subtree_lens(&mut self) {
+        self.for_each_ancestor(|node| *node.subtree_len_mut() -= 1);
+    }
+
+    /// Recalculates the subtree length of every ancestor from its children,
+    /// after the subtree lengths along the path to this node got out of date.
+    pub fn recalc_ancestor_subtree_lens(&mut self) {
+        self.for_each_ancestor(|node| node.recalc_subtree_len());
+    }
+}
+
+impl<BorrowType, K, V> NodeRef<BorrowType, K, V, marker::Internal> {
+    /// Sums up the subtree lengths of the children behind the given edges.
+    ///
+    /// # Safety
+    /// Every item returned by `range` is a valid edge index for the node.
+    pub unsafe fn children_subtree_len<R: Iterator<Item = usize>>(&self, range: R) -> usize {
+        let node = self.reborrow();
+        range.fold(0, |sum, i| {
+            sum.wrapping_add(unsafe { Handle::new_edge(node, i) }.descend().subtree_len())
+        })
+    }
+}
+
+impl<'a, K, V> NodeRef<marker::Mut<'a>, K, V, marker::Internal> {
+    /// Borrows exclusive access to the subtree length of the node.
+    fn subtree_len_mut(&mut self) -> &mut usize {
+        &mut self.as_internal_mut().subtree_len
+    }
+
+    /// Adds `len` to the subtree length of the node.
+    fn add_subtree_len(&mut self, len: usize) {
+        let subtree_len = self.subtree_len_mut();
+        *subtree_len = subtree_len.wrapping_add(len);
+    }
+
+    /// Subtracts `len` from the subtree length of the node.
+    fn sub_subtree_len(&mut self, len: usize) {
+        let subtree_len = self.subtree_len_mut();
+        *subtree_len = subtree_len.wrapping_sub(len);
+    }
+
+    /// Recalculates the subtree length of the node from its own length and
+    /// the subtree lengths of its children.
+    fn recalc_subtree_len(&mut self) {
+        let len = self.len();
+        *self.subtree_len_mut() = len.wrapping_add(unsafe { self.children_subtree_len(0..=len) });
+    }
+}
+
+impl<'a, K, V> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal> {
+    /// Recalculates the subtree length of an internal node from its children.
+    /// Does nothing for a leaf, whose subtree length is always up to date.
+    pub fn recalc_subtree_len(&mut self) {
+        if let ForceResult::Internal(mut node) = unsafe { self.reborrow_mut() }.force() {
+            node.recalc_subtree_len();
+        }
+    }
+
+    /// Recalculates the subtree lengths of all internal nodes in the subtree,
+    /// from the leaves up.
+    pub fn recalc_all_subtree_lens(&mut self) {
+        if let ForceResult::Internal(mut node) = unsafe { self.reborrow_mut() }.force() {
+            for i in 0..=node.len() {
+                let mut child = unsafe { Handle::new_edge(node.reborrow_mut(), i) }.descend();
+                child.recalc_all_subtree_lens();
+            }
+            node.recalc_subtree_len();
+        }
+    }
 }
 
 impl<'a, K, V> NodeRef<marker::Mut<'a>, K, V, marker::Internal> {
@@ -690,6 +812,7 @@ pub fn push(&mut self, key: K, val: V, edge: Root<K, V>) {
         let idx = usize::from(*len);
         assert!(idx < CAPACITY);
         *len += 1;
+        self.add_subtree_len(edge.subtree_len().wrapping_add(1));
         unsafe {
             self.key_area_mut(idx).write(key);
             self.val_area_mut(idx).write(val);
@@ -995,20 +1118,40 @@ fn insert_fit(&mut self, key: K, val: V, edge: Root<K, V>) {
         }
     }
 
+    /// Swaps this edge with the edge to the right of the key-value pair to its right.
+    fn swap_with_next_edge(&mut self) {
+        debug_assert!(self.idx < self.node.len());
+        let idx = self.idx;
+        unsafe {
+            self.node.edge_area_mut(..idx + 2).swap(idx, idx + 1);
+            self.node.correct_childrens_parent_links(idx..idx + 2);
+        }
+    }
+
     /// Inserts a new key-value pair and an edge that will go to the right of that new pair
     /// between this edge and the key-value pair to the right of this edge. This method splits
     /// the node if there isn't enough room.
+    ///
+    /// If `edge_on_left`, the new edge instead takes the place of this edge, and this edge
+    /// goes to the right of the new pair.
+    ///
+    /// The subtree length of this node must already account for the new pair and edge,
+    /// as is the case when they were split off the child node at this edge.
     fn insert<A: Allocator + Clone>(
         mut self,
         key: K,
         val: V,
         edge: Root<K, V>,
+        edge_on_left: bool,
         alloc: A,
     ) -> Option<SplitResult<'a, K, V, marker::Internal>> {
         assert!(edge.height == self.node.height - 1);
 
         if self.node.len() < CAPACITY {
             self.insert_fit(key, val, edge);
+            if edge_on_left {
+                self.swap_with_next_edge();
+            }
             None
         } else {
             let (middle_kv_idx, insertion) = splitpoint(self.idx);
@@ -1023,6 +1166,10 @@ fn insert<A: Allocator + Clone>(
                 },
             };
             insertion_edge.insert_fit(key, val, edge);
+            if edge_on_left {
+                insertion_edge.swap_with_next_edge();
+            }
+            insertion_edge.into_node().recalc_subtree_len();
             Some(result)
         }
     }
@@ -1036,13 +1183,22 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, mark
     /// If the returned result is some `SplitResult`, the `left` field will be the root node.
     /// The returned pointer points to the inserted value, which in the case of `SplitResult`
     /// is in the `left` or `right` tree.
+    ///
+    /// `track_ranks` says whether the tree keeps its subtree lengths exact.
     pub fn insert_recursing<A: Allocator + Clone>(
-        self,
+        mut self,
         key: K,
         value: V,
+        track_ranks: bool,
         alloc: A,
         split_root: impl FnOnce(SplitResult<'a, K, V, marker::LeafOrInternal>),
     ) -> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::KV> {
+        if track_ranks {
+            // Count the new pair in every ancestor up front, so that splitting nodes
+            // on the way up merely redistributes what the subtree lengths count.
+            unsafe { self.reborrow_mut() }.into_node().increment_ancestor_subtree_lens();
+        }
+
         let (mut split, handle) = match self.insert(key, value, alloc.clone()) {
             // SAFETY: we have finished splitting and can now re-awaken the
             // handle to the inserted element.
@@ -1053,7 +1209,7 @@ pub fn insert_recursing<A: Allocator + Clone>(
         loop {
             split = match split.left.ascend() {
                 Ok(parent) => {
-                    match parent.insert(split.kv.0, split.kv.1, split.right, alloc.clone()) {
+                    match parent.insert(split.kv.0, split.kv.1, split.right, false, alloc.clone()) {
                         // SAFETY: we have finished splitting and can now re-awaken the
                         // handle to the inserted element.
                         None => return unsafe { handle.awaken() },
@@ -1071,6 +1227,43 @@ pub fn insert_recursing<A: Allocator + Clone>(
     }
 }
 
+impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>, marker::Edge> {
+    /// Inserts a new key-value pair and a whole subtree next to this edge, like
+    /// `insert_recursing` inserts a pair into a leaf. The subtree goes to the right
+    /// of the new pair, or if `edge_on_left`, takes the place of this edge, which then
+    /// goes to the right of the new pair. The subtree must be one level lower than
+    /// this node, and its root may be underfull, as may be the case after this returns.
+    ///
+    /// The subtree lengths of this node and its ancestors are raised by the length
+    /// of the subtree and the new pair, so they stay exact if they were exact in both.
+    ///
+    /// If the returned result is some `SplitResult`, the `left` field will be the root node.
+    pub fn insert_subtree_recursing<A: Allocator + Clone>(
+        mut self,
+        key: K,
+        val: V,
+        edge: Root<K, V>,
+        edge_on_left: bool,
+        alloc: A,
+    ) -> Option<SplitResult<'a, K, V, marker::LeafOrInternal>> {
+        let added_len = edge.subtree_len().wrapping_add(1);
+        let mut node = unsafe { self.reborrow_mut() }.into_node();
+        node.add_subtree_len(added_len);
+        node.for_each_ancestor(|node| node.add_subtree_len(added_len));
+
+        let mut split =
+            self.insert(key, val, edge, edge_on_left, alloc.clone())?.forget_node_type();
+        loop {
+            split = match split.left.ascend() {
+                Ok(parent) => parent
+                    .insert(split.kv.0, split.kv.1, split.right, false, alloc.clone())?
+                    .forget_node_type(),
+                Err(root) => return Some(SplitResult { left: root, ..split }),
+            };
+        }
+    }
+}
+
 impl<BorrowType: marker::BorrowType, K, V>
     Handle<NodeRef<BorrowType, K, V, marker::Internal>, marker::Edge>
 {
@@ -1264,7 +1457,9 @@ pub fn split<A: Allocator + Clone>(
             );
 
             let height = self.node.height;
-            let right = NodeRef::from_new_internal(new_node, height);
+            let mut right = NodeRef::from_new_internal(new_node, height);
+            right.borrow_mut().recalc_subtree_len();
+            self.node.recalc_subtree_len();
 
             SplitResult { left: self.node, kv, right }
         }
@@ -1408,6 +1603,7 @@ fn do_merge<
                 );
 
                 left_node.correct_childrens_parent_links(old_left_len + 1..new_left_len + 1);
+                left_node.add_subtree_len(right_node.subtree_len().wrapping_add(1));
 
                 alloc.deallocate(right_node.node.cast(), Layout::new::<InternalNode<K, V>>());
             } else {
@@ -1543,6 +1739,10 @@ pub fn bulk_steal_left(&mut self, count: usize) {
                     );
 
                     right.correct_childrens_parent_links(0..new_right_len + 1);
+
+                    let stolen_len = right.children_subtree_len(0..count).wrapping_add(count);
+                    left.sub_subtree_len(stolen_len);
+                    right.add_subtree_len(stolen_len);
                 }
                 (ForceResult::Leaf(_), ForceResult::Leaf(_)) => {}
                 _ => unreachable!(),
@@ -1607,6 +1807,12 @@ pub fn bulk_steal_right(&mut self, count: usize) {
 
                     left.correct_childrens_parent_links(old_left_len + 1..new_left_len + 1);
                     right.correct_childrens_parent_links(0..new_right_len + 1);
+
+                    let stolen_len = left
+                        .children_subtree_len(old_left_len + 1..new_left_len + 1)
+                        .wrapping_add(count);
+                    left.add_subtree_len(stolen_len);
+                    right.sub_subtree_len(stolen_len);
                 }
                 (ForceResult::Leaf(_), ForceResult::Leaf(_)) => {}
                 _ => unreachable!(),
@@ -1639,6 +1845,14 @@ pub fn forget_node_type(
     }
 }
 
+impl<BorrowType, K, V> Handle<NodeRef<BorrowType, K, V, marker::Internal>, marker::KV> {
+    pub fn forget_node_type(
+        self,
+    ) -> Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV> {
+        unsafe { Handle::new_kv(self.node.forget_type(), self.idx) }
+    }
+}
+
 impl<BorrowType, K, V, Type> Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, Type> {
     /// Checks whether the underlying node is an `Internal` node or a `Leaf` node.
     pub fn force(
@@ -1705,6 +1919,12 @@ pub fn move_suffix(
                             right.edge_area_mut(1..new_right_len + 1),
                         );
                         right.correct_childrens_parent_links(1..new_right_len + 1);
+
+                        let moved_len = right
+                            .children_subtree_len(1..new_right_len + 1)
+                            .wrapping_add(new_right_len);
+                        left.sub_subtree_len(moved_len);
+                        right.add_subtree_len(moved_len);
                     }
                     (ForceResult::Leaf(_), ForceResult::Leaf(_)) => {}
                     _ => unreachable!(),
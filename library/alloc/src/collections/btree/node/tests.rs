@@ -17,6 +17,19 @@ pub fn assert_back_pointers(self) {
         }
     }
 
+    // Asserts that the subtree length of each reachable node matches the
+    // number of key-value pairs below it, and returns that number.
+    pub fn assert_subtree_lens(self) -> usize {
+        let mut len = self.len();
+        if let ForceResult::Internal(node) = self.force() {
+            for idx in 0..=node.len() {
+                len += unsafe { Handle::new_edge(node, idx) }.descend().assert_subtree_lens();
+            }
+        }
+        assert_eq!(self.subtree_len(), len);
+        len
+    }
+
     // Renders a multi-line display of the keys in order and in tree hierarchy,
     // picturing the tree growing sideways from its root on the left to its
     // leaves on the right.
@@ -94,6 +107,6 @@ fn test_partial_eq() {
 fn test_sizes() {
     assert_eq!(core::mem::size_of::<LeafNode<(), ()>>(), 16);
     assert_eq!(core::mem::size_of::<LeafNode<i64, i64>>(), 16 + CAPACITY * 2 * 8);
-    assert_eq!(core::mem::size_of::<InternalNode<(), ()>>(), 16 + (CAPACITY + 1) * 8);
-    assert_eq!(core::mem::size_of::<InternalNode<i64, i64>>(), 16 + (CAPACITY * 3 + 1) * 8);
+    assert_eq!(core::mem::size_of::<InternalNode<(), ()>>(), 16 + (CAPACITY + 2) * 8);
+    assert_eq!(core::mem::size_of::<InternalNode<i64, i64>>(), 16 + (CAPACITY * 3 + 2) * 8);
 }
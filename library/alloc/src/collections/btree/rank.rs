@@ -0,0 +1,64 @@
+use super::node::{marker, ForceResult::*, Handle, NodeRef};
+
+impl<BorrowType: marker::BorrowType, K, V> NodeRef<BorrowType, K, V, marker::LeafOrInternal> {
+    /// Finds the key-value pair that has exactly `index` key-value pairs before
+    /// it in the (sub)tree, using the subtree lengths to skip over entire children.
+    /// Returns `None` if the tree holds no more than `index` key-value pairs.
+    pub fn select_kv(
+        self,
+        mut index: usize,
+    ) -> Option<Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>> {
+        if index >= self.subtree_len() {
+            return None;
+        }
+        let mut node = self;
+        loop {
+            match node.force() {
+                Leaf(leaf) => {
+                    return if index < leaf.len() {
+                        Some(unsafe { Handle::new_kv(leaf, index) }.forget_node_type())
+                    } else {
+                        None
+                    };
+                }
+                Internal(internal) => {
+                    let mut edge = internal.first_edge();
+                    loop {
+                        let child_len = edge.reborrow().descend().subtree_len();
+                        if index < child_len {
+                            node = edge.descend();
+                            break;
+                        }
+                        index -= child_len;
+                        match edge.right_kv() {
+                            Ok(kv) if index == 0 => return Some(kv.forget_node_type()),
+                            Ok(kv) => {
+                                index -= 1;
+                                edge = kv.right_edge();
+                            }
+                            Err(_) => return None,
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<BorrowType: marker::BorrowType, K, V>
+    Handle<NodeRef<BorrowType, K, V, marker::Leaf>, marker::Edge>
+{
+    /// Counts the key-value pairs in the tree that lie before this leaf edge,
+    /// by adding up the subtree lengths of the children left of the path to the root.
+    pub fn rank(self) -> usize {
+        let mut rank = self.idx();
+        let mut node = self.into_node().forget_type();
+        while let Ok(parent_edge) = node.ascend() {
+            let idx = parent_edge.idx();
+            let parent = parent_edge.into_node();
+            rank += idx + unsafe { parent.children_subtree_len(0..idx) };
+            node = parent.forget_type();
+        }
+        rank
+    }
+}
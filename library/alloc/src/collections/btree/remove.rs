@@ -7,14 +7,19 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInter
     /// the leaf edge corresponding to that former pair. It's possible this empties
     /// a root node that is internal, which the caller should pop from the map
     /// holding the tree. The caller should also decrement the map's length.
+    ///
+    /// `track_ranks` says whether the tree keeps its subtree lengths exact.
     pub fn remove_kv_tracking<F: FnOnce(), A: Allocator + Clone>(
         self,
         handle_emptied_internal_root: F,
+        track_ranks: bool,
         alloc: A,
     ) -> ((K, V), Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge>) {
         match self.force() {
-            Leaf(node) => node.remove_leaf_kv(handle_emptied_internal_root, alloc),
-            Internal(node) => node.remove_internal_kv(handle_emptied_internal_root, alloc),
+            Leaf(node) => node.remove_leaf_kv(handle_emptied_internal_root, track_ranks, alloc),
+            Internal(node) => {
+                node.remove_internal_kv(handle_emptied_internal_root, track_ranks, alloc)
+            }
         }
     }
 }
@@ -23,9 +28,13 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, mark
     fn remove_leaf_kv<F: FnOnce(), A: Allocator + Clone>(
         self,
         handle_emptied_internal_root: F,
+        track_ranks: bool,
         alloc: A,
     ) -> ((K, V), Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge>) {
         let (old_kv, mut pos) = self.remove();
+        if track_ranks {
+            unsafe { pos.reborrow_mut() }.into_node().decrement_ancestor_subtree_lens();
+        }
         let len = pos.reborrow().into_node().len();
         if len < MIN_LEN {
             let idx = pos.idx();
@@ -76,6 +85,7 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>,
     fn remove_internal_kv<F: FnOnce(), A: Allocator + Clone>(
         self,
         handle_emptied_internal_root: F,
+        track_ranks: bool,
         alloc: A,
     ) -> ((K, V), Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge>) {
         // Remove an adjacent KV from its leaf and then put it back in place of
@@ -83,7 +93,8 @@ fn remove_internal_kv<F: FnOnce(), A: Allocator + Clone>(
         // for the reasons listed in `choose_parent_kv`.
         let left_leaf_kv = self.left_edge().descend().last_leaf_edge().left_kv();
         let left_leaf_kv = unsafe { left_leaf_kv.ok().unwrap_unchecked() };
-        let (left_kv, left_hole) = left_leaf_kv.remove_leaf_kv(handle_emptied_internal_root, alloc);
+        let (left_kv, left_hole) =
+            left_leaf_kv.remove_leaf_kv(handle_emptied_internal_root, track_ranks, alloc);
 
         // The internal node may have been stolen from or merged. Go back right
         // to find where the original KV ended up.
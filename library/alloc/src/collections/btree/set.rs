@@ -1043,6 +1043,81 @@ pub fn append(&mut self, other: &mut Self)
         self.map.append(&mut other.map);
     }
 
+    /// Consumes both sets and returns the set of values in either of them.
+    ///
+    /// For values present in both sets, the value from `self` is kept and the
+    /// one from `other` is dropped.
+    ///
+    /// Unlike collecting [`union`], which looks up and inserts every value,
+    /// this only walks the range of values spanned by both sets, and builds
+    /// the result for that range in time linear in the number of values in
+    /// it. Values outside that range stay in their nodes, which are moved into
+    /// the result as whole subtrees, so the union of sets whose values don't
+    /// overlap takes logarithmic time.
+    ///
+    /// [`union`]: BTreeSet::union
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_merge)]
+    /// use std::collections::BTreeSet;
+    ///
+    /// let a = BTreeSet::from([1, 2, 3]);
+    /// let b = BTreeSet::from([3, 4, 5]);
+    ///
+    /// let union = a.union_into(b);
+    /// assert_eq!(Vec::from_iter(union), [1, 2, 3, 4, 5]);
+    /// ```
+    #[unstable(feature = "btree_merge", issue = "none")]
+    pub fn union_into(mut self, other: Self) -> Self
+    where
+        T: Ord,
+        A: Clone,
+    {
+        self.map.merge_with(other.map, |_, self_val, _| self_val);
+        self
+    }
+
+    /// Consumes both sets and returns the set of values in both of them.
+    ///
+    /// The values are taken from `self`, and all values from `other` are dropped.
+    ///
+    /// Unlike collecting [`intersection`], this only walks the range of values
+    /// spanned by both sets, and builds the result in time linear in the
+    /// number of values in that range. Values outside that range are dropped
+    /// along with their nodes.
+    ///
+    /// [`intersection`]: BTreeSet::intersection
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_merge)]
+    /// use std::collections::BTreeSet;
+    ///
+    /// let a = BTreeSet::from([1, 2, 3]);
+    /// let b = BTreeSet::from([2, 3, 4]);
+    ///
+    /// let intersection = a.intersection_into(b);
+    /// assert_eq!(Vec::from_iter(intersection), [2, 3]);
+    /// ```
+    #[unstable(feature = "btree_merge", issue = "none")]
+    pub fn intersection_into(mut self, other: Self) -> Self
+    where
+        T: Ord,
+        A: Clone,
+    {
+        if other.is_empty() {
+            self.clear();
+        } else if !self.is_empty() {
+            self.map.merge_overlap(other.map, false, |self_next, other_next| {
+                self_next.filter(|_| other_next.is_some())
+            });
+        }
+        self
+    }
+
     /// Splits the collection into two at the value. Returns a new collection
     /// with all elements greater than or equal to the value.
     ///
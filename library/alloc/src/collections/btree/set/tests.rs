@@ -745,6 +745,47 @@ fn test_append() {
     assert_eq!(a.contains(&5), true);
 }
 
+#[test]
+fn test_union_into() {
+    let a = BTreeSet::from_iter((0..300).step_by(2));
+    let b = BTreeSet::from_iter((0..300).step_by(3));
+    let union = a.clone().union_into(b.clone());
+    assert!(union.iter().eq(a.union(&b)));
+    let c = BTreeSet::from_iter(250..1000);
+    let union = c.clone().union_into(a.clone());
+    assert!(union.iter().eq(a.union(&c)));
+
+    assert!(BTreeSet::new().union_into(a.clone()).iter().eq(a.iter()));
+    assert!(a.clone().union_into(BTreeSet::new()).iter().eq(a.iter()));
+}
+
+#[test]
+fn test_union_into_drops_duplicates() {
+    let a = CrashTestDummy::new(0);
+    let b = CrashTestDummy::new(1);
+    let left = BTreeSet::from([a.spawn(Panic::Never), b.spawn(Panic::Never)]);
+    let right = BTreeSet::from([b.spawn(Panic::Never)]);
+
+    let union = left.union_into(right);
+    assert_eq!(union.len(), 2);
+    assert_eq!(b.dropped(), 1);
+}
+
+#[test]
+fn test_intersection_into() {
+    let a = BTreeSet::from_iter((0..300).step_by(2));
+    let b = BTreeSet::from_iter((0..300).step_by(3));
+    let intersection = a.clone().intersection_into(b.clone());
+    assert!(intersection.iter().eq(a.intersection(&b)));
+    let c = BTreeSet::from_iter(250..1000);
+    let intersection = c.clone().intersection_into(a.clone());
+    assert!(intersection.iter().eq(a.intersection(&c)));
+
+    assert!(BTreeSet::new().intersection_into(a.clone()).is_empty());
+    assert!(a.clone().intersection_into(BTreeSet::new()).is_empty());
+    assert!(a.intersection_into(BTreeSet::from([1, 3, 5])).is_empty());
+}
+
 #[test]
 fn test_first_last() {
     let mut a = BTreeSet::new();
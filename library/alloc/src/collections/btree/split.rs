@@ -52,7 +52,13 @@ pub fn split_off<Q: ?Sized + Ord, A: Allocator + Clone>(&mut self, key: &Q, allo
                     left_node = edge.descend();
                     right_node = node.first_edge().descend();
                 }
-                (Leaf(_), Leaf(_)) => break,
+                (Leaf(edge), Leaf(mut node)) => {
+                    // Moving suffixes on the lower levels left the subtree
+                    // lengths on the paths down to these leaves out of date.
+                    edge.into_node().recalc_ancestor_subtree_lens();
+                    node.recalc_ancestor_subtree_lens();
+                    break;
+                }
                 _ => unreachable!(),
             }
         }
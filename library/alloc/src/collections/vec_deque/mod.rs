@@ -11,7 +11,7 @@
 use core::fmt;
 use core::hash::{Hash, Hasher};
 use core::iter::{repeat_n, repeat_with, ByRefSized};
-use core::mem::{ManuallyDrop, SizedTypeProperties};
+use core::mem::{ManuallyDrop, MaybeUninit, SizedTypeProperties};
 use core::ops::{Index, IndexMut, Range, RangeBounds};
 use core::ptr;
 use core::slice;
@@ -1224,6 +1224,86 @@ pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
         unsafe { (&mut *self.buffer_range(a_range), &mut *self.buffer_range(b_range)) }
     }
 
+    /// Returns the remaining spare capacity of the deque as a pair of slices
+    /// of `MaybeUninit<T>`.
+    ///
+    /// The spare capacity follows the back of the deque and may wrap around
+    /// the end of the buffer, so it is split in two just like the contents
+    /// returned by [`as_slices`]. Filling the first slice and then the second
+    /// one, in order, appends elements to the back of the deque once they are
+    /// committed with [`set_len`].
+    ///
+    /// [`as_slices`]: VecDeque::as_slices
+    /// [`set_len`]: VecDeque::set_len
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(vec_deque_spare_capacity)]
+    ///
+    /// use std::collections::VecDeque;
+    ///
+    /// let mut deque = VecDeque::with_capacity(4);
+    /// deque.extend([1, 2, 3]);
+    /// deque.pop_front();
+    /// deque.pop_front();
+    ///
+    /// let (first, second) = deque.spare_capacity_mut();
+    /// assert_eq!(first.len() + second.len(), deque.capacity() - 1);
+    /// for (i, slot) in first.iter_mut().chain(second).enumerate() {
+    ///     slot.write(4 + i);
+    /// }
+    ///
+    /// // Mark the spare capacity as initialized.
+    /// unsafe { deque.set_len(deque.capacity()) };
+    /// assert_eq!(deque.iter().take(3).copied().collect::<Vec<_>>(), [3, 4, 5]);
+    /// ```
+    #[unstable(feature = "vec_deque_spare_capacity", issue = "none")]
+    pub fn spare_capacity_mut(&mut self) -> (&mut [MaybeUninit<T>], &mut [MaybeUninit<T>]) {
+        let cap = self.capacity();
+        let tail = self.to_physical_idx(self.len);
+        // `head < cap` unless `cap == 0`, so this can't overflow.
+        let head_len = cap - self.head;
+        let (a_range, b_range) =
+            if self.len < head_len { (tail..cap, 0..self.head) } else { (tail..self.head, 0..0) };
+        // SAFETY: both ranges lie inside `0..cap` and don't overlap the
+        // initialized elements, so handing them out as uninitialized is fine.
+        unsafe {
+            (
+                &mut *(self.buffer_range(a_range) as *mut [MaybeUninit<T>]),
+                &mut *(self.buffer_range(b_range) as *mut [MaybeUninit<T>]),
+            )
+        }
+    }
+
+    /// Forces the length of the deque to `new_len`.
+    ///
+    /// This is a low-level operation that maintains none of the normal
+    /// invariants of the type. Normally changing the length of a deque is done
+    /// using one of the safe operations instead, such as [`truncate`],
+    /// [`resize`], [`extend`], or [`clear`].
+    ///
+    /// [`truncate`]: VecDeque::truncate
+    /// [`resize`]: VecDeque::resize
+    /// [`extend`]: Extend::extend
+    /// [`clear`]: VecDeque::clear
+    ///
+    /// # Safety
+    ///
+    /// - `new_len` must be less than or equal to [`capacity()`].
+    /// - If `new_len` is larger than the current length, the elements added at
+    ///   the back must have been initialized, in order, through the slices
+    ///   returned by [`spare_capacity_mut`].
+    ///
+    /// [`capacity()`]: VecDeque::capacity
+    /// [`spare_capacity_mut`]: VecDeque::spare_capacity_mut
+    #[inline]
+    #[unstable(feature = "vec_deque_spare_capacity", issue = "none")]
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        debug_assert!(new_len <= self.capacity());
+        self.len = new_len;
+    }
+
     /// Returns the number of elements in the deque.
     ///
     /// # Examples
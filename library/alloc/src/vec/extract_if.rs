@@ -31,6 +31,8 @@ pub struct ExtractIf<
     pub(super) vec: &'a mut Vec<T, A>,
     /// The index of the item that will be inspected by the next call to `next`.
     pub(super) idx: usize,
+    /// The index after the last item that may be drained.
+    pub(super) end: usize,
     /// The number of items that have been drained (removed) thus far.
     pub(super) del: usize,
     /// The original length of `vec` prior to draining.
@@ -60,7 +62,7 @@ impl<T, F, A: Allocator> Iterator for ExtractIf<'_, T, F, A>
 
     fn next(&mut self) -> Option<T> {
         unsafe {
-            while self.idx < self.old_len {
+            while self.idx < self.end {
                 let i = self.idx;
                 let v = slice::from_raw_parts_mut(self.vec.as_mut_ptr(), self.old_len);
                 let drained = (self.pred)(&mut v[i]);
@@ -83,7 +85,7 @@ fn next(&mut self) -> Option<T> {
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        (0, Some(self.old_len - self.idx))
+        (0, Some(self.end - self.idx))
     }
 }
 
@@ -95,12 +97,14 @@ impl<T, F, A: Allocator> Drop for ExtractIf<'_, T, F, A>
     fn drop(&mut self) {
         unsafe {
             if self.idx < self.old_len && self.del > 0 {
-                // This is a pretty messed up state, and there isn't really an
-                // obviously right thing to do. We don't want to keep trying
-                // to execute `pred`, so we just backshift all the unprocessed
-                // elements and tell the vec that they still exist. The backshift
-                // is required to prevent a double-drop of the last successfully
-                // drained item prior to a panic in the predicate.
+                // The unprocessed elements are the ones after the range, plus the
+                // ones in it that were not visited if the iterator was not
+                // exhausted. The latter is a pretty messed up state, and there
+                // isn't really an obviously right thing to do. We don't want to
+                // keep trying to execute `pred`, so we just backshift all the
+                // unprocessed elements and tell the vec that they still exist.
+                // The backshift is required to prevent a double-drop of the last
+                // successfully drained item prior to a panic in the predicate.
                 let ptr = self.vec.as_mut_ptr();
                 let src = ptr.add(self.idx);
                 let dst = src.sub(self.del);
@@ -69,6 +69,8 @@
 use crate::alloc::{Allocator, Global};
 use crate::borrow::{Cow, ToOwned};
 use crate::boxed::Box;
+#[cfg(not(no_global_oom_handling))]
+use crate::collections::BTreeSet;
 use crate::collections::TryReserveError;
 use crate::raw_vec::RawVec;
 
@@ -1702,7 +1704,51 @@ pub fn retain<F>(&mut self, mut f: F)
     /// assert_eq!(vec, [2, 3, 4]);
     /// ```
     #[stable(feature = "vec_retain_mut", since = "1.61.0")]
-    pub fn retain_mut<F>(&mut self, mut f: F)
+    pub fn retain_mut<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let len = self.len();
+        self.retain_in(0, len, f);
+    }
+
+    /// Retains only the elements within `range` specified by the predicate.
+    ///
+    /// In other words, remove all elements `e` within `range` for which `f(&e)`
+    /// returns `false`. Elements outside of `range` are always kept, and the
+    /// predicate is not called on them. Like [`retain`], this operates in place,
+    /// visiting each element of the range exactly once in the original order,
+    /// and preserves the order of the retained elements.
+    ///
+    /// [`retain`]: Vec::retain
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(vec_retain_range)]
+    ///
+    /// let mut vec = vec![1, 2, 3, 4, 5, 6];
+    /// vec.retain_range(2.., |&x| x % 2 == 0);
+    /// assert_eq!(vec, [1, 2, 4, 6]);
+    /// ```
+    #[unstable(feature = "vec_retain_range", issue = "none")]
+    pub fn retain_range<R, F>(&mut self, range: R, mut f: F)
+    where
+        R: RangeBounds<usize>,
+        F: FnMut(&T) -> bool,
+    {
+        let Range { start, end } = slice::range(range, ..self.len());
+        self.retain_in(start, end, |elem| f(elem));
+    }
+
+    /// Removes the elements in `start..end` for which `f` returns `false`, moving
+    /// each run of retained elements, and the tail after `end`, with one copy.
+    fn retain_in<F>(&mut self, start: usize, end: usize, mut f: F)
     where
         F: FnMut(&mut T) -> bool,
     {
@@ -1711,33 +1757,38 @@ pub fn retain_mut<F>(&mut self, mut f: F)
         // since we may make some holes during the process.
         unsafe { self.set_len(0) };
 
-        // Vec: [Kept, Kept, Hole, Hole, Hole, Hole, Unchecked, Unchecked]
-        //      |<-              processed len   ->| ^- next to check
-        //                  |<-  deleted cnt     ->|
-        //      |<-              original_len                          ->|
-        // Kept: Elements which predicate returns true on.
+        // Vec: [Kept, Kept, Hole, Hole, Hole, Hole, Kept, Kept, Unchecked, Unchecked]
+        //      |<-              processed len               ->| ^- next to check
+        //                  |<-   deleted cnt    ->| ^- run start
+        //      |<-                          original_len                          ->|
+        // Kept: Elements which predicate returns true on, or which are before `start`.
         // Hole: Moved or dropped element slot.
-        // Unchecked: Unchecked valid elements.
+        // Unchecked: Unchecked valid elements, including the ones after `end`.
+        //
+        // Kept elements from `run start` on have not been moved to cover the holes yet.
+        // They are moved together once the run ends, which saves moving them one by one.
         //
         // This drop guard will be invoked when predicate or `drop` of element panicked.
-        // It shifts unchecked elements to cover holes and `set_len` to the correct length.
-        // In cases when predicate and `drop` never panick, it will be optimized out.
+        // It shifts the pending run and unchecked elements to cover holes and `set_len`
+        // to the correct length. Otherwise, it finishes the job by doing the same.
         struct BackshiftOnDrop<'a, T, A: Allocator> {
             v: &'a mut Vec<T, A>,
             processed_len: usize,
             deleted_cnt: usize,
+            run_start: usize,
             original_len: usize,
         }
 
         impl<T, A: Allocator> Drop for BackshiftOnDrop<'_, T, A> {
             fn drop(&mut self) {
                 if self.deleted_cnt > 0 {
-                    // SAFETY: Trailing unchecked items must be valid since we never touch them.
+                    // SAFETY: The pending run and trailing unchecked items must be valid
+                    // since we never moved or dropped them.
                     unsafe {
                         ptr::copy(
-                            self.v.as_ptr().add(self.processed_len),
-                            self.v.as_mut_ptr().add(self.processed_len - self.deleted_cnt),
-                            self.original_len - self.processed_len,
+                            self.v.as_ptr().add(self.run_start),
+                            self.v.as_mut_ptr().add(self.run_start - self.deleted_cnt),
+                            self.original_len - self.run_start,
                         );
                     }
                 }
@@ -1748,50 +1799,39 @@ fn drop(&mut self) {
             }
         }
 
-        let mut g = BackshiftOnDrop { v: self, processed_len: 0, deleted_cnt: 0, original_len };
+        let mut g = BackshiftOnDrop {
+            v: self,
+            processed_len: start,
+            deleted_cnt: 0,
+            run_start: start,
+            original_len,
+        };
 
-        fn process_loop<F, T, A: Allocator, const DELETED: bool>(
-            original_len: usize,
-            f: &mut F,
-            g: &mut BackshiftOnDrop<'_, T, A>,
-        ) where
-            F: FnMut(&mut T) -> bool,
-        {
-            while g.processed_len != original_len {
-                // SAFETY: Unchecked element must be valid.
-                let cur = unsafe { &mut *g.v.as_mut_ptr().add(g.processed_len) };
-                if !f(cur) {
-                    // Advance early to avoid double drop if `drop_in_place` panicked.
-                    g.processed_len += 1;
-                    g.deleted_cnt += 1;
-                    // SAFETY: We never touch this element again after dropped.
-                    unsafe { ptr::drop_in_place(cur) };
-                    // We already advanced the counter.
-                    if DELETED {
-                        continue;
-                    } else {
-                        break;
-                    }
-                }
-                if DELETED {
-                    // SAFETY: `deleted_cnt` > 0, so the hole slot must not overlap with current element.
-                    // We use copy for move, and never touch this element again.
-                    unsafe {
-                        let hole_slot = g.v.as_mut_ptr().add(g.processed_len - g.deleted_cnt);
-                        ptr::copy_nonoverlapping(cur, hole_slot, 1);
-                    }
-                }
+        while g.processed_len != end {
+            // SAFETY: Unchecked element must be valid.
+            let cur = unsafe { &mut *g.v.as_mut_ptr().add(g.processed_len) };
+            if f(cur) {
                 g.processed_len += 1;
+                continue;
+            }
+            if g.deleted_cnt > 0 {
+                // SAFETY: `deleted_cnt` holes precede the run of kept elements, so moving
+                // the run back by that much stays within the processed part. We use copy
+                // for move, and never touch the elements in their old place again.
+                unsafe {
+                    let run = g.v.as_mut_ptr().add(g.run_start);
+                    ptr::copy(run, run.sub(g.deleted_cnt), g.processed_len - g.run_start);
+                }
             }
+            // Advance early to avoid double drop if `drop_in_place` panicked.
+            g.processed_len += 1;
+            g.deleted_cnt += 1;
+            g.run_start = g.processed_len;
+            // SAFETY: We never touch this element again after dropped.
+            unsafe { ptr::drop_in_place(cur) };
         }
 
-        // Stage 1: Nothing was deleted.
-        process_loop::<F, T, A, false>(original_len, &mut f, &mut g);
-
-        // Stage 2: Some elements were deleted.
-        process_loop::<F, T, A, true>(original_len, &mut f, &mut g);
-
-        // All item are processed. This can be optimized to `set_len` by LLVM.
+        // All items are processed. Move the last run and the tail into place.
         drop(g);
     }
 
@@ -1819,6 +1859,44 @@ pub fn dedup_by_key<F, K>(&mut self, mut key: F)
         self.dedup_by(|a, b| key(a) == key(b))
     }
 
+    /// Removes all but the first of the elements in the vector that resolve to the same key,
+    /// whether they are consecutive or not.
+    ///
+    /// Unlike [`dedup_by_key`], this doesn't require the vector to be sorted, and it keeps
+    /// the order of the retained elements. Each key is computed exactly once, in order, and
+    /// remembered in a [`BTreeSet`] while the vector is processed, so the whole operation takes
+    /// O(*n* \* log(*n*)) time and moves the retained elements at most once.
+    ///
+    /// The keys must implement [`Ord`] rather than [`Hash`], since hash-based sets live in
+    /// `std`. There, you can get hash-based deduplication by calling [`retain`] with a closure
+    /// that inserts the keys into a `HashSet`.
+    ///
+    /// [`dedup_by_key`]: Vec::dedup_by_key
+    /// [`BTreeSet`]: crate::collections::BTreeSet
+    /// [`retain`]: Vec::retain
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(vec_dedup_by_key_all)]
+    ///
+    /// let mut vec = vec![10, 20, 21, 30, 20, 11];
+    ///
+    /// vec.dedup_by_key_all(|i| *i / 10);
+    ///
+    /// assert_eq!(vec, [10, 20, 30]);
+    /// ```
+    #[cfg(not(no_global_oom_handling))]
+    #[unstable(feature = "vec_dedup_by_key_all", issue = "none")]
+    pub fn dedup_by_key_all<F, K>(&mut self, mut key: F)
+    where
+        F: FnMut(&mut T) -> K,
+        K: Ord,
+    {
+        let mut seen = BTreeSet::new();
+        self.retain_mut(|elem| seen.insert(key(elem)));
+    }
+
     /// Removes all but the first of consecutive elements in the vector satisfying a given equality
     /// relation.
     ///
@@ -3209,6 +3287,51 @@ pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Splice<'_, I::IntoI
     /// ```
     #[unstable(feature = "extract_if", reason = "recently added", issue = "43244")]
     pub fn extract_if<F>(&mut self, filter: F) -> ExtractIf<'_, T, F, A>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let old_len = self.len();
+        self.extract_if_in(0, old_len, filter)
+    }
+
+    /// Creates an iterator which uses a closure to determine if an element within `range`
+    /// should be removed.
+    ///
+    /// This behaves like [`extract_if`], except that only the elements within `range` are
+    /// passed to the closure and may be removed. Elements outside of `range` are always
+    /// retained, and the ones after it are moved back all at once when the iterator is dropped.
+    ///
+    /// [`extract_if`]: Vec::extract_if
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the vector.
+    ///
+    /// # Examples
+    ///
+    /// Removing the even numbers from the second half of a vector:
+    ///
+    /// ```
+    /// #![feature(vec_extract_if_range)]
+    /// let mut numbers = vec![1, 2, 3, 4, 5, 6, 7, 8];
+    ///
+    /// let evens = numbers.extract_if_range(4.., |x| *x % 2 == 0).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(evens, [6, 8]);
+    /// assert_eq!(numbers, [1, 2, 3, 4, 5, 7]);
+    /// ```
+    #[unstable(feature = "vec_extract_if_range", issue = "none")]
+    pub fn extract_if_range<R, F>(&mut self, range: R, filter: F) -> ExtractIf<'_, T, F, A>
+    where
+        R: RangeBounds<usize>,
+        F: FnMut(&mut T) -> bool,
+    {
+        let Range { start, end } = slice::range(range, ..self.len());
+        self.extract_if_in(start, end, filter)
+    }
+
+    fn extract_if_in<F>(&mut self, start: usize, end: usize, filter: F) -> ExtractIf<'_, T, F, A>
     where
         F: FnMut(&mut T) -> bool,
     {
@@ -3219,7 +3342,7 @@ pub fn extract_if<F>(&mut self, filter: F) -> ExtractIf<'_, T, F, A>
             self.set_len(0);
         }
 
-        ExtractIf { vec: self, idx: 0, del: 0, old_len, pred: filter }
+        ExtractIf { vec: self, idx: start, end, del: 0, old_len, pred: filter }
     }
 }
 
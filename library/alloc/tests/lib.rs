@@ -42,6 +42,10 @@
 #![feature(drain_keep_rest)]
 #![feature(local_waker)]
 #![feature(vec_pop_if)]
+#![feature(vec_retain_range)]
+#![feature(vec_extract_if_range)]
+#![feature(vec_dedup_by_key_all)]
+#![feature(vec_deque_spare_capacity)]
 #![allow(internal_features)]
 #![deny(fuzzy_provenance_casts)]
 #![deny(unsafe_op_in_unsafe_fn)]
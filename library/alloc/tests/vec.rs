@@ -405,6 +405,81 @@ fn test_retain_maybeuninits() {
     assert_eq!(vec, [2, 4]);
 }
 
+#[test]
+fn test_retain_runs() {
+    let mut vec = Vec::from_iter(0..100);
+    vec.retain(|&x| x % 10 < 3 || x % 7 == 0);
+    assert_eq!(vec, Vec::from_iter((0..100).filter(|&x| x % 10 < 3 || x % 7 == 0)));
+
+    let mut vec = vec![String::from("a"), String::from("b"), String::from("c")];
+    vec.retain(|s| s != "a");
+    assert_eq!(vec, ["b", "c"]);
+}
+
+#[test]
+#[cfg_attr(not(panic = "unwind"), ignore = "test requires unwinding support")]
+fn test_retain_pred_panic_in_run() {
+    let v = (0..8).map(Rc::new).collect::<Vec<_>>();
+    let mut kept = v.clone();
+    catch_unwind(AssertUnwindSafe(|| {
+        kept.retain(|r| match **r {
+            1 | 2 => false,
+            6 => panic!(),
+            _ => true,
+        });
+    }))
+    .unwrap_err();
+    // The run of kept elements before the panic is moved into place along with the rest.
+    assert_eq!(kept.iter().map(|r| **r).collect::<Vec<_>>(), [0, 3, 4, 5, 6, 7]);
+    drop(kept);
+    assert!(v.iter().all(|r| Rc::strong_count(r) == 1));
+}
+
+#[test]
+fn test_retain_range() {
+    let mut vec = Vec::from_iter(0..10);
+    let mut visited = Vec::new();
+    vec.retain_range(2..8, |&x| {
+        visited.push(x);
+        x % 2 == 0
+    });
+    assert_eq!(visited, [2, 3, 4, 5, 6, 7]);
+    assert_eq!(vec, [0, 1, 2, 4, 6, 8, 9]);
+
+    let mut vec = Vec::from_iter(0..10);
+    vec.retain_range(..=3, |_| false);
+    assert_eq!(vec, [4, 5, 6, 7, 8, 9]);
+    vec.retain_range(4.., |_| false);
+    assert_eq!(vec, [4, 5, 6, 7]);
+    vec.retain_range(2..2, |_| unreachable!());
+    assert_eq!(vec, [4, 5, 6, 7]);
+}
+
+#[test]
+#[should_panic]
+fn test_retain_range_out_of_bounds() {
+    let mut vec = vec![1, 2, 3];
+    vec.retain_range(..5, |_| true);
+}
+
+#[test]
+#[cfg_attr(not(panic = "unwind"), ignore = "test requires unwinding support")]
+fn test_retain_range_pred_panic() {
+    let v = (0..8).map(Rc::new).collect::<Vec<_>>();
+    let mut kept = v.clone();
+    catch_unwind(AssertUnwindSafe(|| {
+        kept.retain_range(1..6, |r| match **r {
+            2 => false,
+            4 => panic!(),
+            _ => true,
+        });
+    }))
+    .unwrap_err();
+    assert_eq!(kept.iter().map(|r| **r).collect::<Vec<_>>(), [0, 1, 3, 4, 5, 6, 7]);
+    drop(kept);
+    assert!(v.iter().all(|r| Rc::strong_count(r) == 1));
+}
+
 #[test]
 fn test_dedup() {
     fn case(a: Vec<i32>, b: Vec<i32>) {
@@ -439,6 +514,30 @@ fn case(a: Vec<i32>, b: Vec<i32>) {
     case(vec![10, 11, 20, 21, 22, 30, 31], vec![10, 20, 30]);
 }
 
+#[test]
+fn test_dedup_by_key_all() {
+    fn case(a: Vec<i32>, b: Vec<i32>) {
+        let mut v = a;
+        v.dedup_by_key_all(|i| *i / 10);
+        assert_eq!(v, b);
+    }
+    case(vec![], vec![]);
+    case(vec![10], vec![10]);
+    case(vec![10, 11], vec![10]);
+    case(vec![30, 20, 10], vec![30, 20, 10]);
+    case(vec![10, 20, 11, 30, 21], vec![10, 20, 30]);
+    case(vec![31, 10, 32, 11, 20, 33], vec![31, 10, 20]);
+
+    let mut keys = Vec::new();
+    let mut v = vec![3, 1, 3, 2, 1];
+    v.dedup_by_key_all(|&mut i| {
+        keys.push(i);
+        i
+    });
+    assert_eq!(v, [3, 1, 2]);
+    assert_eq!(keys, [3, 1, 3, 2, 1]);
+}
+
 #[test]
 fn test_dedup_by() {
     let mut vec = vec!["foo", "bar", "Bar", "baz", "bar"];
@@ -1662,6 +1761,39 @@ fn drop(&mut self) {
     }
 }
 
+#[test]
+fn extract_if_range() {
+    let mut vec = Vec::from_iter(0..10);
+    let mut visited = Vec::new();
+    let removed = vec
+        .extract_if_range(3..7, |x| {
+            visited.push(*x);
+            *x % 2 == 0
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(visited, [3, 4, 5, 6]);
+    assert_eq!(removed, [4, 6]);
+    assert_eq!(vec, [0, 1, 2, 3, 5, 7, 8, 9]);
+
+    let mut vec = Vec::from_iter(0..10);
+    let mut iter = vec.extract_if_range(5.., |_| true);
+    assert_eq!(iter.size_hint(), (0, Some(5)));
+    assert_eq!(iter.next(), Some(5));
+    drop(iter);
+    assert_eq!(vec, [0, 1, 2, 3, 4, 6, 7, 8, 9]);
+
+    let mut vec = vec![1, 2, 3];
+    assert_eq!(vec.extract_if_range(..0, |_| true).count(), 0);
+    assert_eq!(vec, [1, 2, 3]);
+}
+
+#[test]
+#[should_panic]
+fn extract_if_range_out_of_bounds() {
+    let mut vec = vec![1, 2, 3];
+    let _ = vec.extract_if_range(2..1, |_| true);
+}
+
 #[test]
 fn extract_if_unconsumed() {
     let mut vec = vec![1, 2, 3, 4];
@@ -1823,3 +1823,34 @@ fn check(buf: *const i32, last: *const i32, mut it: impl Iterator<Item = i32>) {
         assert_eq!(v.capacity(), 13);
     }
 }
+
+#[test]
+fn test_spare_capacity_mut() {
+    let mut v = VecDeque::with_capacity(8);
+    v.extend(0..6);
+    v.drain(..4);
+
+    // The spare capacity wraps around the end of the buffer.
+    let (a, b) = v.spare_capacity_mut();
+    assert_eq!((a.len(), b.len()), (2, 4));
+    for (i, slot) in a.iter_mut().chain(b).enumerate() {
+        slot.write(6 + i as i32);
+    }
+    unsafe { v.set_len(8) };
+    assert_eq!(v, [4, 5, 6, 7, 8, 9, 10, 11]);
+    let (a, b) = v.spare_capacity_mut();
+    assert!(a.is_empty() && b.is_empty());
+
+    // Once the contents wrap, the spare capacity is contiguous.
+    v.pop_back();
+    v.pop_back();
+    let (a, b) = v.spare_capacity_mut();
+    assert_eq!((a.len(), b.len()), (2, 0));
+    a[0].write(12);
+    unsafe { v.set_len(7) };
+    assert_eq!(v, [4, 5, 6, 7, 8, 9, 12]);
+
+    let mut v = VecDeque::<i32>::new();
+    let (a, b) = v.spare_capacity_mut();
+    assert!(a.is_empty() && b.is_empty());
+}
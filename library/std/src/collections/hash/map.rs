@@ -1199,6 +1199,88 @@ impl<K, V, S> HashMap<K, V, S>
 where
     S: BuildHasher,
 {
+    /// Returns the key-value pair of an entry with the given `hash` for which
+    /// `is_match` returns `true`.
+    ///
+    /// This looks up an entry without hashing anything, using a hash computed
+    /// beforehand with the map's hasher, and without requiring the search key
+    /// to implement [`Borrow`] for a type the key borrows as. The hash must be
+    /// the one that [`hasher`] computes for the matching key, as by
+    /// [`BuildHasher::hash_one`], or the entry may not be found.
+    ///
+    /// [`hasher`]: HashMap::hasher
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(hash_map_with_hash)]
+    /// use std::collections::HashMap;
+    /// use std::hash::BuildHasher;
+    ///
+    /// let map = HashMap::from([(String::from("a"), 1), (String::from("b"), 2)]);
+    ///
+    /// // Hash once, then look up as often as needed.
+    /// let hash = map.hasher().hash_one("b");
+    /// assert_eq!(map.find_with_hash(hash, |k| k == "b"), Some((&String::from("b"), &2)));
+    /// assert_eq!(map.find_with_hash(hash, |k| k == "c"), None);
+    /// ```
+    #[inline]
+    #[unstable(feature = "hash_map_with_hash", issue = "none")]
+    pub fn find_with_hash<F>(&self, hash: u64, is_match: F) -> Option<(&K, &V)>
+    where
+        F: FnMut(&K) -> bool,
+    {
+        self.base.raw_entry().from_hash(hash, is_match)
+    }
+
+    /// Gets the entry with the given `hash` for which `is_match` returns
+    /// `true`, for in-place manipulation.
+    ///
+    /// Like [`find_with_hash`], this uses a hash computed beforehand with the
+    /// map's hasher instead of hashing a key. A vacant entry only asks for an
+    /// owned key when something is inserted, so this also avoids creating an
+    /// owned key for entries that already exist.
+    ///
+    /// The hash must be the one that [`hasher`] computes for the matching key,
+    /// and any key inserted through the entry must also have that hash.
+    /// Otherwise the map may behave erratically, although still memory-safely:
+    /// lookups may miss entries, or find entries they shouldn't.
+    ///
+    /// [`find_with_hash`]: HashMap::find_with_hash
+    /// [`hasher`]: HashMap::hasher
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(hash_map_with_hash)]
+    /// use std::collections::hash_map::{HashEntry, HashMap};
+    /// use std::hash::BuildHasher;
+    ///
+    /// // Intern strings, only allocating for new ones.
+    /// let mut ids: HashMap<String, usize> = HashMap::new();
+    /// for word in ["apple", "banana", "apple"] {
+    ///     let hash = ids.hasher().hash_one(word);
+    ///     let next_id = ids.len();
+    ///     if let HashEntry::Vacant(entry) = ids.entry_with_hash(hash, |k| k == word) {
+    ///         entry.insert(word.to_owned(), next_id);
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(ids.len(), 2);
+    /// assert_eq!(ids["banana"], 1);
+    /// ```
+    #[inline]
+    #[unstable(feature = "hash_map_with_hash", issue = "none")]
+    pub fn entry_with_hash<F>(&mut self, hash: u64, is_match: F) -> HashEntry<'_, K, V, S>
+    where
+        F: FnMut(&K) -> bool,
+    {
+        match self.base.raw_entry_mut().from_hash(hash, is_match) {
+            base::RawEntryMut::Occupied(base) => HashEntry::Occupied(OccupiedHashEntry { base }),
+            base::RawEntryMut::Vacant(base) => HashEntry::Vacant(VacantHashEntry { base, hash }),
+        }
+    }
+
     /// Creates a raw entry builder for the HashMap.
     ///
     /// Raw entries provide the lowest level of control for searching and
@@ -2070,6 +2152,133 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     }
 }
 
+/// A view into a single entry in a map, found by its hash, which may either be
+/// vacant or occupied.
+///
+/// This `enum` is constructed from the [`entry_with_hash`] method on [`HashMap`].
+///
+/// [`entry_with_hash`]: HashMap::entry_with_hash
+#[unstable(feature = "hash_map_with_hash", issue = "none")]
+pub enum HashEntry<'a, K: 'a, V: 'a, S: 'a> {
+    /// An occupied entry.
+    Occupied(OccupiedHashEntry<'a, K, V, S>),
+    /// A vacant entry.
+    Vacant(VacantHashEntry<'a, K, V, S>),
+}
+
+/// A view into an occupied entry in a `HashMap`.
+/// It is part of the [`HashEntry`] enum.
+#[unstable(feature = "hash_map_with_hash", issue = "none")]
+pub struct OccupiedHashEntry<'a, K: 'a, V: 'a, S: 'a> {
+    base: base::RawOccupiedEntryMut<'a, K, V, S>,
+}
+
+/// A view into a vacant entry in a `HashMap`.
+/// It is part of the [`HashEntry`] enum.
+#[unstable(feature = "hash_map_with_hash", issue = "none")]
+pub struct VacantHashEntry<'a, K: 'a, V: 'a, S: 'a> {
+    base: base::RawVacantEntryMut<'a, K, V, S>,
+    hash: u64,
+}
+
+impl<'a, K, V, S> OccupiedHashEntry<'a, K, V, S> {
+    /// Gets a reference to the key in the entry.
+    #[inline]
+    #[must_use]
+    #[unstable(feature = "hash_map_with_hash", issue = "none")]
+    pub fn key(&self) -> &K {
+        self.base.key()
+    }
+
+    /// Gets a reference to the value in the entry.
+    #[inline]
+    #[must_use]
+    #[unstable(feature = "hash_map_with_hash", issue = "none")]
+    pub fn get(&self) -> &V {
+        self.base.get()
+    }
+
+    /// Gets a mutable reference to the value in the entry.
+    #[inline]
+    #[unstable(feature = "hash_map_with_hash", issue = "none")]
+    pub fn get_mut(&mut self) -> &mut V {
+        self.base.get_mut()
+    }
+
+    /// Converts the entry into a mutable reference to the value in the entry
+    /// with a lifetime bound to the map itself.
+    #[inline]
+    #[must_use = "`self` will be dropped if the result is not used"]
+    #[unstable(feature = "hash_map_with_hash", issue = "none")]
+    pub fn into_mut(self) -> &'a mut V {
+        self.base.into_mut()
+    }
+
+    /// Sets the value of the entry, and returns the entry's old value.
+    #[inline]
+    #[unstable(feature = "hash_map_with_hash", issue = "none")]
+    pub fn insert(&mut self, value: V) -> V {
+        self.base.insert(value)
+    }
+
+    /// Takes the value out of the entry, and returns it.
+    #[inline]
+    #[unstable(feature = "hash_map_with_hash", issue = "none")]
+    pub fn remove(self) -> V {
+        self.base.remove()
+    }
+
+    /// Takes the key and value out of the map, and returns them.
+    #[inline]
+    #[unstable(feature = "hash_map_with_hash", issue = "none")]
+    pub fn remove_entry(self) -> (K, V) {
+        self.base.remove_entry()
+    }
+}
+
+impl<'a, K, V, S> VacantHashEntry<'a, K, V, S> {
+    /// Sets the value of the entry with the `VacantHashEntry`'s hash and the
+    /// given key, and returns a mutable reference to the value.
+    ///
+    /// `key` must have the hash the entry was looked up with.
+    #[inline]
+    #[unstable(feature = "hash_map_with_hash", issue = "none")]
+    pub fn insert(self, key: K, value: V) -> &'a mut V
+    where
+        K: Hash,
+        S: BuildHasher,
+    {
+        self.base.insert_hashed_nocheck(self.hash, key, value).1
+    }
+}
+
+#[unstable(feature = "hash_map_with_hash", issue = "none")]
+impl<K: Debug, V: Debug, S> Debug for HashEntry<'_, K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            HashEntry::Vacant(ref v) => f.debug_tuple("HashEntry").field(v).finish(),
+            HashEntry::Occupied(ref o) => f.debug_tuple("HashEntry").field(o).finish(),
+        }
+    }
+}
+
+#[unstable(feature = "hash_map_with_hash", issue = "none")]
+impl<K: Debug, V: Debug, S> Debug for OccupiedHashEntry<'_, K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OccupiedHashEntry")
+            .field("key", self.key())
+            .field("value", self.get())
+            .finish_non_exhaustive()
+    }
+}
+
+#[unstable(feature = "hash_map_with_hash", issue = "none")]
+impl<K, V, S> Debug for VacantHashEntry<'_, K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VacantHashEntry").field("hash", &self.hash).finish_non_exhaustive()
+    }
+}
+
 /// A view into a single entry in a map, which may either be vacant or occupied.
 ///
 /// This `enum` is constructed from the [`entry`] method on [`HashMap`].
@@ -944,6 +944,46 @@ fn test_raw_entry() {
     }
 }
 
+#[test]
+fn test_find_and_entry_with_hash() {
+    use super::HashEntry::{Occupied, Vacant};
+    use crate::hash::BuildHasher;
+
+    let mut map: HashMap<String, i32> = HashMap::new();
+    map.insert("a".to_string(), 1);
+    map.insert("b".to_string(), 2);
+
+    let hash_a = map.hasher().hash_one("a");
+    let hash_c = map.hasher().hash_one("c");
+    assert_eq!(map.find_with_hash(hash_a, |k| k == "a"), Some((&"a".to_string(), &1)));
+    assert_eq!(map.find_with_hash(hash_c, |k| k == "c"), None);
+    assert_eq!(map.find_with_hash(hash_c, |k| k == "a"), None);
+
+    match map.entry_with_hash(hash_a, |k| k == "a") {
+        Occupied(mut view) => *view.get_mut() += 10,
+        Vacant(_) => unreachable!(),
+    }
+    match map.entry_with_hash(hash_c, |k| k == "c") {
+        Occupied(_) => unreachable!(),
+        Vacant(view) => {
+            *view.insert("c".to_string(), 3) += 1;
+        }
+    }
+    match map.entry_with_hash(map.hasher().hash_one("b"), |k| k == "b") {
+        Occupied(view) => assert_eq!(view.remove_entry(), ("b".to_string(), 2)),
+        Vacant(_) => unreachable!(),
+    }
+
+    // Ensure lookups by hash agree with lookups by key.
+    for k in ["a", "b", "c", "d"] {
+        let hash = map.hasher().hash_one(k);
+        assert_eq!(map.find_with_hash(hash, |q| q == k), map.get_key_value(k));
+    }
+    assert_eq!(map["a"], 11);
+    assert_eq!(map["c"], 4);
+    assert_eq!(map.len(), 2);
+}
+
 mod test_extract_if {
     use super::*;
 
@@ -0,0 +1,826 @@
+#[cfg(test)]
+mod tests;
+
+use hashbrown::hash_table as base;
+
+use crate::collections::TryReserveError;
+use crate::fmt::{self, Debug};
+use crate::iter::FusedIterator;
+
+use super::map::map_try_reserve_error;
+
+/// A low-level hash table with explicit hashing, storing values without
+/// separate keys.
+///
+/// Unlike [`HashMap`] and [`HashSet`], a `HashTable` neither stores a hasher
+/// nor requires its elements to implement [`Hash`] or [`Eq`]. Instead, every
+/// method that searches the table takes the hash of the element it is looking
+/// for and a closure deciding whether a stored element is the one, and every
+/// method that may grow the table takes a closure computing the hash of a
+/// stored element.
+///
+/// This makes it possible to:
+///
+/// * look up elements using a hash that was computed once and stored,
+/// * compare elements using state the elements don't contain, such as an
+///   index into a separate arena of strings, and
+/// * use part of an element as its key without storing the key twice.
+///
+/// It is a logic error for the hash passed alongside an element to differ from
+/// the hash that the `hasher` closure computes for that element, or for the
+/// `eq` closure to match elements with different hashes. The behavior
+/// resulting from such a logic error is not specified, but will be encapsulated
+/// to the `HashTable` that observed the logic error and not result in undefined
+/// behavior. This could include panics, incorrect results, aborts, memory
+/// leaks, and non-termination.
+///
+/// The table does not prevent inserting several elements that compare equal.
+/// Lookups then return an arbitrary one of them.
+///
+/// [`HashMap`]: crate::collections::HashMap
+/// [`HashSet`]: crate::collections::HashSet
+/// [`Hash`]: crate::hash::Hash
+///
+/// # Examples
+///
+/// ```
+/// #![feature(hash_table)]
+/// use std::collections::HashTable;
+/// use std::hash::{BuildHasher, RandomState};
+///
+/// // Intern strings, looking them up by `&str` without allocating.
+/// let state = RandomState::new();
+/// let mut table: HashTable<String> = HashTable::new();
+///
+/// for word in ["apple", "banana", "apple"] {
+///     let hash = state.hash_one(word);
+///     table
+///         .entry(hash, |s| s == word, |s| state.hash_one(s))
+///         .or_insert_with(|| word.to_owned());
+/// }
+///
+/// assert_eq!(table.len(), 2);
+/// let hash = state.hash_one("banana");
+/// assert_eq!(table.find(hash, |s| s == "banana").map(String::as_str), Some("banana"));
+/// ```
+#[unstable(feature = "hash_table", issue = "none")]
+#[repr(transparent)]
+pub struct HashTable<T> {
+    base: base::HashTable<T>,
+}
+
+impl<T> HashTable<T> {
+    /// Creates an empty `HashTable`.
+    ///
+    /// The table is initially created with a capacity of 0, so it will not
+    /// allocate until it is first inserted into.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(hash_table)]
+    /// use std::collections::HashTable;
+    ///
+    /// let table: HashTable<i32> = HashTable::new();
+    /// assert_eq!(table.capacity(), 0);
+    /// ```
+    #[inline]
+    #[must_use]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn new() -> HashTable<T> {
+        HashTable { base: base::HashTable::new() }
+    }
+
+    /// Creates an empty `HashTable` with at least the specified capacity.
+    ///
+    /// The table will be able to hold at least `capacity` elements without
+    /// reallocating. If `capacity` is 0, the table will not allocate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(hash_table)]
+    /// use std::collections::HashTable;
+    ///
+    /// let table: HashTable<i32> = HashTable::with_capacity(10);
+    /// assert!(table.capacity() >= 10);
+    /// ```
+    #[inline]
+    #[must_use]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn with_capacity(capacity: usize) -> HashTable<T> {
+        HashTable { base: base::HashTable::with_capacity(capacity) }
+    }
+
+    /// Returns a reference to an element with the given `hash` for which `eq`
+    /// returns `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(hash_table)]
+    /// use std::collections::HashTable;
+    /// use std::hash::{BuildHasher, RandomState};
+    ///
+    /// let state = RandomState::new();
+    /// let mut table = HashTable::new();
+    /// table.insert_unique(state.hash_one(1), 1, |v| state.hash_one(v));
+    ///
+    /// assert_eq!(table.find(state.hash_one(1), |&v| v == 1), Some(&1));
+    /// assert_eq!(table.find(state.hash_one(2), |&v| v == 2), None);
+    /// ```
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn find(&self, hash: u64, eq: impl FnMut(&T) -> bool) -> Option<&T> {
+        self.base.find(hash, eq)
+    }
+
+    /// Returns a mutable reference to an element with the given `hash` for
+    /// which `eq` returns `true`.
+    ///
+    /// Mutating the element in a way that changes its hash or how `eq`
+    /// closures treat it is a logic error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(hash_table)]
+    /// use std::collections::HashTable;
+    /// use std::hash::{BuildHasher, RandomState};
+    ///
+    /// let state = RandomState::new();
+    /// let mut table = HashTable::new();
+    /// table.insert_unique(state.hash_one("a"), ("a", 1), |(k, _)| state.hash_one(k));
+    ///
+    /// if let Some((_, v)) = table.find_mut(state.hash_one("a"), |&(k, _)| k == "a") {
+    ///     *v += 1;
+    /// }
+    /// assert_eq!(table.find(state.hash_one("a"), |&(k, _)| k == "a"), Some(&("a", 2)));
+    /// ```
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn find_mut(&mut self, hash: u64, eq: impl FnMut(&T) -> bool) -> Option<&mut T> {
+        self.base.find_mut(hash, eq)
+    }
+
+    /// Returns an `OccupiedEntry` for an element with the given `hash` for
+    /// which `eq` returns `true`, or an `AbsentEntry` if there is none.
+    ///
+    /// Unlike [`entry`], this never needs to grow the table, so it doesn't take
+    /// a `hasher`.
+    ///
+    /// [`entry`]: HashTable::entry
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(hash_table)]
+    /// use std::collections::HashTable;
+    /// use std::hash::{BuildHasher, RandomState};
+    ///
+    /// let state = RandomState::new();
+    /// let mut table = HashTable::new();
+    /// table.insert_unique(state.hash_one(1), 1, |v| state.hash_one(v));
+    ///
+    /// if let Ok(entry) = table.find_entry(state.hash_one(1), |&v| v == 1) {
+    ///     entry.remove();
+    /// }
+    /// assert!(table.is_empty());
+    /// assert!(table.find_entry(state.hash_one(1), |&v| v == 1).is_err());
+    /// ```
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn find_entry(
+        &mut self,
+        hash: u64,
+        eq: impl FnMut(&T) -> bool,
+    ) -> Result<OccupiedEntry<'_, T>, AbsentEntry<'_, T>> {
+        match self.base.find_entry(hash, eq) {
+            Ok(base) => Ok(OccupiedEntry { base }),
+            Err(base) => Err(AbsentEntry { base }),
+        }
+    }
+
+    /// Returns the entry for an element with the given `hash` for which `eq`
+    /// returns `true`, for in-place manipulation.
+    ///
+    /// If the entry is vacant, room for one more element is reserved, which
+    /// may require rehashing all elements with `hasher`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(hash_table)]
+    /// use std::collections::HashTable;
+    /// use std::hash::{BuildHasher, RandomState};
+    ///
+    /// // Count letters, storing each letter next to its count.
+    /// let state = RandomState::new();
+    /// let mut table = HashTable::new();
+    /// for c in "abracadabra".chars() {
+    ///     table
+    ///         .entry(state.hash_one(c), |&(k, _)| k == c, |(k, _)| state.hash_one(k))
+    ///         .and_modify(|(_, n)| *n += 1)
+    ///         .or_insert((c, 1));
+    /// }
+    ///
+    /// assert_eq!(table.find(state.hash_one('a'), |&(k, _)| k == 'a'), Some(&('a', 5)));
+    /// ```
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn entry(
+        &mut self,
+        hash: u64,
+        eq: impl FnMut(&T) -> bool,
+        hasher: impl Fn(&T) -> u64,
+    ) -> Entry<'_, T> {
+        match self.base.entry(hash, eq, hasher) {
+            base::Entry::Occupied(base) => Entry::Occupied(OccupiedEntry { base }),
+            base::Entry::Vacant(base) => Entry::Vacant(VacantEntry { base }),
+        }
+    }
+
+    /// Inserts an element with the given `hash` without checking whether an
+    /// equal element is already present, and returns its entry.
+    ///
+    /// Growing the table may require rehashing all elements with `hasher`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(hash_table)]
+    /// use std::collections::HashTable;
+    /// use std::hash::{BuildHasher, RandomState};
+    ///
+    /// let state = RandomState::new();
+    /// let mut table = HashTable::new();
+    /// let entry = table.insert_unique(state.hash_one(1), 1, |v| state.hash_one(v));
+    /// assert_eq!(entry.get(), &1);
+    /// ```
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn insert_unique(
+        &mut self,
+        hash: u64,
+        value: T,
+        hasher: impl Fn(&T) -> u64,
+    ) -> OccupiedEntry<'_, T> {
+        OccupiedEntry { base: self.base.insert_unique(hash, value, hasher) }
+    }
+
+    /// Clears the table, removing all elements. Keeps the allocated memory for
+    /// reuse.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(hash_table)]
+    /// use std::collections::HashTable;
+    /// use std::hash::{BuildHasher, RandomState};
+    ///
+    /// let state = RandomState::new();
+    /// let mut table = HashTable::new();
+    /// table.insert_unique(state.hash_one(1), 1, |v| state.hash_one(v));
+    /// table.clear();
+    /// assert!(table.is_empty());
+    /// ```
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn clear(&mut self) {
+        self.base.clear();
+    }
+
+    /// Shrinks the capacity of the table as much as possible, rehashing all
+    /// elements with `hasher`.
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn shrink_to_fit(&mut self, hasher: impl Fn(&T) -> u64) {
+        self.base.shrink_to_fit(hasher);
+    }
+
+    /// Shrinks the capacity of the table with a lower limit, rehashing all
+    /// elements with `hasher`.
+    ///
+    /// The capacity will remain at least as large as both the length and the
+    /// supplied value. If the current capacity is less than the lower limit,
+    /// this is a no-op.
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn shrink_to(&mut self, min_capacity: usize, hasher: impl Fn(&T) -> u64) {
+        self.base.shrink_to(min_capacity, hasher);
+    }
+
+    /// Reserves capacity for at least `additional` more elements, rehashing
+    /// all elements with `hasher` if the table grows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new allocation size overflows [`usize`].
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn reserve(&mut self, additional: usize, hasher: impl Fn(&T) -> u64) {
+        self.base.reserve(additional, hasher)
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements,
+    /// rehashing all elements with `hasher` if the table grows.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an
+    /// error is returned.
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn try_reserve(
+        &mut self,
+        additional: usize,
+        hasher: impl Fn(&T) -> u64,
+    ) -> Result<(), TryReserveError> {
+        self.base.try_reserve(additional, hasher).map_err(map_try_reserve_error)
+    }
+
+    /// Returns the number of elements the table can hold without reallocating.
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn capacity(&self) -> usize {
+        self.base.capacity()
+    }
+
+    /// Returns the number of elements in the table.
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn len(&self) -> usize {
+        self.base.len()
+    }
+
+    /// Returns `true` if the table contains no elements.
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn is_empty(&self) -> bool {
+        self.base.is_empty()
+    }
+
+    /// An iterator visiting all elements in arbitrary order.
+    /// The iterator element type is `&'a T`.
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { base: self.base.iter() }
+    }
+
+    /// An iterator visiting all elements in arbitrary order, with mutable
+    /// references to the elements.
+    /// The iterator element type is `&'a mut T`.
+    ///
+    /// Mutating an element in a way that changes its hash or how `eq`
+    /// closures treat it is a logic error.
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut { base: self.base.iter_mut() }
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, removes all elements `e` for which `f(&mut e)` returns
+    /// `false`. The elements are visited in unsorted (and unspecified) order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(hash_table)]
+    /// use std::collections::HashTable;
+    /// use std::hash::{BuildHasher, RandomState};
+    ///
+    /// let state = RandomState::new();
+    /// let mut table = HashTable::new();
+    /// for x in 0..8 {
+    ///     table.insert_unique(state.hash_one(x), x, |v| state.hash_one(v));
+    /// }
+    /// table.retain(|&mut x| x % 2 == 0);
+    /// assert_eq!(table.len(), 4);
+    /// ```
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn retain(&mut self, f: impl FnMut(&mut T) -> bool) {
+        self.base.retain(f)
+    }
+
+    /// Clears the table, returning all elements as an iterator. Keeps the
+    /// allocated memory for reuse.
+    ///
+    /// If the returned iterator is dropped before being fully consumed, it
+    /// drops the remaining elements.
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        Drain { base: self.base.drain() }
+    }
+
+    fn from_base_mut(base: &mut base::HashTable<T>) -> &mut HashTable<T> {
+        // SAFETY: `HashTable` is a `repr(transparent)` wrapper around `base::HashTable`.
+        unsafe { &mut *(base as *mut base::HashTable<T>).cast::<HashTable<T>>() }
+    }
+}
+
+#[unstable(feature = "hash_table", issue = "none")]
+impl<T: Clone> Clone for HashTable<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self { base: self.base.clone() }
+    }
+
+    #[inline]
+    fn clone_from(&mut self, source: &Self) {
+        self.base.clone_from(&source.base);
+    }
+}
+
+#[unstable(feature = "hash_table", issue = "none")]
+impl<T> Default for HashTable<T> {
+    /// Creates an empty `HashTable`.
+    #[inline]
+    fn default() -> HashTable<T> {
+        HashTable::new()
+    }
+}
+
+#[unstable(feature = "hash_table", issue = "none")]
+impl<T: Debug> Debug for HashTable<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// A view into a single entry in a [`HashTable`], which may either be vacant
+/// or occupied.
+///
+/// This `enum` is constructed from the [`entry`] method on [`HashTable`].
+///
+/// [`entry`]: HashTable::entry
+#[unstable(feature = "hash_table", issue = "none")]
+pub enum Entry<'a, T: 'a> {
+    /// An occupied entry.
+    #[unstable(feature = "hash_table", issue = "none")]
+    Occupied(OccupiedEntry<'a, T>),
+
+    /// A vacant entry.
+    #[unstable(feature = "hash_table", issue = "none")]
+    Vacant(VacantEntry<'a, T>),
+}
+
+#[unstable(feature = "hash_table", issue = "none")]
+impl<T: Debug> Debug for Entry<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Entry::Vacant(ref v) => f.debug_tuple("Entry").field(v).finish(),
+            Entry::Occupied(ref o) => f.debug_tuple("Entry").field(o).finish(),
+        }
+    }
+}
+
+/// A view into an occupied entry in a [`HashTable`].
+/// It is part of the [`Entry`] enum, and is returned by
+/// [`HashTable::find_entry`] and [`HashTable::insert_unique`].
+#[unstable(feature = "hash_table", issue = "none")]
+pub struct OccupiedEntry<'a, T: 'a> {
+    base: base::OccupiedEntry<'a, T>,
+}
+
+#[unstable(feature = "hash_table", issue = "none")]
+impl<T: Debug> Debug for OccupiedEntry<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OccupiedEntry").field("value", self.get()).finish_non_exhaustive()
+    }
+}
+
+/// A view into a vacant entry in a [`HashTable`].
+/// It is part of the [`Entry`] enum.
+#[unstable(feature = "hash_table", issue = "none")]
+pub struct VacantEntry<'a, T: 'a> {
+    base: base::VacantEntry<'a, T>,
+}
+
+#[unstable(feature = "hash_table", issue = "none")]
+impl<T> Debug for VacantEntry<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VacantEntry").finish_non_exhaustive()
+    }
+}
+
+/// The result of a failed [`HashTable::find_entry`].
+///
+/// It holds on to the borrow of the table, which can be recovered with
+/// [`into_table`](AbsentEntry::into_table).
+#[unstable(feature = "hash_table", issue = "none")]
+pub struct AbsentEntry<'a, T: 'a> {
+    base: base::AbsentEntry<'a, T>,
+}
+
+#[unstable(feature = "hash_table", issue = "none")]
+impl<T> Debug for AbsentEntry<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AbsentEntry").finish_non_exhaustive()
+    }
+}
+
+impl<'a, T> Entry<'a, T> {
+    /// Sets the value of the entry, replacing any existing value, and returns
+    /// an `OccupiedEntry`.
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn insert(self, value: T) -> OccupiedEntry<'a, T> {
+        match self {
+            Entry::Occupied(mut entry) => {
+                *entry.get_mut() = value;
+                entry
+            }
+            Entry::Vacant(entry) => entry.insert(value),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting `default` if empty, and
+    /// returns an `OccupiedEntry`.
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn or_insert(self, default: T) -> OccupiedEntry<'a, T> {
+        match self {
+            Entry::Occupied(entry) => entry,
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of `default` if
+    /// empty, and returns an `OccupiedEntry`.
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn or_insert_with(self, default: impl FnOnce() -> T) -> OccupiedEntry<'a, T> {
+        match self {
+            Entry::Occupied(entry) => entry,
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Provides in-place mutable access to an occupied entry before any
+    /// potential inserts into the table.
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn and_modify(self, f: impl FnOnce(&mut T)) -> Self {
+        match self {
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+}
+
+impl<'a, T> OccupiedEntry<'a, T> {
+    /// Takes the value out of the entry, and returns it along with a
+    /// `VacantEntry` for the same hash.
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn remove(self) -> (T, VacantEntry<'a, T>) {
+        let (value, base) = self.base.remove();
+        (value, VacantEntry { base })
+    }
+
+    /// Gets a reference to the value in the entry.
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn get(&self) -> &T {
+        self.base.get()
+    }
+
+    /// Gets a mutable reference to the value in the entry.
+    ///
+    /// If you need a reference to the `OccupiedEntry` which may outlive the
+    /// destruction of the `Entry` value, see [`into_mut`].
+    ///
+    /// [`into_mut`]: Self::into_mut
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.base.get_mut()
+    }
+
+    /// Converts the `OccupiedEntry` into a mutable reference to the value in
+    /// the entry with a lifetime bound to the table itself.
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn into_mut(self) -> &'a mut T {
+        self.base.into_mut()
+    }
+
+    /// Converts the `OccupiedEntry` into a mutable reference to the
+    /// underlying table.
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn into_table(self) -> &'a mut HashTable<T> {
+        HashTable::from_base_mut(self.base.into_table())
+    }
+}
+
+impl<'a, T> VacantEntry<'a, T> {
+    /// Inserts a value with the entry's hash, and returns an `OccupiedEntry`.
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn insert(self, value: T) -> OccupiedEntry<'a, T> {
+        OccupiedEntry { base: self.base.insert(value) }
+    }
+
+    /// Converts the `VacantEntry` into a mutable reference to the underlying
+    /// table.
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn into_table(self) -> &'a mut HashTable<T> {
+        HashTable::from_base_mut(self.base.into_table())
+    }
+}
+
+impl<'a, T> AbsentEntry<'a, T> {
+    /// Converts the `AbsentEntry` into a mutable reference to the underlying
+    /// table.
+    #[inline]
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub fn into_table(self) -> &'a mut HashTable<T> {
+        HashTable::from_base_mut(self.base.into_table())
+    }
+}
+
+/// An iterator over the elements of a `HashTable`.
+///
+/// This `struct` is created by the [`iter`] method on [`HashTable`].
+///
+/// [`iter`]: HashTable::iter
+#[unstable(feature = "hash_table", issue = "none")]
+pub struct Iter<'a, T: 'a> {
+    base: base::Iter<'a, T>,
+}
+
+/// A mutable iterator over the elements of a `HashTable`.
+///
+/// This `struct` is created by the [`iter_mut`] method on [`HashTable`].
+///
+/// [`iter_mut`]: HashTable::iter_mut
+#[unstable(feature = "hash_table", issue = "none")]
+pub struct IterMut<'a, T: 'a> {
+    base: base::IterMut<'a, T>,
+}
+
+/// An owning iterator over the elements of a `HashTable`.
+///
+/// This `struct` is created by the [`into_iter`] method on [`HashTable`]
+/// (provided by the [`IntoIterator`] trait).
+///
+/// [`into_iter`]: IntoIterator::into_iter
+#[unstable(feature = "hash_table", issue = "none")]
+pub struct IntoIter<T> {
+    base: base::IntoIter<T>,
+}
+
+/// A draining iterator over the elements of a `HashTable`.
+///
+/// This `struct` is created by the [`drain`] method on [`HashTable`].
+///
+/// [`drain`]: HashTable::drain
+#[unstable(feature = "hash_table", issue = "none")]
+pub struct Drain<'a, T: 'a> {
+    base: base::Drain<'a, T>,
+}
+
+#[unstable(feature = "hash_table", issue = "none")]
+impl<'a, T> IntoIterator for &'a HashTable<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+#[unstable(feature = "hash_table", issue = "none")]
+impl<'a, T> IntoIterator for &'a mut HashTable<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+#[unstable(feature = "hash_table", issue = "none")]
+impl<T> IntoIterator for HashTable<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    /// Creates a consuming iterator, that is, one that moves each element out
+    /// of the table in arbitrary order. The table cannot be used after calling
+    /// this.
+    #[inline]
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { base: self.base.into_iter() }
+    }
+}
+
+#[unstable(feature = "hash_table", issue = "none")]
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        self.base.next()
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.base.size_hint()
+    }
+}
+#[unstable(feature = "hash_table", issue = "none")]
+impl<T> ExactSizeIterator for Iter<'_, T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.base.len()
+    }
+}
+#[unstable(feature = "hash_table", issue = "none")]
+impl<T> FusedIterator for Iter<'_, T> {}
+
+#[unstable(feature = "hash_table", issue = "none")]
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut T> {
+        self.base.next()
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.base.size_hint()
+    }
+}
+#[unstable(feature = "hash_table", issue = "none")]
+impl<T> ExactSizeIterator for IterMut<'_, T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.base.len()
+    }
+}
+#[unstable(feature = "hash_table", issue = "none")]
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+#[unstable(feature = "hash_table", issue = "none")]
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.base.next()
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.base.size_hint()
+    }
+}
+#[unstable(feature = "hash_table", issue = "none")]
+impl<T> ExactSizeIterator for IntoIter<T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.base.len()
+    }
+}
+#[unstable(feature = "hash_table", issue = "none")]
+impl<T> FusedIterator for IntoIter<T> {}
+
+#[unstable(feature = "hash_table", issue = "none")]
+impl<T> Iterator for Drain<'_, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.base.next()
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.base.size_hint()
+    }
+}
+#[unstable(feature = "hash_table", issue = "none")]
+impl<T> ExactSizeIterator for Drain<'_, T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.base.len()
+    }
+}
+#[unstable(feature = "hash_table", issue = "none")]
+impl<T> FusedIterator for Drain<'_, T> {}
+
+#[unstable(feature = "hash_table", issue = "none")]
+impl<T: Debug> Debug for Drain<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.base.fmt(f)
+    }
+}
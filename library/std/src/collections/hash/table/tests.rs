@@ -0,0 +1,105 @@
+use super::{Entry, HashTable};
+use crate::hash::{BuildHasher, RandomState};
+
+fn insert_all(state: &RandomState, table: &mut HashTable<(u32, u32)>, pairs: &[(u32, u32)]) {
+    for &(k, v) in pairs {
+        table.insert_unique(state.hash_one(k), (k, v), |&(k, _)| state.hash_one(k));
+    }
+}
+
+#[test]
+fn test_find() {
+    let state = RandomState::new();
+    let mut table = HashTable::new();
+    insert_all(&state, &mut table, &[(1, 10), (2, 20), (3, 30)]);
+
+    assert_eq!(table.len(), 3);
+    assert_eq!(table.find(state.hash_one(2), |&(k, _)| k == 2), Some(&(2, 20)));
+    assert_eq!(table.find(state.hash_one(4), |&(k, _)| k == 4), None);
+
+    // A matching element with the wrong hash is not found.
+    assert_eq!(table.find(state.hash_one(4), |&(k, _)| k == 2), None);
+
+    table.find_mut(state.hash_one(3), |&(k, _)| k == 3).unwrap().1 += 3;
+    assert_eq!(table.find(state.hash_one(3), |&(k, _)| k == 3), Some(&(3, 33)));
+}
+
+#[test]
+fn test_entry() {
+    let state = RandomState::new();
+    let hasher = |&(k, _): &(u32, u32)| state.hash_one(k);
+    let mut table = HashTable::new();
+
+    for k in (0..100).chain(0..50) {
+        table
+            .entry(state.hash_one(k), |&(x, _)| x == k, hasher)
+            .and_modify(|e| e.1 += 1)
+            .or_insert((k, 0));
+    }
+    assert_eq!(table.len(), 100);
+    assert!(table.iter().all(|&(k, n)| n == (k < 50) as u32));
+
+    match table.entry(state.hash_one(7), |&(x, _)| x == 7, hasher) {
+        Entry::Occupied(entry) => {
+            let (removed, vacant) = entry.remove();
+            assert_eq!(removed, (7, 1));
+            assert_eq!(vacant.insert((7, 70)).get(), &(7, 70));
+        }
+        Entry::Vacant(_) => unreachable!(),
+    }
+    assert_eq!(table.len(), 100);
+
+    let entry = table.entry(state.hash_one(100), |&(x, _)| x == 100, hasher).insert((100, 0));
+    assert_eq!(entry.into_table().len(), 101);
+}
+
+#[test]
+fn test_find_entry() {
+    let state = RandomState::new();
+    let mut table = HashTable::new();
+    insert_all(&state, &mut table, &[(1, 10), (2, 20)]);
+
+    let entry = table.find_entry(state.hash_one(1), |&(k, _)| k == 1).unwrap();
+    assert_eq!(entry.remove().0, (1, 10));
+    let absent = table.find_entry(state.hash_one(1), |&(k, _)| k == 1).unwrap_err();
+    assert_eq!(absent.into_table().len(), 1);
+}
+
+#[test]
+fn test_iter_retain_drain() {
+    let state = RandomState::new();
+    let mut table = HashTable::new();
+    insert_all(&state, &mut table, &Vec::from_iter((0..20).map(|k| (k, k))));
+
+    assert_eq!(table.iter().len(), 20);
+    for (_, v) in &mut table {
+        *v *= 2;
+    }
+    table.retain(|&mut (k, _)| k % 2 == 0);
+    assert_eq!(table.len(), 10);
+
+    let capacity = table.capacity();
+    let mut drained = Vec::from_iter(table.drain());
+    drained.sort();
+    assert_eq!(drained, Vec::from_iter((0..20).step_by(2).map(|k| (k, k * 2))));
+    assert!(table.is_empty());
+    assert_eq!(table.capacity(), capacity);
+
+    insert_all(&state, &mut table, &[(5, 5)]);
+    assert_eq!(Vec::from_iter(table.clone()), [(5, 5)]);
+    assert_eq!(format!("{table:?}"), "{(5, 5)}");
+}
+
+#[test]
+fn test_reserve_shrink() {
+    let state = RandomState::new();
+    let hasher = |&(k, _): &(u32, u32)| state.hash_one(k);
+    let mut table = HashTable::new();
+    table.reserve(100, hasher);
+    assert!(table.capacity() >= 100);
+    insert_all(&state, &mut table, &[(1, 1)]);
+    table.shrink_to_fit(hasher);
+    assert!(table.capacity() < 100);
+    assert_eq!(table.find(state.hash_one(1), |&(k, _)| k == 1), Some(&(1, 1)));
+    assert!(table.try_reserve(usize::MAX, hasher).is_err());
+}
@@ -422,6 +422,9 @@
 #[stable(feature = "rust1", since = "1.0.0")]
 #[doc(inline)]
 pub use self::hash_set::HashSet;
+#[unstable(feature = "hash_table", issue = "none")]
+#[doc(inline)]
+pub use self::hash_table::HashTable;
 
 #[stable(feature = "try_reserve", since = "1.57.0")]
 pub use alloc_crate::collections::TryReserveError;
@@ -452,3 +455,10 @@ pub mod hash_set {
     #[stable(feature = "rust1", since = "1.0.0")]
     pub use super::hash::set::*;
 }
+
+#[unstable(feature = "hash_table", issue = "none")]
+pub mod hash_table {
+    //! A low-level hash table with explicit hashing, storing values without separate keys.
+    #[unstable(feature = "hash_table", issue = "none")]
+    pub use super::hash::table::*;
+}
@@ -11,6 +11,8 @@
 #[cfg(all(test, not(any(target_os = "emscripten", target_env = "sgx", target_os = "xous"))))]
 mod tests;
 
+mod walk;
+
 use crate::ffi::OsString;
 use crate::fmt;
 use crate::io::{self, BorrowedCursor, IoSlice, IoSliceMut, Read, Seek, SeekFrom, Write};
@@ -21,6 +23,9 @@
 use crate::sys_common::{AsInner, AsInnerMut, FromInner, IntoInner};
 use crate::time::SystemTime;
 
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+pub use self::walk::{walk_dir, WalkDir, WalkEntry};
+
 /// An object providing access to an open file on the filesystem.
 ///
 /// An instance of a `File` can be read and/or written depending on what options
@@ -193,6 +198,60 @@ pub struct File {
 #[cfg_attr(not(test), rustc_diagnostic_item = "FsOpenOptions")]
 pub struct OpenOptions(fs_imp::OpenOptions);
 
+/// Options and flags which can be used to configure how a file is copied.
+///
+/// [`fs::copy`](copy) copies the contents and permissions of a file in the
+/// way the platform finds best. `CopyOptions` gives control over that: whether
+/// the copy may or must share storage with the original, whether holes in a
+/// sparse file are preserved, which attributes are copied along, and whether
+/// symbolic links are copied as links.
+///
+/// # Examples
+///
+/// Cloning a large disk image, without taking up any more space on disk:
+///
+/// ```no_run
+/// #![feature(fs_copy_options)]
+/// use std::fs::{CopyOptions, Reflink};
+///
+/// CopyOptions::new().reflink(Reflink::Always).copy("disk.img", "snapshot.img")?;
+/// # std::io::Result::Ok(())
+/// ```
+///
+/// Copying a sparse file, even to another filesystem, with its timestamps:
+///
+/// ```no_run
+/// #![feature(fs_copy_options)]
+/// use std::fs::CopyOptions;
+///
+/// CopyOptions::new().sparse(true).timestamps(true).copy("disk.img", "/mnt/backup/disk.img")?;
+/// # std::io::Result::Ok(())
+/// ```
+#[derive(Clone, Debug)]
+#[unstable(feature = "fs_copy_options", issue = "none")]
+pub struct CopyOptions(fs_imp::CopyOptions);
+
+/// Whether a copy made with [`CopyOptions`] uses a copy-on-write clone.
+///
+/// A clone, also known as a reflink, shares its storage with the original file
+/// until either of them is modified, which makes it very fast and initially
+/// free of cost in disk space. Only some filesystems support clones, and only
+/// within the same filesystem.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[unstable(feature = "fs_copy_options", issue = "none")]
+pub enum Reflink {
+    /// Always copy the data, never sharing storage with the original.
+    #[unstable(feature = "fs_copy_options", issue = "none")]
+    Never,
+    /// Clone the file when possible, and copy the data otherwise.
+    #[default]
+    #[unstable(feature = "fs_copy_options", issue = "none")]
+    Auto,
+    /// Clone the file, failing if that isn't possible.
+    #[unstable(feature = "fs_copy_options", issue = "none")]
+    Always,
+}
+
 /// Representation of the various timestamps on a file.
 #[derive(Copy, Clone, Debug, Default)]
 #[stable(feature = "file_set_times", since = "1.75.0")]
@@ -229,6 +288,50 @@ pub struct DirBuilder {
     recursive: bool,
 }
 
+/// An open directory, relative to which files can be opened, created, removed
+/// and renamed.
+///
+/// Operating on paths relative to a `Dir` rather than on full paths means that
+/// a directory being renamed, or replaced by a symbolic link, while a program
+/// works inside it can't redirect those operations somewhere else. This makes
+/// it possible to walk a tree that other processes may be modifying, such as a
+/// directory writable by untrusted users, without time-of-check to
+/// time-of-use (TOCTOU) races.
+///
+/// The paths given to the methods of `Dir` are resolved relative to the
+/// directory. Absolute paths ignore the directory altogether, and paths with
+/// several components follow symbolic links in all but their last component,
+/// so code that needs to stay inside the directory should open one component
+/// at a time with [`Dir::open_dir_at`].
+///
+/// # Platform-specific behavior
+///
+/// This type is implemented with the `openat` family of functions on most Unix
+/// platforms. On other platforms [`Dir::open`] returns an error of kind
+/// [`io::ErrorKind::Unsupported`].
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(dirfd)]
+/// use std::fs::{Dir, OpenOptions};
+/// use std::io::Write;
+///
+/// let dir = Dir::open("/tmp/untrusted")?;
+/// dir.create_dir_at("logs")?;
+/// let logs = dir.open_dir_at("logs")?;
+/// let mut file = logs.open_file_at("today.txt", OpenOptions::new().write(true).create(true))?;
+/// file.write_all(b"hello")?;
+/// logs.remove_at("today.txt")?;
+/// dir.remove_at("logs")?;
+/// # std::io::Result::Ok(())
+/// ```
+#[unstable(feature = "dirfd", issue = "none")]
+#[derive(Debug)]
+pub struct Dir {
+    inner: fs_imp::DirHandle,
+}
+
 /// Read the entire contents of a file into a bytes vector.
 ///
 /// This is a convenience function for using [`File::open`] and [`read_to_end`]
@@ -343,6 +446,54 @@ fn inner(path: &Path, contents: &[u8]) -> io::Result<()> {
     inner(path.as_ref(), contents.as_ref())
 }
 
+/// Atomically replaces the entire contents of a file with a slice.
+///
+/// Unlike [`write`], this never leaves the file truncated or half-written:
+/// after a crash or power loss, `path` holds either its old contents or all
+/// of the new ones. The contents are written to a temporary file in the same
+/// directory, which is flushed to disk with [`File::sync_all`] and then
+/// renamed over `path`, and the directory is finally flushed too so that the
+/// rename itself is durable.
+///
+/// The file is replaced rather than modified, so it loses the permissions,
+/// owner and hard links of the file it replaces and gets those of a file
+/// newly created by [`File::create`]. If `path` is a symbolic link, the link
+/// itself is replaced.
+///
+/// # Platform-specific behavior
+///
+/// On Linux, the temporary file is created with `O_TMPFILE` when the kernel
+/// and filesystem support it, so it has no name until it is complete, and is
+/// then linked into the directory with `linkat`. Elsewhere, and as a fallback,
+/// it is a hidden file next to `path` that is removed again if writing fails.
+/// The directory is not flushed on Windows, where `rename` is already durable.
+///
+/// Note that this [may change in the future][changes].
+///
+/// [changes]: io#platform-specific-behavior
+///
+/// # Errors
+///
+/// This function will return an error if `path` has no file name or its
+/// directory doesn't exist or isn't writable, and in all the cases where
+/// writing, flushing or renaming fails.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_write_atomic)]
+/// use std::fs;
+///
+/// fn main() -> std::io::Result<()> {
+///     fs::write_atomic("config.toml", "verbose = true\n")?;
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "fs_write_atomic", issue = "none")]
+pub fn write_atomic<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> io::Result<()> {
+    fs_imp::write_atomic(path.as_ref(), contents.as_ref())
+}
+
 impl File {
     /// Attempts to open a file in read-only mode.
     ///
@@ -724,6 +875,142 @@ pub fn set_times(&self, times: FileTimes) -> io::Result<()> {
     pub fn set_modified(&self, time: SystemTime) -> io::Result<()> {
         self.set_times(FileTimes::new().set_modified(time))
     }
+
+    /// Acquires an exclusive lock on the file, blocking until it can be
+    /// acquired.
+    ///
+    /// While a file is locked exclusively, no other file handle can lock it,
+    /// neither exclusively nor shared. The lock is released by [`unlock`],
+    /// or when this file handle and all handles duplicated from it with
+    /// [`try_clone`] are closed.
+    ///
+    /// This lock is advisory on most platforms: it only keeps out other
+    /// processes that also lock the file, not reads and writes. Locking a
+    /// file that this handle already holds a lock on may either succeed or
+    /// deadlock, depending on the platform, so don't rely on it.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix
+    /// with `LOCK_EX`, and to `LockFileEx` on Windows with
+    /// `LOCKFILE_EXCLUSIVE_LOCK` on the whole file, where the lock is
+    /// mandatory. On Unix platforms without `flock` and on other platforms,
+    /// an error of kind [`io::ErrorKind::Unsupported`] is returned.
+    ///
+    /// Note that this [may change in the future][changes].
+    ///
+    /// [changes]: io#platform-specific-behavior
+    /// [`unlock`]: File::unlock
+    /// [`try_clone`]: File::try_clone
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let f = File::create("foo.lock")?;
+    ///     f.lock()?;
+    ///     // Only one process at a time gets here.
+    ///     f.unlock()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "none")]
+    #[doc(alias = "flock", alias = "LockFileEx")]
+    pub fn lock(&self) -> io::Result<()> {
+        self.inner.lock()
+    }
+
+    /// Acquires a shared lock on the file, blocking until it can be acquired.
+    ///
+    /// Any number of file handles can hold a shared lock on a file at the
+    /// same time, but not while another one holds an exclusive lock. See
+    /// [`lock`](File::lock) for how locks are released, and for the
+    /// limitations of locking.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix
+    /// with `LOCK_SH`, and to `LockFileEx` on Windows on the whole file.
+    ///
+    /// Note that this [may change in the future][changes].
+    ///
+    /// [changes]: io#platform-specific-behavior
+    #[unstable(feature = "file_lock", issue = "none")]
+    pub fn lock_shared(&self) -> io::Result<()> {
+        self.inner.lock_shared()
+    }
+
+    /// Tries to acquire an exclusive lock on the file, without blocking.
+    ///
+    /// Returns `Ok(false)` if another file handle holds a lock on the file.
+    /// See [`lock`](File::lock) for the details of locking.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix
+    /// with `LOCK_EX | LOCK_NB`, and to `LockFileEx` on Windows with
+    /// `LOCKFILE_EXCLUSIVE_LOCK | LOCKFILE_FAIL_IMMEDIATELY`.
+    ///
+    /// Note that this [may change in the future][changes].
+    ///
+    /// [changes]: io#platform-specific-behavior
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let f = File::create("foo.lock")?;
+    ///     if !f.try_lock()? {
+    ///         eprintln!("another instance is already running");
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "none")]
+    pub fn try_lock(&self) -> io::Result<bool> {
+        self.inner.try_lock()
+    }
+
+    /// Tries to acquire a shared lock on the file, without blocking.
+    ///
+    /// Returns `Ok(false)` if another file handle holds an exclusive lock on
+    /// the file. See [`lock_shared`](File::lock_shared) for the details of
+    /// shared locks.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix
+    /// with `LOCK_SH | LOCK_NB`, and to `LockFileEx` on Windows with
+    /// `LOCKFILE_FAIL_IMMEDIATELY`.
+    ///
+    /// Note that this [may change in the future][changes].
+    ///
+    /// [changes]: io#platform-specific-behavior
+    #[unstable(feature = "file_lock", issue = "none")]
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        self.inner.try_lock_shared()
+    }
+
+    /// Releases the lock this file handle holds on the file, if any.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix
+    /// with `LOCK_UN`, and to `UnlockFile` on Windows.
+    ///
+    /// Note that this [may change in the future][changes].
+    ///
+    /// [changes]: io#platform-specific-behavior
+    #[unstable(feature = "file_lock", issue = "none")]
+    pub fn unlock(&self) -> io::Result<()> {
+        self.inner.unlock()
+    }
 }
 
 // In addition to the `impl`s here, `File` also has `impl`s for
@@ -1161,6 +1448,97 @@ fn _open(&self, path: &Path) -> io::Result<File> {
     }
 }
 
+impl CopyOptions {
+    /// Creates a blank new set of options, set to copy the contents and
+    /// permissions of a file like [`fs::copy`](copy) does.
+    ///
+    /// All options are initially set to their default: [`Reflink::Auto`],
+    /// not preserving holes, copying the permissions but not the timestamps,
+    /// and following symbolic links.
+    #[unstable(feature = "fs_copy_options", issue = "none")]
+    #[must_use]
+    pub fn new() -> Self {
+        CopyOptions(fs_imp::CopyOptions::new())
+    }
+
+    /// Sets whether the copy may, or must, be a copy-on-write clone of the
+    /// original file.
+    ///
+    /// With [`Reflink::Never`], the data is always copied, even on
+    /// filesystems where a plain copy would share storage.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// Clones are currently only made on Linux, using the `FICLONE` `ioctl`,
+    /// so [`Reflink::Always`] fails with [`io::ErrorKind::Unsupported`]
+    /// elsewhere.
+    #[unstable(feature = "fs_copy_options", issue = "none")]
+    pub fn reflink(&mut self, reflink: Reflink) -> &mut Self {
+        self.0.reflink = reflink;
+        self
+    }
+
+    /// Sets whether holes in a sparse file stay holes in the copy, rather
+    /// than being filled with zeros that take up space on disk.
+    ///
+    /// This only matters when the file isn't cloned.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// Holes are currently only detected on Linux, using `SEEK_DATA` and
+    /// `SEEK_HOLE`. Elsewhere this option is ignored.
+    #[unstable(feature = "fs_copy_options", issue = "none")]
+    pub fn sparse(&mut self, sparse: bool) -> &mut Self {
+        self.0.sparse = sparse;
+        self
+    }
+
+    /// Sets whether the permissions of the original file are copied.
+    ///
+    /// Otherwise, a new file gets the same permissions as one created by
+    /// [`File::create`], and an existing file keeps its permissions.
+    #[unstable(feature = "fs_copy_options", issue = "none")]
+    pub fn permissions(&mut self, permissions: bool) -> &mut Self {
+        self.0.permissions = permissions;
+        self
+    }
+
+    /// Sets whether the last access and modification times of the original
+    /// file are copied.
+    #[unstable(feature = "fs_copy_options", issue = "none")]
+    pub fn timestamps(&mut self, timestamps: bool) -> &mut Self {
+        self.0.timestamps = timestamps;
+        self
+    }
+
+    /// Sets whether a symbolic link is followed, copying the file it points
+    /// to, or recreated as a symbolic link with the same target.
+    ///
+    /// An existing file at the destination is replaced by the new link. On
+    /// Windows, the new link is always a file symbolic link.
+    #[unstable(feature = "fs_copy_options", issue = "none")]
+    pub fn follow_symlinks(&mut self, follow_symlinks: bool) -> &mut Self {
+        self.0.follow_symlinks = follow_symlinks;
+        self
+    }
+
+    /// Copies `from` to `to` with the options specified by `self`.
+    ///
+    /// Like [`fs::copy`](copy), this overwrites the contents of `to` and
+    /// returns the length of the copied file. Copying a symbolic link without
+    /// following it returns 0.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error in the same situations as
+    /// [`fs::copy`](copy), and if [`Reflink::Always`] was set but the file
+    /// couldn't be cloned.
+    #[unstable(feature = "fs_copy_options", issue = "none")]
+    pub fn copy<P: AsRef<Path>, Q: AsRef<Path>>(&self, from: P, to: Q) -> io::Result<u64> {
+        fs_imp::copy_with(from.as_ref(), to.as_ref(), &self.0)
+    }
+}
+
 impl AsInner<fs_imp::OpenOptions> for OpenOptions {
     #[inline]
     fn as_inner(&self) -> &fs_imp::OpenOptions {
@@ -2646,6 +3024,97 @@ fn as_inner_mut(&mut self) -> &mut fs_imp::DirBuilder {
     }
 }
 
+impl Dir {
+    /// Opens the directory at `path`.
+    ///
+    /// Symbolic links are followed, as with [`File::open`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `path` doesn't exist or isn't a
+    /// directory, or if the platform doesn't support directory handles.
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Dir> {
+        fs_imp::DirHandle::open(path.as_ref()).map(|inner| Dir { inner })
+    }
+
+    /// Opens the directory at `path`, relative to this directory.
+    ///
+    /// This fails if the last component of `path` is a symbolic link, so that
+    /// a tree can be walked without ever leaving it.
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn open_dir_at<P: AsRef<Path>>(&self, path: P) -> io::Result<Dir> {
+        self.inner.open_dir_at(path.as_ref()).map(|inner| Dir { inner })
+    }
+
+    /// Opens the file at `path`, relative to this directory, with the given
+    /// options.
+    ///
+    /// This behaves like [`OpenOptions::open`], so a symbolic link in the last
+    /// component of `path` is followed unless the platform-specific custom
+    /// flags say otherwise.
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn open_file_at<P: AsRef<Path>>(&self, path: P, opts: &OpenOptions) -> io::Result<File> {
+        self.inner.open_file_at(path.as_ref(), &opts.0).map(|inner| File { inner })
+    }
+
+    /// Creates a new, empty directory at `path`, relative to this directory.
+    ///
+    /// Like [`create_dir`], it is an error if `path` already exists.
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn create_dir_at<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.inner.create_dir_at(path.as_ref())
+    }
+
+    /// Removes the file, symbolic link or empty directory at `path`, relative
+    /// to this directory.
+    ///
+    /// A symbolic link is removed itself, never the file it points to.
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn remove_at<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.inner.remove_at(path.as_ref())
+    }
+
+    /// Renames `from`, relative to this directory, to `to`, relative to
+    /// `to_dir`, which may be this directory.
+    ///
+    /// This behaves like [`rename`], including replacing `to` if it exists.
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn rename_at<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Dir,
+        to: Q,
+    ) -> io::Result<()> {
+        self.inner.rename_at(from.as_ref(), &to_dir.inner, to.as_ref())
+    }
+
+    /// Queries the metadata of `path`, relative to this directory, following
+    /// symbolic links like [`metadata`].
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn metadata_at<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
+        self.inner.stat_at(path.as_ref(), true).map(Metadata)
+    }
+
+    /// Queries the metadata of `path`, relative to this directory, without
+    /// following symbolic links, like [`symlink_metadata`].
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn symlink_metadata_at<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
+        self.inner.stat_at(path.as_ref(), false).map(Metadata)
+    }
+
+    /// Returns an iterator over the entries of this directory.
+    ///
+    /// Each call starts again from the first entry. The paths of the entries
+    /// are built from the path this directory was opened with, so they can be
+    /// stale if a directory on the way was renamed since. Prefer passing
+    /// [`DirEntry::file_name`] to the `*_at` methods of this `Dir`.
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn read_dir(&self) -> io::Result<ReadDir> {
+        self.inner.read_dir().map(ReadDir)
+    }
+}
+
 /// Returns `Ok(true)` if the path points at an existing entity.
 ///
 /// This function will traverse symbolic links to query information about the
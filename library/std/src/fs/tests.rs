@@ -1,10 +1,10 @@
 use crate::io::prelude::*;
 
 use crate::env;
-use crate::fs::{self, File, FileTimes, OpenOptions};
+use crate::fs::{self, CopyOptions, Dir, File, FileTimes, OpenOptions, Reflink};
 use crate::io::{BorrowedBuf, ErrorKind, SeekFrom};
 use crate::mem::MaybeUninit;
-use crate::path::Path;
+use crate::path::{Path, PathBuf};
 use crate::str;
 use crate::sync::Arc;
 use crate::sys_common::io::test::{tmpdir, TempDir};
@@ -1803,3 +1803,317 @@ fn test_hidden_file_truncation() {
     let metadata = file.metadata().unwrap();
     assert_eq!(metadata.len(), 0);
 }
+
+#[test]
+#[cfg(all(unix, not(miri)))]
+fn dir_relative_operations() {
+    let tmpdir = tmpdir();
+    let dir = check!(Dir::open(tmpdir.path()));
+
+    check!(dir.create_dir_at("sub"));
+    let sub = check!(dir.open_dir_at("sub"));
+    let mut file = check!(sub.open_file_at("a.txt", OpenOptions::new().write(true).create(true)));
+    check!(file.write_all(b"hello"));
+    drop(file);
+    assert_eq!(check!(fs::read(tmpdir.join("sub/a.txt"))), b"hello");
+    assert_eq!(check!(dir.metadata_at("sub/a.txt")).len(), 5);
+
+    // Renaming the directory doesn't change what the handle refers to.
+    check!(fs::rename(tmpdir.join("sub"), tmpdir.join("moved")));
+    check!(sub.rename_at("a.txt", &dir, "b.txt"));
+    assert!(tmpdir.join("b.txt").is_file());
+    assert!(!tmpdir.join("moved/a.txt").exists());
+
+    let names: Vec<_> = check!(dir.read_dir()).map(|e| check!(e).file_name()).collect();
+    assert_eq!(names.len(), 2);
+    // Reading a second time starts over.
+    assert_eq!(check!(dir.read_dir()).count(), 2);
+
+    // Symbolic links are neither traversed nor removed through.
+    check!(symlink_dir(tmpdir.join("moved"), tmpdir.join("link")));
+    assert!(check!(dir.symlink_metadata_at("link")).file_type().is_symlink());
+    assert!(check!(dir.metadata_at("link")).is_dir());
+    assert!(dir.open_dir_at("link").is_err());
+    check!(dir.remove_at("link"));
+    assert!(tmpdir.join("moved").is_dir());
+
+    check!(dir.remove_at("b.txt"));
+    check!(dir.remove_at("moved"));
+    assert_eq!(check!(dir.read_dir()).count(), 0);
+    assert_eq!(dir.remove_at("moved").unwrap_err().kind(), ErrorKind::NotFound);
+}
+
+#[test]
+#[cfg(all(unix, not(miri)))]
+fn dir_remove_at() {
+    let tmpdir = tmpdir();
+    let dir = check!(Dir::open(tmpdir.path()));
+
+    check!(fs::write(tmpdir.join("file"), b""));
+    check!(fs::create_dir(tmpdir.join("empty")));
+    check!(fs::create_dir_all(tmpdir.join("full/child")));
+
+    check!(dir.remove_at("file"));
+    assert!(!tmpdir.join("file").exists());
+    check!(dir.remove_at("empty"));
+    assert!(!tmpdir.join("empty").exists());
+    // A directory that isn't empty is left alone.
+    assert!(dir.remove_at("full").is_err());
+    assert!(tmpdir.join("full/child").is_dir());
+}
+
+#[test]
+fn write_atomic_replaces_contents() {
+    let tmpdir = tmpdir();
+    let path = tmpdir.join("config");
+
+    check!(fs::write_atomic(&path, b"first"));
+    assert_eq!(check!(fs::read(&path)), b"first");
+    check!(fs::write_atomic(&path, b"second, longer"));
+    assert_eq!(check!(fs::read(&path)), b"second, longer");
+
+    // No temporary files are left behind.
+    assert_eq!(check!(fs::read_dir(tmpdir.path())).count(), 1);
+
+    let missing = tmpdir.join("missing/config");
+    assert_eq!(fs::write_atomic(&missing, b"x").unwrap_err().kind(), ErrorKind::NotFound);
+    assert_eq!(fs::write_atomic("/", b"x").unwrap_err().kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn copy_options() {
+    let tmpdir = tmpdir();
+    let src = tmpdir.join("src");
+    let dst = tmpdir.join("dst");
+    check!(fs::write(&src, b"contents"));
+    let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+    check!(check!(File::options().write(true).open(&src)).set_modified(modified));
+    let mut perm = check!(fs::metadata(&src)).permissions();
+    perm.set_readonly(true);
+    check!(fs::set_permissions(&src, perm.clone()));
+
+    let bytes =
+        check!(CopyOptions::new().reflink(Reflink::Never).timestamps(true).copy(&src, &dst));
+    assert_eq!(bytes, 8);
+    assert_eq!(check!(fs::read(&dst)), b"contents");
+    assert!(check!(fs::metadata(&dst)).permissions().readonly());
+    assert_eq!(check!(check!(fs::metadata(&dst)).modified()), modified);
+
+    let plain = tmpdir.join("plain");
+    check!(CopyOptions::new().permissions(false).copy(&src, &plain));
+    assert!(!check!(fs::metadata(&plain)).permissions().readonly());
+    assert_ne!(check!(check!(fs::metadata(&plain)).modified()), modified);
+
+    perm.set_readonly(false);
+    check!(fs::set_permissions(&src, perm.clone()));
+    check!(fs::set_permissions(&dst, perm));
+}
+
+#[test]
+#[cfg(unix)]
+fn copy_options_symlink() {
+    let tmpdir = tmpdir();
+    let link = tmpdir.join("link");
+    let dst = tmpdir.join("dst");
+    check!(fs::write(tmpdir.join("target"), b"contents"));
+    check!(symlink_file("target", &link));
+    check!(fs::write(&dst, b"replaced"));
+
+    assert_eq!(check!(CopyOptions::new().follow_symlinks(false).copy(&link, &dst)), 0);
+    assert_eq!(check!(fs::read_link(&dst)), Path::new("target"));
+
+    check!(fs::remove_file(&dst));
+    assert_eq!(check!(CopyOptions::new().copy(&link, &dst)), 8);
+    assert!(check!(fs::symlink_metadata(&dst)).is_file());
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn copy_options_sparse() {
+    use crate::os::unix::fs::{FileExt, MetadataExt};
+
+    let tmpdir = tmpdir();
+    let src = tmpdir.join("src");
+    let dst = tmpdir.join("dst");
+    let file = check!(File::create(&src));
+    check!(file.write_all_at(b"start", 0));
+    check!(file.write_all_at(b"middle", 32 << 20));
+    check!(file.set_len(64 << 20));
+    drop(file);
+
+    for reflink in [Reflink::Never, Reflink::Auto] {
+        assert_eq!(
+            check!(CopyOptions::new().sparse(true).reflink(reflink).copy(&src, &dst)),
+            64 << 20
+        );
+        let contents = check!(fs::read(&dst));
+        assert_eq!(contents.len(), 64 << 20);
+        assert_eq!(&contents[..5], b"start");
+        assert_eq!(&contents[32 << 20..(32 << 20) + 6], b"middle");
+        assert!(contents[5..32 << 20].iter().all(|&b| b == 0));
+        // The copy has holes wherever the original has them.
+        assert!(check!(fs::metadata(&dst)).blocks() <= check!(fs::metadata(&src)).blocks());
+    }
+}
+
+#[test]
+fn walk_dir_options() {
+    let tmpdir = tmpdir();
+    let root = tmpdir.path();
+    check!(fs::create_dir_all(root.join("a/b")));
+    check!(fs::create_dir(root.join("c")));
+    check!(File::create(root.join("a/b/f")));
+    check!(File::create(root.join("a/g")));
+
+    let walk = |walk: fs::WalkDir| -> Vec<(usize, PathBuf)> {
+        walk.map(|entry| {
+            let entry = check!(entry);
+            (entry.depth(), entry.path().strip_prefix(root).unwrap().to_path_buf())
+        })
+        .collect()
+    };
+    let paths = |paths: &[(usize, &str)]| -> Vec<(usize, PathBuf)> {
+        paths.iter().map(|&(depth, path)| (depth, PathBuf::from(path))).collect()
+    };
+
+    let pre_order = [(0, ""), (1, "a"), (2, "a/b"), (3, "a/b/f"), (2, "a/g"), (1, "c")];
+    assert_eq!(walk(fs::walk_dir(root).sort_by_file_name()), paths(&pre_order));
+    assert_eq!(
+        walk(fs::walk_dir(root).sort_by_file_name().contents_first(true)),
+        paths(&[(3, "a/b/f"), (2, "a/b"), (2, "a/g"), (1, "a"), (1, "c"), (0, "")])
+    );
+    assert_eq!(
+        walk(fs::walk_dir(root).sort_by_file_name().min_depth(1).max_depth(2)),
+        paths(&[(1, "a"), (2, "a/b"), (2, "a/g"), (1, "c")])
+    );
+    // Without sorting, the same entries are yielded in some order.
+    let mut unsorted = walk(fs::walk_dir(root));
+    unsorted.sort();
+    let mut sorted = paths(&pre_order);
+    sorted.sort();
+    assert_eq!(unsorted, sorted);
+
+    let mut walker = fs::walk_dir(root).sort_by_file_name();
+    let mut names = Vec::new();
+    while let Some(entry) = walker.next() {
+        let entry = check!(entry);
+        if entry.file_name() == "a" {
+            walker.skip_current_dir();
+        }
+        names.push(entry.file_name().to_owned());
+    }
+    assert_eq!(names.len(), 3);
+
+    let error = fs::walk_dir(root.join("missing")).next().unwrap().unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::NotFound);
+}
+
+#[test]
+#[cfg(unix)]
+fn walk_dir_symlinks() {
+    let tmpdir = tmpdir();
+    let root = tmpdir.path();
+    check!(fs::create_dir(root.join("a")));
+    check!(File::create(root.join("a/f")));
+    check!(symlink_dir("..", root.join("a/up")));
+    check!(symlink_dir("a", root.join("link")));
+    check!(fs::create_dir(root.join("c")));
+    check!(symlink_dir("../a", root.join("c/a")));
+
+    let entries: Vec<_> =
+        fs::walk_dir(root).sort_by_file_name().map(|entry| check!(entry)).collect();
+    assert_eq!(entries.len(), 7);
+    assert!(entries.iter().all(|e| !e.file_type().is_symlink() || e.path_is_symlink()));
+
+    let mut loops = 0;
+    let mut files = 0;
+    for entry in fs::walk_dir(root).follow_symlinks(true) {
+        match entry {
+            Ok(entry) => files += entry.file_type().is_file() as usize,
+            Err(e) => {
+                assert_eq!(e.kind(), ErrorKind::FilesystemLoop);
+                loops += 1;
+            }
+        }
+    }
+    // `a/up` loops through `a`, `link` and `c/a`, which aren't loops themselves and are
+    // followed to find `link/f` and `c/a/f`.
+    assert_eq!((loops, files), (3, 3));
+}
+
+#[test]
+#[cfg(any(windows, target_os = "linux", target_vendor = "apple", target_os = "freebsd"))]
+fn file_lock() {
+    let tmpdir = tmpdir();
+    let path = tmpdir.join("lock");
+    let f1 = check!(File::create(&path));
+    let f2 = check!(File::open(&path));
+
+    // Unlocking a file that holds no lock succeeds everywhere.
+    check!(f1.unlock());
+
+    check!(f1.lock());
+    assert!(!check!(f2.try_lock()));
+    assert!(!check!(f2.try_lock_shared()));
+    check!(f1.unlock());
+    assert!(check!(f2.try_lock()));
+    assert!(!check!(f1.try_lock_shared()));
+    check!(f2.unlock());
+
+    check!(f1.lock_shared());
+    assert!(check!(f2.try_lock_shared()));
+    assert!(!check!(File::open(&path)).try_lock().unwrap());
+    check!(f2.unlock());
+    check!(f1.unlock());
+
+    // Closing the file releases the lock. Windows may release it some time after the handle is
+    // closed, so only check this on unix.
+    if cfg!(unix) {
+        check!(f1.lock());
+        drop(f1);
+        assert!(check!(f2.try_lock()));
+    }
+}
+
+// Run by `file_lock_cross_process` in a child process, which exits with a code saying which
+// locks it could take.
+#[test]
+#[cfg(any(windows, target_os = "linux", target_os = "macos", target_os = "freebsd"))]
+fn file_lock_child() {
+    let Some(path) = env::var_os("RUST_TEST_FILE_LOCK_PATH") else { return };
+    let file = check!(File::open(path));
+    let code = if check!(file.try_lock()) {
+        1
+    } else if check!(file.try_lock_shared()) {
+        2
+    } else {
+        3
+    };
+    crate::process::exit(code);
+}
+
+#[test]
+#[cfg(any(windows, target_os = "linux", target_os = "macos", target_os = "freebsd"))]
+fn file_lock_cross_process() {
+    use crate::process::Command;
+
+    let tmpdir = tmpdir();
+    let path = tmpdir.join("lock");
+    let file = check!(File::create(&path));
+    let child_lock = || {
+        let status = Command::new(check!(env::current_exe()))
+            .args(["fs::tests::file_lock_child", "--exact", "--test-threads=1"])
+            .env("RUST_TEST_FILE_LOCK_PATH", &path)
+            .status();
+        check!(status).code()
+    };
+
+    assert_eq!(child_lock(), Some(1));
+    check!(file.lock_shared());
+    assert_eq!(child_lock(), Some(2));
+    check!(file.unlock());
+    check!(file.lock());
+    assert_eq!(child_lock(), Some(3));
+    check!(file.unlock());
+    assert_eq!(child_lock(), Some(1));
+}
@@ -0,0 +1,482 @@
+//! Recursive directory traversal.
+
+use super::{
+    canonicalize, metadata, read_dir, symlink_metadata, DirEntry, FileType, Metadata, ReadDir,
+};
+use crate::cmp::Ordering;
+use crate::ffi::OsStr;
+use crate::fmt;
+use crate::io;
+use crate::path::{Path, PathBuf};
+use crate::vec;
+
+/// Returns an iterator over the entries of a directory tree, starting with
+/// `root` itself.
+///
+/// This is a recursive version of [`read_dir`]. By default, the entries of
+/// each directory are yielded right after the directory itself (pre-order),
+/// in the platform and filesystem dependent order of [`read_dir`], and
+/// symbolic links are not followed. See [`WalkDir`] for how to change this.
+///
+/// If `root` is a symbolic link, it is always followed.
+///
+/// # Errors
+///
+/// Errors are yielded by the iterator as they occur, and iteration carries on
+/// with the next entry afterwards, so a directory that can't be read doesn't
+/// stop the walk. Stop iterating at the first error to get a strict walk
+/// instead. Errors include:
+///
+/// * `root` not existing or not being accessible.
+/// * A directory not being readable. The directory itself is yielded first.
+/// * A symbolic link to follow being broken.
+/// * A followed symbolic link pointing to one of its own ancestors. The error
+///   is of kind [`io::ErrorKind::FilesystemLoop`], and the link is not yielded.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_walk_dir)]
+/// use std::fs;
+///
+/// for entry in fs::walk_dir("src").max_depth(3).sort_by_file_name() {
+///     let entry = entry?;
+///     println!("{}{}", "  ".repeat(entry.depth()), entry.file_name().to_string_lossy());
+/// }
+/// # std::io::Result::Ok(())
+/// ```
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+pub fn walk_dir<P: AsRef<Path>>(root: P) -> WalkDir {
+    WalkDir {
+        root: Some(root.as_ref().to_path_buf()),
+        min_depth: 0,
+        max_depth: usize::MAX,
+        follow_symlinks: false,
+        contents_first: false,
+        sorter: None,
+        stack: Vec::new(),
+        ancestors: Vec::new(),
+        deferred: Vec::new(),
+    }
+}
+
+/// An iterator over the entries of a directory tree.
+///
+/// This struct is created by [`walk_dir`], and its options are set with
+/// builder methods before iterating over it. Use
+/// [`skip_current_dir`](WalkDir::skip_current_dir) while iterating to leave
+/// out the rest of a subtree.
+///
+/// # Examples
+///
+/// Skipping hidden directories:
+///
+/// ```no_run
+/// #![feature(fs_walk_dir)]
+/// use std::fs;
+///
+/// let mut walk = fs::walk_dir(".");
+/// while let Some(entry) = walk.next() {
+///     let entry = entry?;
+///     if entry.depth() > 0 && entry.file_name().as_encoded_bytes().starts_with(b".") {
+///         if entry.file_type().is_dir() {
+///             walk.skip_current_dir();
+///         }
+///         continue;
+///     }
+///     println!("{}", entry.path().display());
+/// }
+/// # std::io::Result::Ok(())
+/// ```
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+pub struct WalkDir {
+    root: Option<PathBuf>,
+    min_depth: usize,
+    max_depth: usize,
+    follow_symlinks: bool,
+    contents_first: bool,
+    sorter: Option<Box<dyn FnMut(&WalkEntry, &WalkEntry) -> Ordering + Send + Sync + 'static>>,
+    // The directories being read, the last one being the deepest.
+    stack: Vec<DirList>,
+    // The directories in `stack`, if following symbolic links.
+    ancestors: Vec<Ancestor>,
+    // The directories in `stack`, waiting for their contents to be yielded first.
+    deferred: Vec<WalkEntry>,
+}
+
+struct Ancestor {
+    path: PathBuf,
+    // Only looked up once a symbolic link has to be checked against it.
+    id: Option<FileId>,
+}
+
+// Tells directories apart, to find symbolic links that loop.
+#[derive(PartialEq)]
+enum FileId {
+    // The device and inode numbers, or the volume serial number and file index.
+    Index(u64, u64),
+    // Where the platform doesn't provide those in the metadata.
+    Canonical(PathBuf),
+}
+
+impl FileId {
+    #[cfg(unix)]
+    fn from_metadata(metadata: &Metadata) -> Option<FileId> {
+        use crate::os::unix::fs::MetadataExt;
+        Some(FileId::Index(metadata.dev(), metadata.ino()))
+    }
+
+    #[cfg(windows)]
+    fn from_metadata(metadata: &Metadata) -> Option<FileId> {
+        use crate::os::windows::fs::MetadataExt;
+        Some(FileId::Index(metadata.volume_serial_number()?.into(), metadata.file_index()?))
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    fn from_metadata(_metadata: &Metadata) -> Option<FileId> {
+        None
+    }
+
+    // Identifies the directory at `path`, given its metadata if that was fetched already.
+    fn of(path: &Path, fetched: Option<&Metadata>) -> io::Result<FileId> {
+        let id = match fetched {
+            Some(fetched) => FileId::from_metadata(fetched),
+            None => FileId::from_metadata(&metadata(path)?),
+        };
+        match id {
+            Some(id) => Ok(id),
+            None => canonicalize(path).map(FileId::Canonical),
+        }
+    }
+}
+
+enum DirList {
+    Opened(ReadDir),
+    Sorted(vec::IntoIter<io::Result<WalkEntry>>),
+    // A directory that couldn't be read, and then a skipped or exhausted one.
+    Error(Option<io::Error>),
+}
+
+impl WalkDir {
+    /// Sets the minimum depth of the entries to yield.
+    ///
+    /// `root` has depth 0, its entries depth 1, and so on. Shallower entries
+    /// are still descended into, but not yielded. The default is 0.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn min_depth(mut self, depth: usize) -> Self {
+        self.min_depth = depth;
+        self
+    }
+
+    /// Sets the maximum depth of the entries to yield.
+    ///
+    /// Directories at this depth are yielded but not descended into, so
+    /// `max_depth(1)` is like [`read_dir`] plus `root` itself. The default is
+    /// no limit.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Sets whether symbolic links are followed.
+    ///
+    /// When following them, an entry for a symbolic link describes the file
+    /// it points to, and a link to a directory is descended into unless that
+    /// would loop. When not following them, which is the default, symbolic
+    /// links are yielded as such.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn follow_symlinks(mut self, follow: bool) -> Self {
+        self.follow_symlinks = follow;
+        self
+    }
+
+    /// Sets whether the entries of a directory are yielded before the
+    /// directory itself (post-order), rather than after it (pre-order).
+    ///
+    /// This is useful to remove a tree, which has to be emptied from the
+    /// bottom up. The default is `false`.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn contents_first(mut self, contents_first: bool) -> Self {
+        self.contents_first = contents_first;
+        self
+    }
+
+    /// Sets the order in which the entries of each directory are yielded.
+    ///
+    /// Each directory is read in full before its entries are yielded, which
+    /// takes memory proportional to the size of the largest directory. Errors
+    /// reading a directory are yielded before its entries.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn sort_by<F>(mut self, cmp: F) -> Self
+    where
+        F: FnMut(&WalkEntry, &WalkEntry) -> Ordering + Send + Sync + 'static,
+    {
+        self.sorter = Some(Box::new(cmp));
+        self
+    }
+
+    /// Yields the entries of each directory in the order of their file names.
+    ///
+    /// See [`sort_by`](WalkDir::sort_by).
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn sort_by_file_name(self) -> Self {
+        self.sort_by(|a, b| a.file_name().cmp(b.file_name()))
+    }
+
+    /// Skips the remaining entries of the directory that was descended into
+    /// last.
+    ///
+    /// Right after a directory has been yielded in pre-order, this leaves out
+    /// its whole subtree. Otherwise, this leaves out the rest of the directory
+    /// that contains the entry yielded last. In post-order, the directory
+    /// itself is still yielded.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn skip_current_dir(&mut self) {
+        if let Some(list) = self.stack.last_mut() {
+            *list = DirList::Error(None);
+        }
+    }
+
+    // Descends into `entry` if it is a directory to descend into, and returns it if it is to
+    // be yielded right away.
+    fn handle_entry(&mut self, entry: WalkEntry) -> Option<io::Result<WalkEntry>> {
+        if entry.file_type.is_dir() && entry.depth < self.max_depth {
+            if self.follow_symlinks {
+                // Only a symbolic link can lead back to an ancestor.
+                let id = if entry.followed {
+                    match self.check_loop(&entry) {
+                        Ok(id) => Some(id),
+                        Err(e) => return Some(Err(e)),
+                    }
+                } else {
+                    None
+                };
+                self.ancestors.push(Ancestor { path: entry.path.clone(), id });
+            }
+            let list = self.open(&entry.path);
+            self.stack.push(list);
+            if self.contents_first {
+                self.deferred.push(entry);
+                return None;
+            }
+        }
+        self.yield_entry(entry)
+    }
+
+    // Returns the identity of the directory that a followed symbolic link points to, or an
+    // error if that is one of the directories being walked.
+    fn check_loop(&mut self, entry: &WalkEntry) -> io::Result<FileId> {
+        let id = FileId::of(&entry.path, entry.target.as_ref())?;
+        for ancestor in &mut self.ancestors {
+            if ancestor.id.is_none() {
+                ancestor.id = Some(FileId::of(&ancestor.path, None)?);
+            }
+            if ancestor.id.as_ref() == Some(&id) {
+                let msg = format!("{} is a symbolic link to its ancestor", entry.path.display());
+                return Err(io::Error::new(io::ErrorKind::FilesystemLoop, msg));
+            }
+        }
+        Ok(id)
+    }
+
+    fn open(&mut self, path: &Path) -> DirList {
+        let list = match read_dir(path) {
+            Ok(list) => list,
+            Err(e) => return DirList::Error(Some(e)),
+        };
+        let Some(sorter) = &mut self.sorter else {
+            return DirList::Opened(list);
+        };
+        let depth = self.stack.len() + 1;
+        let mut entries: Vec<_> = list
+            .map(|entry| WalkEntry::from_dir_entry(entry?, depth, self.follow_symlinks))
+            .collect();
+        entries.sort_by(|a, b| match (a, b) {
+            (Ok(a), Ok(b)) => sorter(a, b),
+            (Err(_), Err(_)) => Ordering::Equal,
+            (Err(_), Ok(_)) => Ordering::Less,
+            (Ok(_), Err(_)) => Ordering::Greater,
+        });
+        DirList::Sorted(entries.into_iter())
+    }
+
+    fn yield_entry(&self, entry: WalkEntry) -> Option<io::Result<WalkEntry>> {
+        if entry.depth < self.min_depth { None } else { Some(Ok(entry)) }
+    }
+}
+
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+impl Iterator for WalkDir {
+    type Item = io::Result<WalkEntry>;
+
+    fn next(&mut self) -> Option<io::Result<WalkEntry>> {
+        if let Some(root) = self.root.take() {
+            match WalkEntry::from_root(root) {
+                Ok(entry) => {
+                    if let Some(next) = self.handle_entry(entry) {
+                        return Some(next);
+                    }
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+
+        loop {
+            let depth = self.stack.len();
+            let Some(list) = self.stack.last_mut() else { return None };
+            let next = match list {
+                DirList::Opened(list) => list
+                    .next()
+                    .map(|entry| WalkEntry::from_dir_entry(entry?, depth, self.follow_symlinks)),
+                DirList::Sorted(list) => list.next(),
+                DirList::Error(e) => e.take().map(Err),
+            };
+            match next {
+                Some(Ok(entry)) => {
+                    if let Some(next) = self.handle_entry(entry) {
+                        return Some(next);
+                    }
+                }
+                Some(Err(e)) => return Some(Err(e)),
+                None => {
+                    self.stack.pop();
+                    self.ancestors.truncate(self.stack.len());
+                    if self.deferred.len() > self.stack.len() {
+                        let dir = self.deferred.pop().unwrap();
+                        if let Some(next) = self.yield_entry(dir) {
+                            return Some(next);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+impl fmt::Debug for WalkDir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WalkDir")
+            .field("root", &self.root)
+            .field("min_depth", &self.min_depth)
+            .field("max_depth", &self.max_depth)
+            .field("follow_symlinks", &self.follow_symlinks)
+            .field("contents_first", &self.contents_first)
+            .field("sorted", &self.sorter.is_some())
+            .field("depth", &self.stack.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// An entry yielded by [`WalkDir`].
+///
+/// Unlike a [`DirEntry`], this can also be the root of the walk, and it knows
+/// its depth in the tree and whether it was reached through a symbolic link.
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+pub struct WalkEntry {
+    path: PathBuf,
+    depth: usize,
+    file_type: FileType,
+    followed: bool,
+    // The metadata of the file that a followed symbolic link points to.
+    target: Option<Metadata>,
+    entry: Option<DirEntry>,
+}
+
+impl WalkEntry {
+    fn from_root(path: PathBuf) -> io::Result<WalkEntry> {
+        let link_type = symlink_metadata(&path)?.file_type();
+        let followed = link_type.is_symlink();
+        let target = if followed { Some(metadata(&path)?) } else { None };
+        let file_type = target.as_ref().map_or(link_type, Metadata::file_type);
+        Ok(WalkEntry { path, depth: 0, file_type, followed, target, entry: None })
+    }
+
+    fn from_dir_entry(entry: DirEntry, depth: usize, follow: bool) -> io::Result<WalkEntry> {
+        let path = entry.path();
+        // `DirEntry::file_type` usually doesn't need another system call.
+        let link_type = entry.file_type()?;
+        let followed = follow && link_type.is_symlink();
+        let target = if followed { Some(metadata(&path)?) } else { None };
+        let file_type = target.as_ref().map_or(link_type, Metadata::file_type);
+        Ok(WalkEntry { path, depth, file_type, followed, target, entry: Some(entry) })
+    }
+
+    /// Returns the full path to the entry, which is the root of the walk
+    /// joined with the names of the entries leading to this one.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Converts the entry into its full path.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    #[must_use]
+    pub fn into_path(self) -> PathBuf {
+        self.path
+    }
+
+    /// Returns the file name of the entry.
+    ///
+    /// For the root of the walk, this is the whole path if it has no file
+    /// name, such as `/` or `..`.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    #[must_use]
+    pub fn file_name(&self) -> &OsStr {
+        self.path.file_name().unwrap_or(self.path.as_os_str())
+    }
+
+    /// Returns the depth of the entry: 0 for the root of the walk, 1 for its
+    /// entries, and so on.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    #[must_use]
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the file type of the entry.
+    ///
+    /// If the entry is a symbolic link that was followed, this is the type of
+    /// the file it points to.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    #[must_use]
+    pub fn file_type(&self) -> FileType {
+        self.file_type
+    }
+
+    /// Returns whether the path of the entry is a symbolic link, whether it
+    /// was followed or not.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    #[must_use]
+    pub fn path_is_symlink(&self) -> bool {
+        self.followed || self.file_type.is_symlink()
+    }
+
+    /// Returns the metadata of the entry, that of the file a symbolic link
+    /// points to if it was followed.
+    ///
+    /// This is as cheap as [`DirEntry::metadata`] when possible, and doesn't
+    /// need another system call for a symbolic link that was followed.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn metadata(&self) -> io::Result<Metadata> {
+        match (&self.target, &self.entry) {
+            (Some(target), _) => Ok(target.clone()),
+            (None, Some(entry)) => entry.metadata(),
+            (None, None) => symlink_metadata(&self.path),
+        }
+    }
+}
+
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+impl fmt::Debug for WalkEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WalkEntry")
+            .field("path", &self.path)
+            .field("depth", &self.depth)
+            .field("file_type", &self.file_type)
+            .field("path_is_symlink", &self.path_is_symlink())
+            .finish()
+    }
+}
@@ -2,8 +2,11 @@
 use crate::alloc::Allocator;
 use crate::cmp;
 use crate::collections::VecDeque;
+use crate::fs::File;
 use crate::io::IoSlice;
 use crate::mem::MaybeUninit;
+use crate::net::TcpStream;
+use crate::process::{ChildStderr, ChildStdin, ChildStdout};
 
 #[cfg(test)]
 mod tests;
@@ -72,6 +75,269 @@ pub fn copy<R: ?Sized, W: ?Sized>(reader: &mut R, writer: &mut W) -> Result<u64>
     }
 }
 
+/// Copies at most `limit` bytes from a reader into a writer.
+///
+/// This behaves like [`copy`] on <code>reader.[take]\(limit)</code>: data is
+/// copied until `limit` bytes have been transferred or `reader` returns EOF,
+/// and the same zero-copy optimizations apply.
+///
+/// On success, the number of bytes copied is returned.
+///
+/// [take]: Read::take
+///
+/// # Errors
+///
+/// This function returns an error under the same conditions as [`copy`].
+///
+/// # Examples
+///
+/// ```
+/// #![feature(io_splice)]
+/// use std::io;
+///
+/// fn main() -> io::Result<()> {
+///     let mut reader: &[u8] = b"hello world";
+///     let mut writer: Vec<u8> = vec![];
+///
+///     assert_eq!(io::copy_n(&mut reader, &mut writer, 5)?, 5);
+///     assert_eq!(writer, b"hello");
+///     assert_eq!(reader, b" world");
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "io_splice", issue = "none")]
+pub fn copy_n<R: ?Sized, W: ?Sized>(reader: &mut R, writer: &mut W, limit: u64) -> Result<u64>
+where
+    R: Read,
+    W: Write,
+{
+    copy(&mut Read::take(reader, limit), writer)
+}
+
+/// Moves at most `limit` bytes from a reader into a writer, bypassing
+/// userspace buffers where the operating system allows it, and reports how
+/// the data was moved.
+///
+/// Unlike [`copy`], which only takes the zero-copy path for standard library
+/// types, this works for any type which implements [`AsSpliceHandle`]. If both
+/// sides provide a handle, the data is moved by the kernel; otherwise, or if
+/// the kernel cannot move data between these handles, it falls back to a
+/// [`Read`]/[`Write`] loop.
+///
+/// On success, the number of bytes moved and the [`SpliceMethod`] that moved
+/// them are returned. Data is moved until `limit` bytes have been transferred
+/// or `reader` returns EOF; pass `u64::MAX` to move everything.
+///
+/// # Errors
+///
+/// This function returns an error immediately if any of the underlying
+/// operations fail. When splicing between two sockets the data passes through
+/// a pipe, and bytes which have already been read from `reader` but not yet
+/// written to `writer` are lost if writing fails. Both handles should be in
+/// blocking mode.
+///
+/// # Platform-specific behavior
+///
+/// On Linux (including Android), this function uses `copy_file_range(2)`,
+/// `sendfile(2)` or `splice(2)`, where needed through an intermediate pipe.
+/// Other platforms always use the [`Read`]/[`Write`] loop.
+///
+/// Note that platform-specific behavior [may change in the future][changes].
+///
+/// [changes]: crate::io#platform-specific-behavior
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(io_splice)]
+/// use std::io;
+/// use std::net::TcpStream;
+///
+/// fn main() -> io::Result<()> {
+///     let mut upstream = TcpStream::connect("127.0.0.1:8080")?;
+///     let mut client = TcpStream::connect("127.0.0.1:9090")?;
+///
+///     let (bytes, method) = io::splice(&mut upstream, &mut client, 1 << 20)?;
+///     println!("moved {bytes} bytes using {method:?}");
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "io_splice", issue = "none")]
+pub fn splice<R: ?Sized, W: ?Sized>(
+    reader: &mut R,
+    writer: &mut W,
+    limit: u64,
+) -> Result<(u64, SpliceMethod)>
+where
+    R: Read + AsSpliceHandle,
+    W: Write + AsSpliceHandle,
+{
+    let copied = match (reader.as_splice_handle(), writer.as_splice_handle()) {
+        (Some(input), Some(output)) => match splice_handles(input, output, limit)? {
+            (bytes, Some(method)) => return Ok((bytes, method)),
+            (bytes, None) => bytes,
+        },
+        _ => 0,
+    };
+    let rest = generic_copy(&mut Read::take(reader, limit - copied), writer)?;
+    Ok((copied + rest, SpliceMethod::Buffered))
+}
+
+/// Moves data between two handles in the kernel.
+///
+/// Returns the number of bytes moved and, if all of them were moved, the method that did it.
+/// Otherwise the caller has to copy the rest through a userspace buffer.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn splice_handles(
+    input: SpliceHandle<'_>,
+    output: SpliceHandle<'_>,
+    limit: u64,
+) -> Result<(u64, Option<SpliceMethod>)> {
+    use crate::os::fd::AsRawFd;
+
+    crate::sys::kernel_copy::splice_fds(input.fd.as_raw_fd(), output.fd.as_raw_fd(), limit)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn splice_handles(
+    _input: SpliceHandle<'_>,
+    _output: SpliceHandle<'_>,
+    _limit: u64,
+) -> Result<(u64, Option<SpliceMethod>)> {
+    Ok((0, None))
+}
+
+/// The mechanism [`splice`] used to move data.
+#[unstable(feature = "io_splice", issue = "none")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SpliceMethod {
+    /// The data was copied with `copy_file_range(2)`.
+    #[unstable(feature = "io_splice", issue = "none")]
+    CopyFileRange,
+    /// The data was copied with `sendfile(2)`.
+    #[unstable(feature = "io_splice", issue = "none")]
+    Sendfile,
+    /// The data was moved with `splice(2)`, possibly through an intermediate
+    /// pipe.
+    #[unstable(feature = "io_splice", issue = "none")]
+    Splice,
+    /// At least some of the data went through a userspace buffer using
+    /// [`Read`] and [`Write`].
+    #[unstable(feature = "io_splice", issue = "none")]
+    Buffered,
+}
+
+/// A borrowed operating system handle that [`splice`] can move data to or
+/// from directly.
+///
+/// On Unix platforms, this is created from a
+/// [`BorrowedFd`](crate::os::fd::BorrowedFd) with [`From`]. Other platforms
+/// currently have no way to create one.
+#[unstable(feature = "io_splice", issue = "none")]
+#[derive(Copy, Clone, Debug)]
+pub struct SpliceHandle<'a> {
+    #[cfg(unix)]
+    #[cfg_attr(not(any(target_os = "linux", target_os = "android")), allow(dead_code))]
+    pub(crate) fd: crate::os::fd::BorrowedFd<'a>,
+    #[cfg(not(unix))]
+    _never: (!, crate::marker::PhantomData<&'a ()>),
+}
+
+/// A type whose data can be moved by [`splice`] without going through its
+/// [`Read`] or [`Write`] implementation.
+///
+/// Implementing this trait is a promise that reading from or writing to the
+/// returned handle has the same effect as calling [`Read::read`] or
+/// [`Write::write`] on the type itself. In particular, the type must not hold
+/// any buffered data of its own. Return `None` if that is not the case at the
+/// moment, and `splice` will use the [`Read`] and [`Write`] implementations
+/// instead.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(io_splice)]
+/// use std::io::{self, AsSpliceHandle, Read, SpliceHandle};
+/// use std::net::TcpStream;
+///
+/// struct Connection {
+///     stream: TcpStream,
+/// }
+///
+/// impl Read for Connection {
+///     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+///         self.stream.read(buf)
+///     }
+/// }
+///
+/// impl AsSpliceHandle for Connection {
+///     fn as_splice_handle(&self) -> Option<SpliceHandle<'_>> {
+///         // `Connection` does not buffer anything itself, so the socket can
+///         // be used directly.
+///         self.stream.as_splice_handle()
+///     }
+/// }
+///
+/// fn main() -> io::Result<()> {
+///     let mut conn = Connection { stream: TcpStream::connect("127.0.0.1:8080")? };
+///     let mut out = TcpStream::connect("127.0.0.1:9090")?;
+///     io::splice(&mut conn, &mut out, u64::MAX)?;
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "io_splice", issue = "none")]
+pub trait AsSpliceHandle {
+    /// Returns the handle to move data to or from, or `None` if data has to
+    /// go through the type's [`Read`] or [`Write`] implementation.
+    #[unstable(feature = "io_splice", issue = "none")]
+    fn as_splice_handle(&self) -> Option<SpliceHandle<'_>>;
+}
+
+#[unstable(feature = "io_splice", issue = "none")]
+impl<T: AsSpliceHandle + ?Sized> AsSpliceHandle for &T {
+    #[inline]
+    fn as_splice_handle(&self) -> Option<SpliceHandle<'_>> {
+        (**self).as_splice_handle()
+    }
+}
+
+#[unstable(feature = "io_splice", issue = "none")]
+impl<T: AsSpliceHandle + ?Sized> AsSpliceHandle for &mut T {
+    #[inline]
+    fn as_splice_handle(&self) -> Option<SpliceHandle<'_>> {
+        (**self).as_splice_handle()
+    }
+}
+
+#[unstable(feature = "io_splice", issue = "none")]
+impl<T: AsSpliceHandle + ?Sized> AsSpliceHandle for Box<T> {
+    #[inline]
+    fn as_splice_handle(&self) -> Option<SpliceHandle<'_>> {
+        (**self).as_splice_handle()
+    }
+}
+
+macro_rules! impl_as_splice_handle {
+    ($($t:ty),*$(,)?) => {$(
+        #[unstable(feature = "io_splice", issue = "none")]
+        impl AsSpliceHandle for $t {
+            #[inline]
+            fn as_splice_handle(&self) -> Option<SpliceHandle<'_>> {
+                cfg_if::cfg_if! {
+                    if #[cfg(unix)] {
+                        Some(SpliceHandle { fd: crate::os::fd::AsFd::as_fd(self) })
+                    } else {
+                        None
+                    }
+                }
+            }
+        }
+    )*}
+}
+
+impl_as_splice_handle!(File, TcpStream, ChildStdin, ChildStdout, ChildStderr);
+
 /// The userspace read-write-loop implementation of `io::copy` that is used when
 /// OS-specific specializations for copy offloading are not available or not applicable.
 pub(crate) fn generic_copy<R: ?Sized, W: ?Sized>(reader: &mut R, writer: &mut W) -> Result<u64>
@@ -145,3 +145,36 @@ fn bench_copy_buf_reader(b: &mut Bencher) {
         });
     }
 }
+
+#[test]
+fn copy_n_stops_at_limit() {
+    let mut r: &[u8] = b"hello world";
+    let mut w = Vec::new();
+    assert_eq!(copy_n(&mut r, &mut w, 5).unwrap(), 5);
+    assert_eq!(w, b"hello");
+    assert_eq!(r, b" world");
+
+    assert_eq!(copy_n(&mut r, &mut w, 100).unwrap(), 6);
+    assert_eq!(w, b"hello world");
+    assert_eq!(copy_n(&mut r, &mut w, 100).unwrap(), 0);
+}
+
+#[test]
+fn splice_files() {
+    use crate::fs::File;
+    use crate::sys_common::io::test::tmpdir;
+
+    let tmpdir = tmpdir();
+    let source_path = tmpdir.join("source");
+    let sink_path = tmpdir.join("sink");
+    crate::fs::write(&source_path, b"0123456789").unwrap();
+
+    let mut source = File::open(&source_path).unwrap();
+    let mut sink = File::create(&sink_path).unwrap();
+    let (bytes, _) = splice(&mut source, &mut sink, 4).unwrap();
+    assert_eq!(bytes, 4);
+    let (bytes, _) = splice(&mut source, &mut sink, u64::MAX).unwrap();
+    assert_eq!(bytes, 6);
+    drop(sink);
+    assert_eq!(crate::fs::read(&sink_path).unwrap(), b"0123456789");
+}
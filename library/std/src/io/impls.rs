@@ -5,7 +5,9 @@
 use crate::cmp;
 use crate::collections::VecDeque;
 use crate::fmt;
-use crate::io::{self, BorrowedCursor, BufRead, IoSlice, IoSliceMut, Read, Seek, SeekFrom, Write};
+use crate::io::{
+    self, BorrowedBuf, BorrowedCursor, BufRead, IoSlice, IoSliceMut, Read, Seek, SeekFrom, Write,
+};
 use crate::mem;
 use crate::str;
 
@@ -445,26 +447,48 @@ fn flush(&mut self) -> io::Result<()> {
 /// Read is implemented for `VecDeque<u8>` by consuming bytes from the front of the `VecDeque`.
 #[stable(feature = "vecdeque_read_write", since = "1.63.0")]
 impl<A: Allocator> Read for VecDeque<u8, A> {
-    /// Fill `buf` with the contents of the "front" slice as returned by
-    /// [`as_slices`][`VecDeque::as_slices`]. If the contained byte slices of the `VecDeque` are
-    /// discontiguous, multiple calls to `read` will be needed to read the entire content.
+    /// Fill `buf` with the contents of the `VecDeque`, starting with the "front" slice as
+    /// returned by [`as_slices`][`VecDeque::as_slices`] and continuing with the "back" slice, so a
+    /// single call reads as much as fits even if the contents wrap around the ring buffer.
     #[inline]
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        let (ref mut front, _) = self.as_slices();
+        let (ref mut front, ref mut back) = self.as_slices();
         let n = Read::read(front, buf)?;
+        let n = n + Read::read(back, &mut buf[n..])?;
         self.drain(..n);
         Ok(n)
     }
 
     #[inline]
-    fn read_buf(&mut self, cursor: BorrowedCursor<'_>) -> io::Result<()> {
-        let (ref mut front, _) = self.as_slices();
-        let n = cmp::min(cursor.capacity(), front.len());
-        Read::read_buf(front, cursor)?;
+    fn read_buf(&mut self, mut cursor: BorrowedCursor<'_>) -> io::Result<()> {
+        let (ref mut front, ref mut back) = self.as_slices();
+        let n = cmp::min(cursor.capacity(), front.len() + back.len());
+        Read::read_buf(front, cursor.reborrow())?;
+        Read::read_buf(back, cursor)?;
         self.drain(..n);
         Ok(())
     }
 
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        let (ref mut front, ref mut back) = self.as_slices();
+        let mut n = 0;
+        for buf in bufs {
+            let amt = Read::read(front, buf)?;
+            n += amt + Read::read(back, &mut buf[amt..])?;
+            if front.is_empty() && back.is_empty() {
+                break;
+            }
+        }
+        self.drain(..n);
+        Ok(n)
+    }
+
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        true
+    }
+
     #[inline]
     fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
         // The total len is known upfront so we can reserve it in a single call.
@@ -491,6 +515,8 @@ impl<A: Allocator> BufRead for VecDeque<u8, A> {
     /// Returns the contents of the "front" slice as returned by
     /// [`as_slices`][`VecDeque::as_slices`]. If the contained byte slices of the `VecDeque` are
     /// discontiguous, multiple calls to `fill_buf` will be needed to read the entire content.
+    /// Call [`make_contiguous`][`VecDeque::make_contiguous`] first if the whole content has to be
+    /// visible at once, e.g. to search for a delimiter spanning the wrap-around point.
     #[inline]
     fn fill_buf(&mut self) -> io::Result<&[u8]> {
         let (front, _) = self.as_slices();
@@ -503,6 +529,76 @@ fn consume(&mut self, amt: usize) {
     }
 }
 
+/// Reads at most `n` bytes from `reader` and appends them to the back of `deque`.
+///
+/// Room for `n` more bytes is reserved up front and a single read is then issued straight into
+/// the deque's [spare capacity][`VecDeque::spare_capacity_mut`], without going through an
+/// intermediate buffer. Usually only the first, contiguous part of that capacity is read into,
+/// using [`read_buf`][`Read::read_buf`] so that the memory does not have to be zeroed first.
+/// If the capacity wraps around the end of the ring buffer, `reader` supports vectored reads and
+/// `n` is small, both parts are filled by one [`read_vectored`][`Read::read_vectored`] call
+/// instead. That call only accepts initialized buffers, so the bytes are zeroed first, which is
+/// cheaper than a second read only as long as there are few of them.
+///
+/// Returns the number of bytes appended. Like [`Read::read`], this may be less than `n`, and
+/// `Ok(0)` for a non-zero `n` means `reader` has reached end of file. If the read fails, the
+/// contents of the deque are left unchanged.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(io_read_into_deque)]
+///
+/// use std::collections::VecDeque;
+/// use std::io::{self, BufRead};
+///
+/// let mut input: &[u8] = b"hello\nworld\n";
+/// let mut deque = VecDeque::new();
+///
+/// while io::read_into_deque(&mut input, &mut deque, 4)? != 0 {}
+///
+/// let mut line = String::new();
+/// deque.read_line(&mut line)?;
+/// assert_eq!(line, "hello\n");
+/// assert_eq!(deque, b"world\n");
+/// # io::Result::Ok(())
+/// ```
+#[unstable(feature = "io_read_into_deque", issue = "none")]
+pub fn read_into_deque<R: Read + ?Sized, A: Allocator>(
+    reader: &mut R,
+    deque: &mut VecDeque<u8, A>,
+    n: usize,
+) -> io::Result<usize> {
+    deque.try_reserve(n)?;
+    let len = deque.len();
+    let (first, second) = deque.spare_capacity_mut();
+    let first_len = cmp::min(n, first.len());
+    let second_len = cmp::min(n - first_len, second.len());
+    let mut first: BorrowedBuf<'_> = (&mut first[..first_len]).into();
+    let mut second: BorrowedBuf<'_> = (&mut second[..second_len]).into();
+
+    if second_len == 0 || n > io::DEFAULT_BUF_SIZE || !reader.is_read_vectored() {
+        reader.read_buf(first.unfilled())?;
+    } else {
+        let mut first_cursor = first.unfilled();
+        let mut second_cursor = second.unfilled();
+        let amt = reader.read_vectored(&mut [
+            IoSliceMut::new(first_cursor.ensure_init().init_mut()),
+            IoSliceMut::new(second_cursor.ensure_init().init_mut()),
+        ])?;
+        // `advance` panics if the reader claims to have read more than it was given.
+        let first_amt = cmp::min(amt, first_len);
+        first_cursor.advance(first_amt);
+        second_cursor.advance(amt - first_amt);
+    }
+
+    let amt = first.len() + second.len();
+    // SAFETY: `amt` bytes of the spare capacity have been filled in order, first slice first,
+    // and the reservation above guarantees `len + amt <= capacity`.
+    unsafe { deque.set_len(len + amt) };
+    Ok(amt)
+}
+
 /// Write is implemented for `VecDeque<u8>` by appending to the `VecDeque`, growing it as needed.
 #[stable(feature = "vecdeque_read_write", since = "1.63.0")]
 impl<A: Allocator> Write for VecDeque<u8, A> {
@@ -308,17 +308,25 @@
 
 #[stable(feature = "bufwriter_into_parts", since = "1.56.0")]
 pub use self::buffered::WriterPanicked;
+#[unstable(feature = "io_splice", issue = "none")]
+pub use self::copy::{copy_n, splice, AsSpliceHandle, SpliceHandle, SpliceMethod};
 #[unstable(feature = "raw_os_error_ty", issue = "107792")]
 pub use self::error::RawOsError;
+#[unstable(feature = "io_read_into_deque", issue = "none")]
+pub use self::impls::read_into_deque;
 pub(crate) use self::stdio::attempt_print_to_stderr;
 #[stable(feature = "is_terminal", since = "1.70.0")]
 pub use self::stdio::IsTerminal;
+#[unstable(feature = "raw_stderr", issue = "none")]
+pub use self::stdio::RawStderr;
 #[unstable(feature = "print_internals", issue = "none")]
 #[doc(hidden)]
-pub use self::stdio::{_eprint, _print};
+pub use self::stdio::{_eprint, _print, _raw_eprint};
 #[unstable(feature = "internal_output_capture", issue = "none")]
 #[doc(no_inline, hidden)]
-pub use self::stdio::{set_output_capture, try_set_output_capture};
+pub use self::stdio::{
+    set_error_capture, set_output_capture, try_set_error_capture, try_set_output_capture,
+};
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::{
     buffered::{BufReader, BufWriter, IntoInnerError, LineWriter},
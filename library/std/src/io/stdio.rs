@@ -6,16 +6,18 @@
 use crate::io::prelude::*;
 
 use crate::cell::{Cell, RefCell};
+use crate::cmp;
 use crate::fmt;
 use crate::fs::File;
 use crate::io::{
     self, BorrowedCursor, BufReader, IoSlice, IoSliceMut, LineWriter, Lines, SpecReadByte,
 };
+use crate::mem;
 use crate::panic::{RefUnwindSafe, UnwindSafe};
 use crate::sync::atomic::{AtomicBool, Ordering};
 use crate::sync::{Arc, Mutex, MutexGuard, OnceLock, ReentrantLock, ReentrantLockGuard};
 use crate::sys::stdio;
-use crate::thread::AccessError;
+use crate::thread::{AccessError, LocalKey};
 
 type LocalStream = Arc<Mutex<Vec<u8>>>;
 
@@ -24,13 +26,19 @@
     static OUTPUT_CAPTURE: Cell<Option<LocalStream>> = {
         Cell::new(None)
     }
+
+    /// Used by the test crate to keep a separate copy of the output of the
+    /// eprint macros and panics, which still goes to `OUTPUT_CAPTURE` as well.
+    static ERROR_CAPTURE: Cell<Option<LocalStream>> = {
+        Cell::new(None)
+    }
 }
 
-/// Flag to indicate OUTPUT_CAPTURE is used.
+/// Flag to indicate OUTPUT_CAPTURE or ERROR_CAPTURE is used.
 ///
-/// If it is None and was never set on any thread, this flag is set to false,
-/// and OUTPUT_CAPTURE can be safely ignored on all threads, saving some time
-/// and memory registering an unused thread local.
+/// If they are None and were never set on any thread, this flag is set to false,
+/// and OUTPUT_CAPTURE and ERROR_CAPTURE can be safely ignored on all threads,
+/// saving some time and memory registering unused thread locals.
 ///
 /// Note about memory ordering: This contains information about whether a
 /// thread local variable might be in use. Although this is a global flag, the
@@ -1056,6 +1064,154 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     }
 }
 
+/// A handle to the standard error stream of a process which writes directly
+/// to the operating system.
+///
+/// Unlike [`Stderr`], this handle takes no lock, does no buffering and never
+/// allocates, so it keeps working where [`Stderr`] cannot be used, such as in
+/// signal handlers or while another thread holds the [`Stderr`] lock. On Unix,
+/// every call to [`write`](Write::write) is a single `write(2)` system call,
+/// which is async-signal-safe.
+///
+/// [`write_fmt`](Write::write_fmt) formats into a fixed-size buffer on the
+/// stack first, so that short messages are written with a single system call.
+/// Longer messages are written in several pieces. Formatting itself only
+/// stays async-signal-safe if the formatting trait implementations involved
+/// are, which is the case for integers, strings and the other primitive types.
+///
+/// Since nothing is synchronized, output written through a `RawStderr` can
+/// interleave with output from other threads or from [`Stderr`].
+///
+/// The [`raw_eprintln!`](crate::raw_eprintln) macro prints through this
+/// handle.
+///
+/// ### Note: Windows Portability Considerations
+///
+/// When operating in a console, the Windows implementation of this stream does not support
+/// non-UTF-8 byte sequences. Attempting to write bytes that are not valid UTF-8 will return
+/// an error.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(raw_stderr)]
+/// use std::io::{RawStderr, Write};
+///
+/// // Safe to call from a signal handler.
+/// fn report_fatal_signal(signal: i32) {
+///     let _ = writeln!(RawStderr::new(), "fatal signal {signal}, exiting");
+/// }
+/// # report_fatal_signal(11);
+/// ```
+#[unstable(feature = "raw_stderr", issue = "none")]
+pub struct RawStderr {
+    inner: StderrRaw,
+}
+
+impl RawStderr {
+    /// Creates a new handle to the standard error stream.
+    #[unstable(feature = "raw_stderr", issue = "none")]
+    #[must_use]
+    pub const fn new() -> RawStderr {
+        RawStderr { inner: stderr_raw() }
+    }
+}
+
+#[unstable(feature = "raw_stderr", issue = "none")]
+impl Default for RawStderr {
+    fn default() -> RawStderr {
+        RawStderr::new()
+    }
+}
+
+#[unstable(feature = "raw_stderr", issue = "none")]
+impl fmt::Debug for RawStderr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawStderr").finish_non_exhaustive()
+    }
+}
+
+#[unstable(feature = "raw_stderr", issue = "none")]
+impl Write for RawStderr {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        self.inner.write_vectored(bufs)
+    }
+    #[inline]
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.inner.write_all(buf)
+    }
+    fn write_all_vectored(&mut self, bufs: &mut [IoSlice<'_>]) -> io::Result<()> {
+        self.inner.write_all_vectored(bufs)
+    }
+    fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> io::Result<()> {
+        write_fmt_on_stack(&mut self.inner, args)
+    }
+}
+
+/// The size of the stack buffer used by [`write_fmt_on_stack`].
+const STACK_FORMAT_BUF_SIZE: usize = 1024;
+
+/// Formats `args` into a buffer on the stack and writes it to `out` whenever
+/// the buffer fills up, and once more at the end.
+///
+/// Unlike the default `Write::write_fmt` this does not panic if a formatting
+/// trait implementation returns an error, as it is meant to be used in
+/// contexts such as signal handlers where panicking is not an option.
+fn write_fmt_on_stack<W: Write + ?Sized>(out: &mut W, args: fmt::Arguments<'_>) -> io::Result<()> {
+    struct StackBuffer<'a, W: ?Sized> {
+        out: &'a mut W,
+        buf: [u8; STACK_FORMAT_BUF_SIZE],
+        len: usize,
+        error: io::Result<()>,
+    }
+
+    impl<W: Write + ?Sized> StackBuffer<'_, W> {
+        fn flush_buf(&mut self) -> io::Result<()> {
+            let len = mem::take(&mut self.len);
+            self.out.write_all(&self.buf[..len])
+        }
+    }
+
+    impl<W: Write + ?Sized> fmt::Write for StackBuffer<'_, W> {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let mut bytes = s.as_bytes();
+            while !bytes.is_empty() {
+                if self.len == self.buf.len() {
+                    if let Err(e) = self.flush_buf() {
+                        self.error = Err(e);
+                        return Err(fmt::Error);
+                    }
+                }
+                let n = cmp::min(bytes.len(), self.buf.len() - self.len);
+                self.buf[self.len..][..n].copy_from_slice(&bytes[..n]);
+                self.len += n;
+                bytes = &bytes[n..];
+            }
+            Ok(())
+        }
+    }
+
+    let mut buffer = StackBuffer { out, buf: [0; STACK_FORMAT_BUF_SIZE], len: 0, error: Ok(()) };
+    match fmt::write(&mut buffer, args) {
+        Ok(()) => buffer.flush_buf(),
+        Err(..) if buffer.error.is_err() => buffer.error,
+        Err(..) => {
+            // Still write out what was formatted before the error.
+            buffer.flush_buf()?;
+            Err(io::const_io_error!(io::ErrorKind::Uncategorized, "formatter error"))
+        }
+    }
+}
+
 /// Sets the thread-local output capture buffer and returns the old one.
 #[unstable(
     feature = "internal_output_capture",
@@ -1092,8 +1248,49 @@ pub fn try_set_output_capture(
     OUTPUT_CAPTURE.try_with(move |slot| slot.replace(sink))
 }
 
+/// Sets the thread-local capture buffer for stderr and returns the old one.
+///
+/// While it is set, the output of the eprint macros and panics is copied to
+/// this buffer. It still goes to the one set with [`set_output_capture`] too,
+/// so that buffer keeps all output in the order it was written.
+#[unstable(
+    feature = "internal_output_capture",
+    reason = "this function is meant for use in the test crate \
+        and may disappear in the future",
+    issue = "none"
+)]
+#[doc(hidden)]
+pub fn set_error_capture(sink: Option<LocalStream>) -> Option<LocalStream> {
+    try_set_error_capture(sink).expect(
+        "cannot access a Thread Local Storage value \
+         during or after destruction",
+    )
+}
+
+/// Tries to set the thread-local capture buffer for stderr and returns the
+/// old one. This may fail once thread-local destructors are called. It's used
+/// in panic handling instead of `set_error_capture`.
+#[unstable(
+    feature = "internal_output_capture",
+    reason = "this function is meant for use in the test crate \
+    and may disappear in the future",
+    issue = "none"
+)]
+#[doc(hidden)]
+pub fn try_set_error_capture(
+    sink: Option<LocalStream>,
+) -> Result<Option<LocalStream>, AccessError> {
+    if sink.is_none() && !OUTPUT_CAPTURE_USED.load(Ordering::Relaxed) {
+        // ERROR_CAPTURE is definitely None since OUTPUT_CAPTURE_USED is false.
+        return Ok(None);
+    }
+    OUTPUT_CAPTURE_USED.store(true, Ordering::Relaxed);
+    ERROR_CAPTURE.try_with(move |slot| slot.replace(sink))
+}
+
 /// Write `args` to the capture buffer if enabled and possible, or `global_s`
-/// otherwise. `label` identifies the stream in a panic message.
+/// otherwise. `capture` writes to the capture buffer of the stream, and `label`
+/// identifies the stream in a panic message.
 ///
 /// This function is used to print error messages, so it takes extra
 /// care to avoid causing a panic when `OUTPUT_CAPTURE` is unusable.
@@ -1105,11 +1302,15 @@ pub fn try_set_output_capture(
 ///
 /// Writing to non-blocking stdout/stderr can cause an error, which will lead
 /// this function to panic.
-fn print_to<T>(args: fmt::Arguments<'_>, global_s: fn() -> T, label: &str)
-where
+fn print_to<T>(
+    args: fmt::Arguments<'_>,
+    global_s: fn() -> T,
+    label: &str,
+    capture: fn(fmt::Arguments<'_>) -> bool,
+) where
     T: Write,
 {
-    if print_to_buffer_if_capture_used(args) {
+    if capture(args) {
         // Successfully wrote to capture buffer.
         return;
     }
@@ -1120,23 +1321,36 @@ fn print_to<T>(args: fmt::Arguments<'_>, global_s: fn() -> T, label: &str)
 }
 
 fn print_to_buffer_if_capture_used(args: fmt::Arguments<'_>) -> bool {
-    OUTPUT_CAPTURE_USED.load(Ordering::Relaxed)
-        && OUTPUT_CAPTURE.try_with(|s| {
-            // Note that we completely remove a local sink to write to in case
-            // our printing recursively panics/prints, so the recursive
-            // panic/print goes to the global sink instead of our local sink.
-            s.take().map(|w| {
-                let _ = w.lock().unwrap_or_else(|e| e.into_inner()).write_fmt(args);
-                s.set(Some(w));
-            })
-        }) == Ok(Some(()))
+    OUTPUT_CAPTURE_USED.load(Ordering::Relaxed) && print_to_capture(&OUTPUT_CAPTURE, args)
+}
+
+fn eprint_to_buffer_if_capture_used(args: fmt::Arguments<'_>) -> bool {
+    OUTPUT_CAPTURE_USED.load(Ordering::Relaxed) && {
+        print_to_capture(&ERROR_CAPTURE, args);
+        print_to_capture(&OUTPUT_CAPTURE, args)
+    }
+}
+
+fn print_to_capture(
+    capture: &'static LocalKey<Cell<Option<LocalStream>>>,
+    args: fmt::Arguments<'_>,
+) -> bool {
+    capture.try_with(|s| {
+        // Note that we completely remove a local sink to write to in case
+        // our printing recursively panics/prints, so the recursive
+        // panic/print goes to the global sink instead of our local sink.
+        s.take().map(|w| {
+            let _ = w.lock().unwrap_or_else(|e| e.into_inner()).write_fmt(args);
+            s.set(Some(w));
+        })
+    }) == Ok(Some(()))
 }
 
 /// Used by impl Termination for Result to print error after `main` or a test
 /// has returned. Should avoid panicking, although we can't help it if one of
 /// the Display impls inside args decides to.
 pub(crate) fn attempt_print_to_stderr(args: fmt::Arguments<'_>) {
-    if print_to_buffer_if_capture_used(args) {
+    if eprint_to_buffer_if_capture_used(args) {
         return;
     }
 
@@ -1225,7 +1439,7 @@ fn is_terminal(&self) -> bool {
 #[doc(hidden)]
 #[cfg(not(test))]
 pub fn _print(args: fmt::Arguments<'_>) {
-    print_to(args, stdout, "stdout");
+    print_to(args, stdout, "stdout", print_to_buffer_if_capture_used);
 }
 
 #[unstable(
@@ -1236,7 +1450,18 @@ pub fn _print(args: fmt::Arguments<'_>) {
 #[doc(hidden)]
 #[cfg(not(test))]
 pub fn _eprint(args: fmt::Arguments<'_>) {
-    print_to(args, stderr, "stderr");
+    print_to(args, stderr, "stderr", eprint_to_buffer_if_capture_used);
+}
+
+#[unstable(
+    feature = "print_internals",
+    reason = "implementation detail which may disappear or be replaced at any time",
+    issue = "none"
+)]
+#[doc(hidden)]
+pub fn _raw_eprint(args: fmt::Arguments<'_>) {
+    // There is nobody to report errors to when this is used from a crash handler.
+    let _ = RawStderr::new().write_fmt(args);
 }
 
 #[cfg(test)]
@@ -164,3 +164,58 @@ enum State {
         [Start1, Acquire1, Start2, Release1, Acquire2, Release2, Acquire1, Release1]
     );
 }
+
+#[test]
+fn raw_stderr_write_fmt_long_message() {
+    let message = "0123456789".repeat(300);
+    let mut out = Vec::new();
+    write_fmt_on_stack(&mut out, format_args!("{message} and {}", 42)).unwrap();
+    assert_eq!(out, format!("{message} and 42").into_bytes());
+}
+
+#[test]
+fn raw_stderr_write_fmt_single_write() {
+    struct RecordWrites(Vec<usize>);
+
+    impl Write for RecordWrites {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.push(buf.len());
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut out = RecordWrites(Vec::new());
+    write_fmt_on_stack(&mut out, format_args!("signal {} at {:#x}\n", 11, 0xdead)).unwrap();
+    assert_eq!(out.0, [20]);
+
+    out.0.clear();
+    let long = "x".repeat(STACK_FORMAT_BUF_SIZE + 1);
+    write_fmt_on_stack(&mut out, format_args!("{long}")).unwrap();
+    assert_eq!(out.0, [STACK_FORMAT_BUF_SIZE, 1]);
+}
+
+#[test]
+fn raw_stderr_write_fmt_formatter_error() {
+    struct Fail;
+
+    impl fmt::Display for Fail {
+        fn fmt(&self, _: &mut fmt::Formatter<'_>) -> fmt::Result {
+            Err(fmt::Error)
+        }
+    }
+
+    let mut out = Vec::new();
+    let err = write_fmt_on_stack(&mut out, format_args!("before {Fail}")).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::Uncategorized);
+    assert_eq!(out, b"before ");
+}
+
+#[test]
+fn raw_stderr_write() {
+    let mut stderr = RawStderr::new();
+    stderr.write_all(b"").unwrap();
+    stderr.flush().unwrap();
+}
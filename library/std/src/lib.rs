@@ -369,6 +369,7 @@
 #![feature(slice_concat_trait)]
 #![feature(thin_box)]
 #![feature(try_reserve_kind)]
+#![feature(vec_deque_spare_capacity)]
 #![feature(vec_into_raw_parts)]
 // tidy-alphabetical-end
 //
@@ -222,6 +222,47 @@ macro_rules! eprintln {
     }};
 }
 
+/// Prints to the standard error, with a newline, without taking any locks or
+/// allocating.
+///
+/// Equivalent to the [`eprintln!`] macro, except that output goes through
+/// [`io::RawStderr`]: it is formatted into a fixed-size buffer on the stack
+/// and written straight to the standard error stream. This makes it usable
+/// where [`eprintln!`] could deadlock or allocate, such as in signal handlers,
+/// allocators, or panic and crash hooks.
+///
+/// Output from this macro is not captured by the test harness, and messages
+/// longer than the buffer may interleave with output from other threads.
+///
+/// See [the formatting documentation in `std::fmt`](../std/fmt/index.html)
+/// for details of the macro argument syntax.
+///
+/// [`io::RawStderr`]: crate::io::RawStderr
+///
+/// # Panics
+///
+/// Unlike [`eprintln!`], this macro never panics. Errors writing to the
+/// standard error stream are ignored.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(raw_stderr)]
+///
+/// raw_eprintln!("fatal: out of memory allocating {} bytes", 4096);
+/// ```
+#[macro_export]
+#[unstable(feature = "raw_stderr", issue = "none")]
+#[allow_internal_unstable(print_internals, format_args_nl)]
+macro_rules! raw_eprintln {
+    () => {
+        $crate::io::_raw_eprint($crate::format_args!("\n"))
+    };
+    ($($arg:tt)*) => {{
+        $crate::io::_raw_eprint($crate::format_args_nl!($($arg)*));
+    }};
+}
+
 /// Prints and returns the value of a given expression for quick and dirty
 /// debugging.
 ///
@@ -0,0 +1,245 @@
+#[cfg(all(test, not(any(target_os = "emscripten", target_os = "xous"))))]
+mod tests;
+
+use crate::io;
+use crate::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs, UdpSocket};
+use crate::sys_common::net as net_imp;
+use crate::sys_common::FromInner;
+use crate::time::Duration;
+
+/// A builder for configuring a socket before it is bound or connected.
+///
+/// Some socket options only have an effect if they are set before the socket
+/// is bound, such as `SO_REUSEPORT` or `IPV6_V6ONLY`. [`TcpListener::bind`],
+/// [`TcpStream::connect`] and [`UdpSocket::bind`] do not give a chance to set
+/// them, so this type collects the options first and then creates, configures
+/// and binds or connects the socket in one go.
+///
+/// Options which are not set keep the same defaults as the constructors on
+/// [`TcpListener`], [`TcpStream`] and [`UdpSocket`]. Options which the platform
+/// does not support make the final call fail with an error of kind
+/// [`Unsupported`](io::ErrorKind::Unsupported).
+///
+/// # Examples
+///
+/// Bind a listener which shares its port with other processes:
+///
+/// ```no_run
+/// #![feature(socket_builder)]
+/// use std::net::SocketBuilder;
+///
+/// fn main() -> std::io::Result<()> {
+///     let listener = SocketBuilder::new()
+///         .reuse_port(true)
+///         .recv_buffer_size(1 << 20)
+///         .backlog(1024)
+///         .listen("0.0.0.0:8080")?;
+///     for stream in listener.incoming() {
+///         let _stream = stream?;
+///         // ...
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Debug)]
+#[unstable(feature = "socket_builder", issue = "none")]
+pub struct SocketBuilder(net_imp::SocketBuilder);
+
+impl SocketBuilder {
+    /// Creates a builder with no options set.
+    #[unstable(feature = "socket_builder", issue = "none")]
+    #[must_use]
+    pub fn new() -> SocketBuilder {
+        SocketBuilder(net_imp::SocketBuilder::new())
+    }
+
+    /// Sets the `SO_REUSEADDR` option.
+    ///
+    /// If this is not called, [`listen`] enables the option on all platforms
+    /// except Windows, just like [`TcpListener::bind`].
+    ///
+    /// On Windows this option lets another socket bind to an address which
+    /// is actively in use, so only enable it there if that is intended.
+    ///
+    /// [`listen`]: SocketBuilder::listen
+    #[unstable(feature = "socket_builder", issue = "none")]
+    pub fn reuse_address(&mut self, reuse: bool) -> &mut Self {
+        self.0.reuse_address(reuse);
+        self
+    }
+
+    /// Sets the `SO_REUSEPORT` option, which lets several sockets bind to the
+    /// same address and port.
+    ///
+    /// This is only supported on Unix platforms which have the option, such
+    /// as Linux, Android, the BSDs and Apple platforms.
+    #[unstable(feature = "socket_builder", issue = "none")]
+    pub fn reuse_port(&mut self, reuse: bool) -> &mut Self {
+        self.0.reuse_port(reuse);
+        self
+    }
+
+    /// Sets the `IPV6_V6ONLY` option.
+    ///
+    /// If this is set to `true`, a socket bound to an IPv6 address only
+    /// accepts IPv6 traffic; otherwise it can also be used with IPv4-mapped
+    /// addresses. The option is ignored for sockets bound to an IPv4 address.
+    #[unstable(feature = "socket_builder", issue = "none")]
+    pub fn only_v6(&mut self, only_v6: bool) -> &mut Self {
+        self.0.only_v6(only_v6);
+        self
+    }
+
+    /// Sets the size of the socket's send buffer (`SO_SNDBUF`).
+    ///
+    /// The operating system may round or otherwise adjust the value.
+    #[unstable(feature = "socket_builder", issue = "none")]
+    pub fn send_buffer_size(&mut self, size: usize) -> &mut Self {
+        self.0.send_buffer_size(size);
+        self
+    }
+
+    /// Sets the size of the socket's receive buffer (`SO_RCVBUF`).
+    ///
+    /// The operating system may round or otherwise adjust the value.
+    #[unstable(feature = "socket_builder", issue = "none")]
+    pub fn recv_buffer_size(&mut self, size: usize) -> &mut Self {
+        self.0.recv_buffer_size(size);
+        self
+    }
+
+    /// Enables or disables TCP keepalive probes (`SO_KEEPALIVE`).
+    #[unstable(feature = "socket_builder", issue = "none")]
+    pub fn keepalive(&mut self, keepalive: bool) -> &mut Self {
+        self.0.keepalive(keepalive);
+        self
+    }
+
+    /// Sets how long a connection has to be idle before the first keepalive
+    /// probe is sent.
+    ///
+    /// The time is rounded down to whole seconds. This corresponds to
+    /// `TCP_KEEPIDLE`, or `TCP_KEEPALIVE` on Apple platforms.
+    #[unstable(feature = "socket_builder", issue = "none")]
+    pub fn keepalive_time(&mut self, time: Duration) -> &mut Self {
+        self.0.keepalive_time(time);
+        self
+    }
+
+    /// Sets the time between keepalive probes (`TCP_KEEPINTVL`).
+    ///
+    /// The interval is rounded down to whole seconds.
+    #[unstable(feature = "socket_builder", issue = "none")]
+    pub fn keepalive_interval(&mut self, interval: Duration) -> &mut Self {
+        self.0.keepalive_interval(interval);
+        self
+    }
+
+    /// Sets the `TCP_NODELAY` option.
+    ///
+    /// See [`TcpStream::set_nodelay`].
+    #[unstable(feature = "socket_builder", issue = "none")]
+    pub fn nodelay(&mut self, nodelay: bool) -> &mut Self {
+        self.0.nodelay(nodelay);
+        self
+    }
+
+    /// Sets the `IP_TTL` option.
+    ///
+    /// See [`TcpStream::set_ttl`].
+    #[unstable(feature = "socket_builder", issue = "none")]
+    pub fn ttl(&mut self, ttl: u32) -> &mut Self {
+        self.0.ttl(ttl);
+        self
+    }
+
+    /// Puts the socket into nonblocking mode once it has been bound or
+    /// connected.
+    ///
+    /// Connecting itself still blocks.
+    #[unstable(feature = "socket_builder", issue = "none")]
+    pub fn nonblocking(&mut self, nonblocking: bool) -> &mut Self {
+        self.0.nonblocking(nonblocking);
+        self
+    }
+
+    /// Sets the local address that [`connect`] binds the socket to before
+    /// connecting.
+    ///
+    /// [`connect`]: SocketBuilder::connect
+    #[unstable(feature = "socket_builder", issue = "none")]
+    pub fn local_addr(&mut self, addr: SocketAddr) -> &mut Self {
+        self.0.local_addr(addr);
+        self
+    }
+
+    /// Sets the maximum length of the queue of pending connections used by
+    /// [`listen`].
+    ///
+    /// The operating system may cap this value.
+    ///
+    /// [`listen`]: SocketBuilder::listen
+    #[unstable(feature = "socket_builder", issue = "none")]
+    pub fn backlog(&mut self, backlog: u32) -> &mut Self {
+        self.0.backlog(backlog);
+        self
+    }
+
+    /// Creates a TCP socket with the configured options, binds it to `addr`
+    /// and starts listening on it.
+    ///
+    /// If `addr` yields multiple addresses, each is tried in turn like
+    /// [`TcpListener::bind`] does.
+    #[unstable(feature = "socket_builder", issue = "none")]
+    pub fn listen<A: ToSocketAddrs>(&self, addr: A) -> io::Result<TcpListener> {
+        super::each_addr(addr, |addr| self.0.listen(addr)).map(TcpListener::from_inner)
+    }
+
+    /// Creates a TCP socket with the configured options and connects it to
+    /// `addr`.
+    ///
+    /// If `addr` yields multiple addresses, each is tried in turn like
+    /// [`TcpStream::connect`] does.
+    #[unstable(feature = "socket_builder", issue = "none")]
+    pub fn connect<A: ToSocketAddrs>(&self, addr: A) -> io::Result<TcpStream> {
+        super::each_addr(addr, |addr| self.0.connect(addr)).map(TcpStream::from_inner)
+    }
+
+    /// Creates a TCP socket with the configured options and connects it to
+    /// `addr`, giving up after `timeout`.
+    ///
+    /// See [`TcpStream::connect_timeout`].
+    #[unstable(feature = "socket_builder", issue = "none")]
+    pub fn connect_timeout(&self, addr: &SocketAddr, timeout: Duration) -> io::Result<TcpStream> {
+        self.0.connect_timeout(addr, timeout).map(TcpStream::from_inner)
+    }
+
+    /// Creates a UDP socket with the configured options and binds it to
+    /// `addr`.
+    ///
+    /// If `addr` yields multiple addresses, each is tried in turn like
+    /// [`UdpSocket::bind`] does.
+    ///
+    /// # Errors
+    ///
+    /// The TCP-only options [`keepalive`], [`keepalive_time`],
+    /// [`keepalive_interval`] and [`nodelay`] cannot be applied to a UDP
+    /// socket; if any of them was set, this fails with an error of kind
+    /// [`InvalidInput`](io::ErrorKind::InvalidInput).
+    ///
+    /// [`keepalive`]: SocketBuilder::keepalive
+    /// [`keepalive_time`]: SocketBuilder::keepalive_time
+    /// [`keepalive_interval`]: SocketBuilder::keepalive_interval
+    /// [`nodelay`]: SocketBuilder::nodelay
+    #[unstable(feature = "socket_builder", issue = "none")]
+    pub fn bind_udp<A: ToSocketAddrs>(&self, addr: A) -> io::Result<UdpSocket> {
+        super::each_addr(addr, |addr| self.0.bind_udp(addr)).map(UdpSocket::from_inner)
+    }
+}
+
+#[unstable(feature = "socket_builder", issue = "none")]
+impl Default for SocketBuilder {
+    fn default() -> SocketBuilder {
+        SocketBuilder::new()
+    }
+}
@@ -0,0 +1,138 @@
+use crate::io::prelude::*;
+use crate::io::ErrorKind;
+use crate::net::test::{next_test_ip4, next_test_ip6};
+use crate::net::*;
+use crate::sys_common::AsInner;
+use crate::thread;
+use crate::time::Duration;
+
+fn each_ip(f: &mut dyn FnMut(SocketAddr)) {
+    f(next_test_ip4());
+    f(next_test_ip6());
+}
+
+macro_rules! t {
+    ($e:expr) => {
+        match $e {
+            Ok(t) => t,
+            Err(e) => panic!("received error for `{}`: {}", stringify!($e), e),
+        }
+    };
+}
+
+#[test]
+fn listen_and_connect() {
+    each_ip(&mut |addr| {
+        let listener = t!(SocketBuilder::new()
+            .reuse_address(true)
+            .recv_buffer_size(64 * 1024)
+            .send_buffer_size(64 * 1024)
+            .backlog(16)
+            .listen(&addr));
+        assert_eq!(t!(listener.local_addr()), addr);
+
+        let t = thread::spawn(move || {
+            let mut stream = t!(SocketBuilder::new()
+                .nodelay(true)
+                .keepalive(true)
+                .keepalive_time(Duration::from_secs(30))
+                .keepalive_interval(Duration::from_secs(5))
+                .connect(&addr));
+            assert!(t!(stream.nodelay()));
+            t!(stream.write_all(&[99]));
+        });
+
+        let mut stream = t!(listener.accept()).0;
+        let mut buf = [0];
+        t!(stream.read_exact(&mut buf));
+        assert_eq!(buf[0], 99);
+        t.join().unwrap();
+    })
+}
+
+#[test]
+fn connect_from_local_addr() {
+    each_ip(&mut |addr| {
+        let listener = t!(SocketBuilder::new().listen(&addr));
+        let local = if addr.is_ipv4() { next_test_ip4() } else { next_test_ip6() };
+        let stream = t!(SocketBuilder::new().local_addr(local).connect(&addr));
+        assert_eq!(t!(stream.local_addr()), local);
+        let (_, peer) = t!(listener.accept());
+        assert_eq!(peer, local);
+    })
+}
+
+#[test]
+fn ttl() {
+    let addr = next_test_ip4();
+    let listener = t!(SocketBuilder::new().ttl(42).listen(&addr));
+    assert_eq!(t!(listener.ttl()), 42);
+    let socket = t!(SocketBuilder::new().ttl(7).bind_udp(next_test_ip4()));
+    assert_eq!(t!(socket.ttl()), 7);
+}
+
+#[test]
+fn only_v6() {
+    let addr = next_test_ip6();
+    let listener = t!(SocketBuilder::new().only_v6(true).listen(&addr));
+    // `TcpListener::only_v6` is deprecated, so read `IPV6_V6ONLY` back through the sys layer.
+    assert!(t!(listener.as_inner().only_v6()));
+}
+
+#[test]
+fn nonblocking_listener() {
+    each_ip(&mut |addr| {
+        let listener = t!(SocketBuilder::new().nonblocking(true).listen(&addr));
+        match listener.accept() {
+            Ok(_) => panic!("expected error"),
+            Err(e) => assert_eq!(e.kind(), ErrorKind::WouldBlock),
+        }
+    })
+}
+
+#[test]
+fn bind_udp() {
+    each_ip(&mut |addr| {
+        let socket = t!(SocketBuilder::new().recv_buffer_size(64 * 1024).bind_udp(&addr));
+        assert_eq!(t!(socket.local_addr()), addr);
+
+        t!(socket.send_to(&[1, 2, 3], &addr));
+        let mut buf = [0; 3];
+        let (n, from) = t!(socket.recv_from(&mut buf));
+        assert_eq!(&buf[..n], &[1, 2, 3]);
+        assert_eq!(from, addr);
+    })
+}
+
+#[test]
+fn bind_udp_rejects_tcp_options() {
+    let addr = next_test_ip4();
+    let err = SocketBuilder::new().nodelay(true).bind_udp(&addr).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = SocketBuilder::new().keepalive(true).bind_udp(&addr).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = SocketBuilder::new()
+        .keepalive_interval(Duration::from_secs(5))
+        .bind_udp(&addr)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+fn reuse_port() {
+    each_ip(&mut |addr| {
+        let mut builder = SocketBuilder::new();
+        builder.reuse_port(true);
+        let _first = t!(builder.listen(&addr));
+        let _second = t!(builder.listen(&addr));
+        assert!(SocketBuilder::new().reuse_port(false).listen(&addr).is_err());
+    })
+}
+
+#[test]
+#[cfg(windows)]
+fn reuse_port_unsupported() {
+    let err = SocketBuilder::new().reuse_port(true).listen(next_test_ip4()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unsupported);
+}
@@ -7,6 +7,8 @@
 //!
 //! * [`TcpListener`] and [`TcpStream`] provide functionality for communication over TCP
 //! * [`UdpSocket`] provides functionality for communication over UDP
+//! * [`SocketBuilder`] sets socket options which need to be in place before a TCP or UDP socket
+//!   is bound or connected
 //! * [`IpAddr`] represents IP addresses of either IPv4 or IPv6; [`Ipv4Addr`] and
 //!   [`Ipv6Addr`] are respectively IPv4 and IPv6 addresses
 //! * [`SocketAddr`] represents socket addresses of either IPv4 or IPv6; [`SocketAddrV4`]
@@ -23,6 +25,8 @@
 
 use crate::io::{self, ErrorKind};
 
+#[unstable(feature = "socket_builder", issue = "none")]
+pub use self::builder::SocketBuilder;
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::ip_addr::{IpAddr, Ipv4Addr, Ipv6Addr, Ipv6MulticastScope};
 #[stable(feature = "rust1", since = "1.0.0")]
@@ -36,6 +40,7 @@
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use core::net::AddrParseError;
 
+mod builder;
 mod ip_addr;
 mod socket_addr;
 mod tcp;
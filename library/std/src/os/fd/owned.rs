@@ -202,6 +202,16 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     }
 }
 
+#[cfg(unix)]
+#[unstable(feature = "io_splice", issue = "none")]
+impl<'a> From<BorrowedFd<'a>> for io::SpliceHandle<'a> {
+    /// Creates a handle that [`io::splice`] moves data to or from directly.
+    #[inline]
+    fn from(fd: BorrowedFd<'a>) -> io::SpliceHandle<'a> {
+        io::SpliceHandle { fd }
+    }
+}
+
 macro_rules! impl_is_terminal {
     ($($t:ty),*$(,)?) => {$(
         #[unstable(feature = "sealed", issue = "none")]
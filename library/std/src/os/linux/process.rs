@@ -6,11 +6,14 @@
 
 use crate::io::Result;
 use crate::os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, OwnedFd, RawFd};
-use crate::process;
+use crate::process::{self, ExitStatus};
 use crate::sealed::Sealed;
 #[cfg(not(doc))]
 use crate::sys::fd::FileDesc;
+#[cfg(not(doc))]
+use crate::sys::process::pidfd;
 use crate::sys_common::{AsInner, AsInnerMut, FromInner, IntoInner};
+use crate::time::Duration;
 
 #[cfg(doc)]
 struct FileDesc;
@@ -38,6 +41,12 @@
 ///
 /// // The file descriptor will be closed when `pidfd` is dropped.
 /// ```
+///
+/// The pidfd becomes readable once the process has exited, so it can be
+/// registered with `poll(2)` or `epoll(7)` through [`AsFd`] or [`AsRawFd`] to
+/// wait on many processes at once. When it is reported as ready, [`try_wait`]
+/// collects the exit status without blocking.
+///
 /// Refer to the man page of [`pidfd_open(2)`] for further details.
 ///
 /// [`Command`]: process::Command
@@ -45,12 +54,54 @@
 /// [`Child`]: process::Child
 /// [`pidfd`]: fn@ChildExt::pidfd
 /// [`take_pidfd`]: ChildExt::take_pidfd
+/// [`try_wait`]: PidFd::try_wait
 /// [`pidfd_open(2)`]: https://man7.org/linux/man-pages/man2/pidfd_open.2.html
 #[derive(Debug)]
 pub struct PidFd {
     inner: FileDesc,
 }
 
+impl PidFd {
+    /// Forces the child process to exit.
+    ///
+    /// Unlike sending `SIGKILL` to a pid, this uses [`pidfd_send_signal(2)`],
+    /// so the signal can't reach an unrelated process that reused the pid of
+    /// an already reaped child.
+    ///
+    /// [`pidfd_send_signal(2)`]: https://man7.org/linux/man-pages/man2/pidfd_send_signal.2.html
+    pub fn kill(&self) -> Result<()> {
+        pidfd::kill(self)
+    }
+
+    /// Waits for the child to exit completely, returning the status that it
+    /// exited with.
+    ///
+    /// Waiting reaps the child. Once it has succeeded, waiting on the
+    /// [`Child`] this pidfd was taken from returns an error.
+    ///
+    /// [`Child`]: process::Child
+    pub fn wait(&self) -> Result<ExitStatus> {
+        pidfd::wait(self).map(FromInner::from_inner)
+    }
+
+    /// Attempts to collect the exit status of the child if it has already
+    /// exited.
+    ///
+    /// Returns `Ok(None)` if the child is still running.
+    pub fn try_wait(&self) -> Result<Option<ExitStatus>> {
+        Ok(pidfd::try_wait(self)?.map(FromInner::from_inner))
+    }
+
+    /// Waits for the child to exit for at most `timeout`, returning the status
+    /// that it exited with.
+    ///
+    /// Returns `Ok(None)` if the child is still running when the timeout
+    /// elapses.
+    pub fn wait_timeout(&self, timeout: Duration) -> Result<Option<ExitStatus>> {
+        Ok(pidfd::wait_timeout(self, timeout)?.map(FromInner::from_inner))
+    }
+}
+
 impl AsInner<FileDesc> for PidFd {
     #[inline]
     fn as_inner(&self) -> &FileDesc {
@@ -136,6 +187,36 @@ pub trait ChildExt: Sealed {
     /// [`create_pidfd`]: CommandExt::create_pidfd
     /// [`Child`]: process::Child
     fn take_pidfd(&mut self) -> Result<PidFd>;
+
+    /// Waits for the child to exit for at most `timeout`, returning the status
+    /// that it exited with.
+    ///
+    /// Returns `Ok(None)` if the child is still running when the timeout
+    /// elapses. As with [`Child::wait`], the stdin handle of the child is
+    /// closed before waiting.
+    ///
+    /// Waiting is done on the pidfd of the child, which must have been
+    /// requested with [`create_pidfd`] and not taken. Unlike
+    /// [`Child::wait_timeout`], this never falls back to another strategy.
+    ///
+    /// ```no_run
+    /// #![feature(linux_pidfd)]
+    /// use std::os::linux::process::{ChildExt, CommandExt};
+    /// use std::process::Command;
+    /// use std::time::Duration;
+    ///
+    /// let mut child = Command::new("sleep").arg("10").create_pidfd(true).spawn()?;
+    /// if child.wait_timeout(Duration::from_secs(1))?.is_none() {
+    ///     child.kill()?;
+    ///     child.wait()?;
+    /// }
+    /// # std::io::Result::Ok(())
+    /// ```
+    ///
+    /// [`create_pidfd`]: CommandExt::create_pidfd
+    /// [`Child::wait`]: process::Child::wait
+    /// [`Child::wait_timeout`]: process::Child::wait_timeout
+    fn wait_timeout(&mut self, timeout: Duration) -> Result<Option<ExitStatus>>;
 }
 
 /// Os-specific extensions for [`Command`]
@@ -681,6 +681,14 @@ fn as_fd(&self) -> BorrowedFd<'_> {
     }
 }
 
+#[unstable(feature = "io_splice", issue = "none")]
+impl io::AsSpliceHandle for UnixStream {
+    #[inline]
+    fn as_splice_handle(&self) -> Option<io::SpliceHandle<'_>> {
+        Some(self.as_fd().into())
+    }
+}
+
 #[stable(feature = "io_safety", since = "1.63.0")]
 impl From<UnixStream> for OwnedFd {
     /// Takes ownership of a [`UnixStream`]'s socket file descriptor.
@@ -24,11 +24,11 @@
 use crate::thread;
 
 #[cfg(not(test))]
-use crate::io::try_set_output_capture;
+use crate::io::{try_set_error_capture, try_set_output_capture};
 // make sure to use the stderr output configured
 // by libtest in the real copy of std
 #[cfg(test)]
-use realstd::io::try_set_output_capture;
+use realstd::io::{try_set_error_capture, try_set_output_capture};
 
 // Binary interface to the panic runtime that the standard library depends on.
 //
@@ -292,7 +292,16 @@ fn default_hook(info: &PanicInfo<'_>) {
     };
 
     if let Ok(Some(local)) = try_set_output_capture(None) {
-        write(&mut *local.lock().unwrap_or_else(|e| e.into_inner()));
+        if let Ok(Some(error)) = try_set_error_capture(None) {
+            // Write the message once, so that both copies of it agree.
+            let mut message = Vec::new();
+            write(&mut message);
+            local.lock().unwrap_or_else(|e| e.into_inner()).extend_from_slice(&message);
+            error.lock().unwrap_or_else(|e| e.into_inner()).extend_from_slice(&message);
+            try_set_error_capture(Some(error)).ok();
+        } else {
+            write(&mut *local.lock().unwrap_or_else(|e| e.into_inner()));
+        }
         try_set_output_capture(Some(local)).ok();
     } else if let Some(mut out) = panic_output() {
         write(&mut out);
@@ -151,6 +151,11 @@
 #[cfg(all(test, not(any(target_os = "emscripten", target_env = "sgx", target_os = "xous"))))]
 mod tests;
 
+mod pipeline;
+
+#[unstable(feature = "process_pipeline", issue = "none")]
+pub use self::pipeline::{Pipeline, PipelineChild, PipelineOutput, PipelineStatus};
+
 use crate::io::prelude::*;
 
 use crate::convert::Infallible;
@@ -166,6 +171,7 @@
 #[stable(feature = "command_access", since = "1.57.0")]
 pub use crate::sys_common::process::CommandEnvs;
 use crate::sys_common::{AsInner, AsInnerMut, FromInner, IntoInner};
+use crate::time::Duration;
 
 /// Representation of a running or exited child process.
 ///
@@ -2200,6 +2206,50 @@ pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
         Ok(self.handle.try_wait()?.map(ExitStatus))
     }
 
+    /// Waits for the child to exit for at most `timeout`, returning the status
+    /// that it exited with.
+    ///
+    /// If the child has exited, then `Ok(Some(status))` is returned. If the
+    /// child is still running when the timeout elapses, then `Ok(None)` is
+    /// returned and the child keeps running. If an error occurs, then that
+    /// error is returned.
+    ///
+    /// As with [`wait`], the stdin handle to the child process, if any, is
+    /// closed before waiting.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// On Linux, this waits on a pidfd of the child, and on macOS and the BSDs
+    /// on a kqueue, so the exit is noticed as soon as it happens. Windows waits
+    /// on the process handle. Elsewhere, the child is polled with an
+    /// increasing delay between attempts.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(child_wait_timeout)]
+    /// use std::process::Command;
+    /// use std::time::Duration;
+    ///
+    /// let mut child = Command::new("sleep").arg("10").spawn()?;
+    /// match child.wait_timeout(Duration::from_secs(1))? {
+    ///     Some(status) => println!("exited with: {status}"),
+    ///     None => {
+    ///         println!("still running, killing it");
+    ///         child.kill()?;
+    ///         child.wait()?;
+    ///     }
+    /// }
+    /// # std::io::Result::Ok(())
+    /// ```
+    ///
+    /// [`wait`]: Child::wait
+    #[unstable(feature = "child_wait_timeout", issue = "none")]
+    pub fn wait_timeout(&mut self, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+        drop(self.stdin.take());
+        Ok(self.handle.wait_timeout(timeout)?.map(ExitStatus))
+    }
+
     /// Simultaneously waits for the child to exit and collect all remaining
     /// output on the stdout/stderr handles, returning an `Output`
     /// instance.
@@ -2236,28 +2286,43 @@ pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
     pub fn wait_with_output(mut self) -> io::Result<Output> {
         drop(self.stdin.take());
 
-        let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
-        match (self.stdout.take(), self.stderr.take()) {
-            (None, None) => {}
-            (Some(mut out), None) => {
-                let res = out.read_to_end(&mut stdout);
-                res.unwrap();
-            }
-            (None, Some(mut err)) => {
-                let res = err.read_to_end(&mut stderr);
-                res.unwrap();
-            }
-            (Some(out), Some(err)) => {
-                let res = read2(out.inner, &mut stdout, err.inner, &mut stderr);
-                res.unwrap();
-            }
-        }
+        let (stdout, stderr) = read_output(self.stdout.take(), self.stderr.take()).unwrap();
 
         let status = self.wait()?;
         Ok(Output { status, stdout, stderr })
     }
 }
 
+/// Reads the stdout and stderr pipes of a child process to the end, draining
+/// both at the same time like [`Child::wait_with_output`] does.
+///
+/// This lets the caller wait for the child on its own, for example with
+/// [`Child::wait_timeout`], while another thread collects the output.
+#[unstable(
+    feature = "process_internals",
+    reason = "this function is meant for use in the test crate \
+        and may disappear in the future",
+    issue = "none"
+)]
+#[doc(hidden)]
+pub fn read_output(
+    stdout: Option<ChildStdout>,
+    stderr: Option<ChildStderr>,
+) -> io::Result<(Vec<u8>, Vec<u8>)> {
+    let (mut out_buf, mut err_buf) = (Vec::new(), Vec::new());
+    match (stdout, stderr) {
+        (None, None) => {}
+        (Some(mut out), None) => {
+            out.read_to_end(&mut out_buf)?;
+        }
+        (None, Some(mut err)) => {
+            err.read_to_end(&mut err_buf)?;
+        }
+        (Some(out), Some(err)) => read2(out.inner, &mut out_buf, err.inner, &mut err_buf)?,
+    }
+    Ok((out_buf, err_buf))
+}
+
 /// Terminates the current process with the specified exit code.
 ///
 /// This function will never return and will immediately terminate the current
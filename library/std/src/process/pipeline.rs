@@ -0,0 +1,301 @@
+//! Chains of processes, each reading the output of the previous one.
+
+use super::{Child, ChildStdin, ChildStdout, Command, ExitStatus, Stdio};
+use crate::io;
+use crate::panic;
+use crate::sys::pipe::{read2, AnonPipe};
+use crate::thread;
+
+/// A builder for a chain of processes connected like `a | b | c` in a shell.
+///
+/// The standard output of each stage is connected to the standard input of
+/// the next one. The standard input of the first stage and the standard output
+/// of the last stage are configured on their [`Command`]s as usual, while
+/// whatever the other stages had configured for those is replaced by the pipes
+/// connecting them. Standard error is left as configured on each stage.
+///
+/// The pipes connect the stages directly: the data doesn't go through this
+/// process, and this process doesn't keep any end of the pipes open. So when a
+/// stage exits early, the stage writing to it is notified of the broken pipe
+/// on its next write, just as in a shell.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(process_pipeline)]
+/// use std::process::{Command, Pipeline, Stdio};
+///
+/// let mut sort = Command::new("sort");
+/// sort.stdout(Stdio::piped());
+/// let child = Pipeline::new()
+///     .stage(Command::new("ls"))
+///     .stage(sort)
+///     .spawn()?;
+///
+/// let output = child.wait_with_output()?;
+/// assert!(output.status.success());
+/// println!("{}", String::from_utf8_lossy(&output.stdout));
+/// # std::io::Result::Ok(())
+/// ```
+#[unstable(feature = "process_pipeline", issue = "none")]
+#[derive(Debug, Default)]
+pub struct Pipeline {
+    stages: Vec<Command>,
+}
+
+impl Pipeline {
+    /// Constructs a new, empty `Pipeline`.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn new() -> Pipeline {
+        Pipeline { stages: Vec::new() }
+    }
+
+    /// Appends a stage that reads the standard output of the current last
+    /// stage.
+    ///
+    /// The pipeline takes ownership of `command`, since spawning the pipeline
+    /// overwrites the standard input and output settings that connect it to
+    /// its neighbours.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn stage(&mut self, command: Command) -> &mut Pipeline {
+        self.stages.push(command);
+        self
+    }
+
+    /// Returns the stages of the pipeline, in order.
+    ///
+    /// Once the pipeline has been spawned, every stage but the first has its
+    /// standard input set to [`Stdio::null`], and every stage but the last has
+    /// its standard output set to [`Stdio::piped`]. Spawning the pipeline
+    /// again connects them anew.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn stages(&self) -> &[Command] {
+        &self.stages
+    }
+
+    /// Spawns all the stages of the pipeline, returning a handle to them.
+    ///
+    /// If a stage fails to spawn, the stages spawned before it are killed and
+    /// waited for, and the error is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind [`io::ErrorKind::InvalidInput`] if the
+    /// pipeline has no stages.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn spawn(&mut self) -> io::Result<PipelineChild> {
+        let Some(last) = self.stages.len().checked_sub(1) else {
+            return Err(io::const_io_error!(io::ErrorKind::InvalidInput, "pipeline has no stages"));
+        };
+
+        let mut children: Vec<Child> = Vec::with_capacity(self.stages.len());
+        let mut pipe: Option<ChildStdout> = None;
+        for (i, command) in self.stages.iter_mut().enumerate() {
+            if let Some(pipe) = pipe.take() {
+                command.stdin(pipe);
+            }
+            if i != last {
+                command.stdout(Stdio::piped());
+            }
+            let result = command.spawn();
+            if i != 0 {
+                // The command still owns the read end of the pipe from the previous stage.
+                // Close it, so that the previous stage sees a broken pipe once this one exits.
+                command.stdin(Stdio::null());
+            }
+            match result {
+                Ok(mut child) => {
+                    if i != last {
+                        pipe = child.stdout.take();
+                    }
+                    children.push(child);
+                }
+                Err(e) => {
+                    for mut child in children {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                    }
+                    return Err(e);
+                }
+            }
+        }
+
+        let stdin = children[0].stdin.take();
+        let stdout = children[last].stdout.take();
+        Ok(PipelineChild { stdin, stdout, children })
+    }
+
+    /// Spawns all the stages of the pipeline and waits for all of them to
+    /// exit, returning their statuses.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn status(&mut self) -> io::Result<PipelineStatus> {
+        self.spawn()?.wait()
+    }
+}
+
+/// A handle to the running or exited processes of a [`Pipeline`].
+///
+/// As with [`Child`], dropping a `PipelineChild` neither kills nor waits for
+/// the processes.
+#[unstable(feature = "process_pipeline", issue = "none")]
+#[derive(Debug)]
+pub struct PipelineChild {
+    /// The handle for writing to the standard input of the first stage, if it
+    /// has been captured.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub stdin: Option<ChildStdin>,
+
+    /// The handle for reading from the standard output of the last stage, if
+    /// it has been captured.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub stdout: Option<ChildStdout>,
+
+    children: Vec<Child>,
+}
+
+impl PipelineChild {
+    /// Returns the processes of the stages, in order.
+    ///
+    /// Their standard error handles are still available through
+    /// [`Child::stderr`] if they have been captured.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn children(&mut self) -> &mut [Child] {
+        &mut self.children
+    }
+
+    /// Forces all the stages to exit.
+    ///
+    /// Every stage is killed even if killing one of them fails, in which case
+    /// the first error is returned.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn kill(&mut self) -> io::Result<()> {
+        let mut result = Ok(());
+        for child in &mut self.children {
+            let killed = child.kill();
+            if result.is_ok() {
+                result = killed;
+            }
+        }
+        result
+    }
+
+    /// Waits for all the stages to exit, returning their statuses.
+    ///
+    /// The standard input of the first stage is closed before waiting.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn wait(&mut self) -> io::Result<PipelineStatus> {
+        drop(self.stdin.take());
+        let statuses = self.children.iter_mut().map(Child::wait).collect::<io::Result<_>>()?;
+        Ok(PipelineStatus { statuses })
+    }
+
+    /// Waits for all the stages to exit, collecting the standard output of the
+    /// last stage and the standard error of every stage.
+    ///
+    /// The standard input of the first stage is closed before waiting. The
+    /// standard output of the last stage, and the standard error of each
+    /// stage, must have been captured with [`Stdio::piped`] to be collected.
+    /// Handles already taken out of [`PipelineChild::stdout`] or
+    /// [`PipelineChild::children`] are not read from.
+    ///
+    /// All the captured pipes are read at the same time, so that a stage
+    /// blocked on a full standard error pipe doesn't stop the stages after it
+    /// from ever reaching the end of their input.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn wait_with_output(mut self) -> io::Result<PipelineOutput> {
+        drop(self.stdin.take());
+        let mut stdout = Vec::new();
+        let mut stderr = vec![Vec::new(); self.children.len()];
+        let mut pipes = Vec::new();
+        if let Some(out) = self.stdout.take() {
+            pipes.push((out.inner, &mut stdout));
+        }
+        for (child, buf) in self.children.iter_mut().zip(&mut stderr) {
+            if let Some(err) = child.stderr.take() {
+                pipes.push((err.inner, buf));
+            }
+        }
+        read_all(pipes)?;
+
+        let status = self.wait()?;
+        Ok(PipelineOutput { status, stdout, stderr })
+    }
+}
+
+/// Reads all the pipes to the end at the same time. Like
+/// [`Child::wait_with_output`], this uses `read2` for two pipes; any further
+/// pairs are read on scoped threads of their own.
+fn read_all(mut pipes: Vec<(AnonPipe, &mut Vec<u8>)>) -> io::Result<()> {
+    fn read_pair(pipes: &mut Vec<(AnonPipe, &mut Vec<u8>)>) -> io::Result<()> {
+        match (pipes.pop(), pipes.pop()) {
+            (Some((p2, v2)), Some((p1, v1))) => read2(p1, v1, p2, v2),
+            (Some((p, v)), None) => p.read_to_end(v).map(drop),
+            _ => Ok(()),
+        }
+    }
+
+    thread::scope(|s| {
+        let mut readers = Vec::new();
+        while pipes.len() > 2 {
+            let mut pair = pipes.split_off(pipes.len() - 2);
+            readers.push(thread::Builder::new().spawn_scoped(s, move || read_pair(&mut pair))?);
+        }
+        let mut result = read_pair(&mut pipes);
+        for reader in readers {
+            let read = reader.join().unwrap_or_else(|e| panic::resume_unwind(e));
+            if result.is_ok() {
+                result = read;
+            }
+        }
+        result
+    })
+}
+
+/// The exit statuses of the stages of a [`Pipeline`].
+///
+/// As with `set -o pipefail` in a shell, the pipeline as a whole only
+/// succeeds if every stage does.
+#[unstable(feature = "process_pipeline", issue = "none")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PipelineStatus {
+    statuses: Vec<ExitStatus>,
+}
+
+impl PipelineStatus {
+    /// Returns the exit statuses of the stages, in order.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn statuses(&self) -> &[ExitStatus] {
+        &self.statuses
+    }
+
+    /// Was termination successful for every stage?
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn success(&self) -> bool {
+        self.statuses.iter().all(ExitStatus::success)
+    }
+
+    /// Returns the status of the pipeline as a whole: the status of the last
+    /// stage that failed, or of the last stage if all of them succeeded.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn status(&self) -> ExitStatus {
+        let last = *self.statuses.last().unwrap();
+        self.statuses.iter().rev().copied().find(|status| !status.success()).unwrap_or(last)
+    }
+}
+
+/// The output of a finished [`Pipeline`].
+#[unstable(feature = "process_pipeline", issue = "none")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PipelineOutput {
+    /// The exit statuses of the stages.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub status: PipelineStatus,
+
+    /// The data that the last stage wrote to its standard output.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub stdout: Vec<u8>,
+    /// The data that each stage wrote to its standard error, in order of the
+    /// stages. It is empty for stages whose standard error wasn't captured.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub stderr: Vec<Vec<u8>>,
+}
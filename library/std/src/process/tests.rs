@@ -230,6 +230,81 @@ fn test_finish_twice() {
     assert!(prog.wait().unwrap().code() == Some(1));
 }
 
+#[test]
+#[cfg(unix)]
+#[cfg_attr(any(target_os = "vxworks"), ignore)]
+fn test_wait_timeout() {
+    use crate::time::Duration;
+
+    let mut prog = shell_cmd().arg("-c").arg("sleep 1000").spawn().unwrap();
+    assert!(prog.wait_timeout(Duration::from_millis(10)).unwrap().is_none());
+    prog.kill().unwrap();
+    let status = prog.wait_timeout(Duration::from_secs(60)).unwrap().unwrap();
+    assert!(!status.success());
+    assert_eq!(prog.wait_timeout(Duration::ZERO).unwrap(), Some(status));
+
+    // Like `wait`, `wait_timeout` closes stdin, which lets `cat` exit.
+    let mut prog = Command::new("cat").stdin(Stdio::piped()).spawn().unwrap();
+    let status = prog.wait_timeout(Duration::from_secs(60)).unwrap().unwrap();
+    assert!(status.success());
+}
+
+#[test]
+#[cfg(unix)]
+#[cfg_attr(any(target_os = "vxworks"), ignore)]
+fn test_pipeline() {
+    use super::Pipeline;
+
+    let sh = |script| {
+        let mut command = shell_cmd();
+        command.args(["-c", script]);
+        command
+    };
+    let mut cat = sh("cat; echo world");
+    cat.stdin(Stdio::piped());
+    let mut upper = Command::new("tr");
+    upper.args(["a-z", "A-Z"]).stdout(Stdio::piped());
+    let mut child = Pipeline::new().stage(cat).stage(upper).spawn().unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"hello\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.status.statuses().len(), 2);
+    assert_eq!(output.stdout, b"HELLO\nWORLD\n");
+
+    // Like with pipefail, the pipeline fails with the last failing stage.
+    let status =
+        Pipeline::new().stage(sh("exit 3")).stage(sh("exit 4")).stage(sh("true")).status().unwrap();
+    assert!(!status.success());
+    assert_eq!(status.status().code(), Some(4));
+    let codes: Vec<_> = status.statuses().iter().map(|status| status.code()).collect();
+    assert_eq!(codes, [Some(3), Some(4), Some(0)]);
+
+    // `yes` only stops once `head` exits, if nothing else keeps the pipe open.
+    let mut head = Command::new("head");
+    head.args(["-n", "1"]).stdout(Stdio::piped());
+    let output =
+        Pipeline::new().stage(Command::new("yes")).stage(head).spawn().unwrap().wait_with_output();
+    assert_eq!(output.unwrap().stdout, b"y\n");
+
+    // Captured standard error is read along with the output, so a stage that fills its standard
+    // error pipe before writing anything else doesn't keep the pipeline from finishing.
+    let mut noisy = sh("head -c 1000000 /dev/zero >&2; cat");
+    noisy.stderr(Stdio::piped());
+    let mut last = sh("cat; echo oops >&2");
+    last.stdout(Stdio::piped()).stderr(Stdio::piped());
+    let child = Pipeline::new().stage(sh("echo start")).stage(noisy).stage(last).spawn().unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"start\n");
+    assert_eq!(output.stderr.len(), 3);
+    assert!(output.stderr[0].is_empty());
+    assert_eq!(output.stderr[1].len(), 1000000);
+    assert_eq!(output.stderr[2], b"oops\n");
+
+    let err = Pipeline::new().spawn().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
 #[test]
 #[cfg_attr(any(target_os = "vxworks"), ignore)]
 fn test_wait_with_output_once() {
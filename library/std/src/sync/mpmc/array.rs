@@ -561,4 +561,38 @@ pub(crate) fn is_full(&self) -> bool {
         // when the channel was not full, so it is safe to just return `false`.
         head.wrapping_add(self.one_lap) == tail & !self.mark_bit
     }
+
+    /// Returns `true` if a send operation can proceed without blocking.
+    pub(crate) fn is_ready_to_send(&self) -> bool {
+        !self.is_full() || self.is_disconnected()
+    }
+
+    /// Returns `true` if a receive operation can proceed without blocking.
+    pub(crate) fn is_ready_to_recv(&self) -> bool {
+        !self.is_empty() || self.is_disconnected()
+    }
+
+    /// Registers an operation waiting for a send operation to become ready, and returns `true`
+    /// if it already is.
+    pub(crate) fn watch_send(&self, oper: Operation, cx: &Context) -> bool {
+        self.senders.watch(oper, cx);
+        self.is_ready_to_send()
+    }
+
+    /// Unregisters an operation registered with `watch_send`.
+    pub(crate) fn unwatch_send(&self, oper: Operation) {
+        self.senders.unwatch(oper);
+    }
+
+    /// Registers an operation waiting for a receive operation to become ready, and returns
+    /// `true` if it already is.
+    pub(crate) fn watch_recv(&self, oper: Operation, cx: &Context) -> bool {
+        self.receivers.watch(oper, cx);
+        self.is_ready_to_recv()
+    }
+
+    /// Unregisters an operation registered with `watch_recv`.
+    pub(crate) fn unwatch_recv(&self, oper: Operation) {
+        self.receivers.unwatch(oper);
+    }
 }
@@ -1,6 +1,7 @@
 use crate::error;
 use crate::fmt;
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 pub use crate::sync::mpsc::{RecvError, RecvTimeoutError, SendError, TryRecvError, TrySendError};
 
 /// An error returned from the [`send_timeout`] method.
@@ -9,23 +10,28 @@
 ///
 /// [`send_timeout`]: super::Sender::send_timeout
 #[derive(PartialEq, Eq, Clone, Copy)]
+#[unstable(feature = "mpmc_channel", issue = "none")]
 pub enum SendTimeoutError<T> {
     /// The message could not be sent because the channel is full and the operation timed out.
     ///
     /// If this is a zero-capacity channel, then the error indicates that there was no receiver
     /// available to receive the message and the operation timed out.
-    Timeout(T),
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    Timeout(#[unstable(feature = "mpmc_channel", issue = "none")] T),
 
     /// The message could not be sent because the channel is disconnected.
-    Disconnected(T),
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    Disconnected(#[unstable(feature = "mpmc_channel", issue = "none")] T),
 }
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T> fmt::Debug for SendTimeoutError<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         "SendTimeoutError(..)".fmt(f)
     }
 }
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T> fmt::Display for SendTimeoutError<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
@@ -35,8 +41,10 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     }
 }
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T> error::Error for SendTimeoutError<T> {}
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T> From<SendError<T>> for SendTimeoutError<T> {
     fn from(err: SendError<T>) -> SendTimeoutError<T> {
         match err {
@@ -44,3 +52,44 @@ fn from(err: SendError<T>) -> SendTimeoutError<T> {
         }
     }
 }
+
+/// An error returned from the [`try_ready`] method.
+///
+/// Failed because none of the channel operations were ready.
+///
+/// [`try_ready`]: super::Select::try_ready
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[unstable(feature = "mpmc_channel", issue = "none")]
+pub struct TryReadyError;
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl fmt::Display for TryReadyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "all operations in select would block".fmt(f)
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl error::Error for TryReadyError {}
+
+/// An error returned from the [`ready_timeout`] and [`ready_deadline`]
+/// methods.
+///
+/// Failed because none of the channel operations became ready before the
+/// timeout.
+///
+/// [`ready_timeout`]: super::Select::ready_timeout
+/// [`ready_deadline`]: super::Select::ready_deadline
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[unstable(feature = "mpmc_channel", issue = "none")]
+pub struct ReadyTimeoutError;
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl fmt::Display for ReadyTimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "timed out waiting on select".fmt(f)
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl error::Error for ReadyTimeoutError {}
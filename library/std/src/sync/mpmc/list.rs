@@ -612,6 +612,39 @@ pub(crate) fn is_empty(&self) -> bool {
     pub(crate) fn is_full(&self) -> bool {
         false
     }
+
+    /// Returns `true` if a send operation can proceed without blocking.
+    pub(crate) fn is_ready_to_send(&self) -> bool {
+        true
+    }
+
+    /// Returns `true` if a receive operation can proceed without blocking.
+    pub(crate) fn is_ready_to_recv(&self) -> bool {
+        !self.is_empty() || self.is_disconnected()
+    }
+
+    /// Registers an operation waiting for a send operation to become ready, and returns `true`
+    /// if it already is.
+    ///
+    /// Sending into an unbounded channel never blocks, so there is nothing to register.
+    pub(crate) fn watch_send(&self, _oper: Operation, _cx: &Context) -> bool {
+        true
+    }
+
+    /// Unregisters an operation registered with `watch_send`.
+    pub(crate) fn unwatch_send(&self, _oper: Operation) {}
+
+    /// Registers an operation waiting for a receive operation to become ready, and returns
+    /// `true` if it already is.
+    pub(crate) fn watch_recv(&self, oper: Operation, cx: &Context) -> bool {
+        self.receivers.watch(oper, cx);
+        self.is_ready_to_recv()
+    }
+
+    /// Unregisters an operation registered with `watch_recv`.
+    pub(crate) fn unwatch_recv(&self, oper: Operation) {
+        self.receivers.unwatch(oper);
+    }
 }
 
 impl<T> Drop for Channel<T> {
@@ -1,8 +1,71 @@
-//! Multi-producer multi-consumer channels.
-
-// This module is not currently exposed publicly, but is used
-// as the implementation for the channels in `sync::mpsc`. The
-// implementation comes from the crossbeam-channel crate:
+//! Multi-producer, multi-consumer FIFO queue communication primitives.
+//!
+//! This module provides message-based communication over channels, concretely
+//! defined by two types:
+//!
+//! * [`Sender`]
+//! * [`Receiver`]
+//!
+//! A [`Sender`] is used to send data to a [`Receiver`]. Both are clone-able,
+//! so that many threads can send simultaneously into a channel
+//! (multi-producer), and many threads can receive from it (multi-consumer).
+//! Each message is received by exactly one of the receivers.
+//!
+//! These channels come in three flavors:
+//!
+//! 1. An unbounded channel, created by [`channel`]. Sends never block, and
+//!    the channel conceptually has an infinite buffer.
+//!
+//! 2. A bounded channel, created by [`sync_channel`] with a non-zero bound.
+//!    The storage for pending messages is a pre-allocated buffer of a fixed
+//!    size, and sends block while it is full.
+//!
+//! 3. A zero-capacity channel, created by [`sync_channel`] with a bound of 0.
+//!    It has no buffer at all: each sender blocks until a receiver atomically
+//!    takes the message from it.
+//!
+//! [`Select`] can be used to wait until one of several operations on
+//! different channels can proceed.
+//!
+//! ## Disconnection
+//!
+//! Once all [`Sender`]s of a channel have been dropped, receiving returns an
+//! error as soon as the channel is empty. Once all [`Receiver`]s have been
+//! dropped, sending returns an error and hands the message back.
+//!
+//! # Examples
+//!
+//! Distributing work among several threads:
+//!
+//! ```
+//! #![feature(mpmc_channel)]
+//! use std::sync::mpmc;
+//! use std::thread;
+//!
+//! let (jobs, job_queue) = mpmc::channel();
+//! let (results, result_queue) = mpmc::channel();
+//!
+//! for _ in 0..4 {
+//!     let job_queue = job_queue.clone();
+//!     let results = results.clone();
+//!     thread::spawn(move || {
+//!         for job in job_queue {
+//!             results.send(job * 2).unwrap();
+//!         }
+//!     });
+//! }
+//! drop(results);
+//!
+//! for job in 0..100 {
+//!     jobs.send(job).unwrap();
+//! }
+//! drop(jobs);
+//!
+//! assert_eq!(result_queue.iter().sum::<i32>(), 9900);
+//! ```
+
+// The implementation comes from the crossbeam-channel crate, and also backs
+// the channels in `sync::mpsc`:
 //
 // Copyright (c) 2019 The Crossbeam Project Developers
 //
@@ -40,14 +103,42 @@
 mod waker;
 mod zero;
 
+#[cfg(all(test, not(target_os = "emscripten")))]
+mod tests;
+
 use crate::fmt;
 use crate::panic::{RefUnwindSafe, UnwindSafe};
 use crate::time::{Duration, Instant};
+#[unstable(feature = "mpmc_channel", issue = "none")]
 pub use error::*;
+#[unstable(feature = "mpmc_channel", issue = "none")]
+pub use select::Select;
 
 /// Creates a channel of unbounded capacity.
 ///
 /// This channel has a growable buffer that can hold any number of messages at a time.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(mpmc_channel)]
+/// use std::sync::mpmc::channel;
+/// use std::thread;
+///
+/// let (sender, receiver) = channel();
+///
+/// // Spawn off an expensive computation
+/// thread::spawn(move || {
+///     sender.send(2 + 2).unwrap();
+/// });
+///
+/// // Do some useful work for a while
+///
+/// // Let's see what that answer was
+/// assert_eq!(receiver.recv().unwrap(), 4);
+/// ```
+#[must_use]
+#[unstable(feature = "mpmc_channel", issue = "none")]
 pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
     let (s, r) = counter::new(list::Channel::new());
     let s = Sender { flavor: SenderFlavor::List(s) };
@@ -61,6 +152,31 @@ pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
 ///
 /// A special case is zero-capacity channel, which cannot hold any messages. Instead, send and
 /// receive operations must appear at the same time in order to pair up and pass the message over.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(mpmc_channel)]
+/// use std::sync::mpmc::{sync_channel, TrySendError};
+/// use std::thread;
+///
+/// let (sender, receiver) = sync_channel(1);
+///
+/// // This returns immediately because there's room in the buffer
+/// sender.send(1).unwrap();
+/// // The buffer is full now
+/// assert_eq!(sender.try_send(2), Err(TrySendError::Full(2)));
+///
+/// thread::spawn(move || {
+///     // This will wait for the parent thread to start receiving
+///     sender.send(2).unwrap();
+/// });
+///
+/// assert_eq!(receiver.recv().unwrap(), 1);
+/// assert_eq!(receiver.recv().unwrap(), 2);
+/// ```
+#[must_use]
+#[unstable(feature = "mpmc_channel", issue = "none")]
 pub fn sync_channel<T>(cap: usize) -> (Sender<T>, Receiver<T>) {
     if cap == 0 {
         let (s, r) = counter::new(zero::Channel::new());
@@ -76,6 +192,36 @@ pub fn sync_channel<T>(cap: usize) -> (Sender<T>, Receiver<T>) {
 }
 
 /// The sending side of a channel.
+///
+/// Senders can be cloned and shared between threads. The channel is
+/// disconnected for the receivers once all senders have been dropped.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(mpmc_channel)]
+/// use std::sync::mpmc::channel;
+/// use std::thread;
+///
+/// let (sender, receiver) = channel();
+/// let sender2 = sender.clone();
+///
+/// // First thread owns sender
+/// thread::spawn(move || {
+///     sender.send(1).unwrap();
+/// });
+///
+/// // Second thread owns sender2
+/// thread::spawn(move || {
+///     sender2.send(2).unwrap();
+/// });
+///
+/// let msg = receiver.recv().unwrap();
+/// let msg2 = receiver.recv().unwrap();
+///
+/// assert_eq!(3, msg + msg2);
+/// ```
+#[unstable(feature = "mpmc_channel", issue = "none")]
 pub struct Sender<T> {
     flavor: SenderFlavor<T>,
 }
@@ -92,10 +238,14 @@ enum SenderFlavor<T> {
     Zero(counter::Sender<zero::Channel<T>>),
 }
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 unsafe impl<T: Send> Send for Sender<T> {}
+#[unstable(feature = "mpmc_channel", issue = "none")]
 unsafe impl<T: Send> Sync for Sender<T> {}
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T> UnwindSafe for Sender<T> {}
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T> RefUnwindSafe for Sender<T> {}
 
 impl<T> Sender<T> {
@@ -106,6 +256,22 @@ impl<T> Sender<T> {
     ///
     /// If called on a zero-capacity channel, this method will send the message only if there
     /// happens to be a receive operation on the other side of the channel at the same time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(mpmc_channel)]
+    /// use std::sync::mpmc::{sync_channel, TrySendError};
+    ///
+    /// let (sender, receiver) = sync_channel(1);
+    ///
+    /// assert_eq!(sender.try_send(1), Ok(()));
+    /// assert_eq!(sender.try_send(2), Err(TrySendError::Full(2)));
+    ///
+    /// drop(receiver);
+    /// assert_eq!(sender.try_send(3), Err(TrySendError::Disconnected(3)));
+    /// ```
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn try_send(&self, msg: T) -> Result<(), TrySendError<T>> {
         match &self.flavor {
             SenderFlavor::Array(chan) => chan.try_send(msg),
@@ -122,6 +288,21 @@ pub fn try_send(&self, msg: T) -> Result<(), TrySendError<T>> {
     ///
     /// If called on a zero-capacity channel, this method will wait for a receive operation to
     /// appear on the other side of the channel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(mpmc_channel)]
+    /// use std::sync::mpmc::{channel, SendError};
+    ///
+    /// let (sender, receiver) = channel();
+    ///
+    /// assert_eq!(sender.send(1), Ok(()));
+    ///
+    /// drop(receiver);
+    /// assert_eq!(sender.send(2), Err(SendError(2)));
+    /// ```
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn send(&self, msg: T) -> Result<(), SendError<T>> {
         match &self.flavor {
             SenderFlavor::Array(chan) => chan.send(msg, None),
@@ -133,13 +314,7 @@ pub fn send(&self, msg: T) -> Result<(), SendError<T>> {
             SendTimeoutError::Timeout(_) => unreachable!(),
         })
     }
-}
 
-// The methods below are not used by `sync::mpsc`, but
-// are useful and we'll likely want to expose them
-// eventually
-#[allow(unused)]
-impl<T> Sender<T> {
     /// Waits for a message to be sent into the channel, but only for a limited time.
     ///
     /// If the channel is full and not disconnected, this call will block until the send operation
@@ -148,6 +323,23 @@ impl<T> Sender<T> {
     ///
     /// If called on a zero-capacity channel, this method will wait for a receive operation to
     /// appear on the other side of the channel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(mpmc_channel)]
+    /// use std::sync::mpmc::{sync_channel, SendTimeoutError};
+    /// use std::time::Duration;
+    ///
+    /// let (sender, _receiver) = sync_channel(0);
+    ///
+    /// // Nobody is receiving, so the message is handed back after the timeout.
+    /// assert_eq!(
+    ///     sender.send_timeout(1, Duration::from_millis(10)),
+    ///     Err(SendTimeoutError::Timeout(1)),
+    /// );
+    /// ```
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn send_timeout(&self, msg: T, timeout: Duration) -> Result<(), SendTimeoutError<T>> {
         match Instant::now().checked_add(timeout) {
             Some(deadline) => self.send_deadline(msg, deadline),
@@ -164,6 +356,7 @@ pub fn send_timeout(&self, msg: T, timeout: Duration) -> Result<(), SendTimeoutE
     ///
     /// If called on a zero-capacity channel, this method will wait for a receive operation to
     /// appear on the other side of the channel.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn send_deadline(&self, msg: T, deadline: Instant) -> Result<(), SendTimeoutError<T>> {
         match &self.flavor {
             SenderFlavor::Array(chan) => chan.send(msg, Some(deadline)),
@@ -175,6 +368,7 @@ pub fn send_deadline(&self, msg: T, deadline: Instant) -> Result<(), SendTimeout
     /// Returns `true` if the channel is empty.
     ///
     /// Note: Zero-capacity channels are always empty.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn is_empty(&self) -> bool {
         match &self.flavor {
             SenderFlavor::Array(chan) => chan.is_empty(),
@@ -186,6 +380,7 @@ pub fn is_empty(&self) -> bool {
     /// Returns `true` if the channel is full.
     ///
     /// Note: Zero-capacity channels are always full.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn is_full(&self) -> bool {
         match &self.flavor {
             SenderFlavor::Array(chan) => chan.is_full(),
@@ -195,6 +390,7 @@ pub fn is_full(&self) -> bool {
     }
 
     /// Returns the number of messages in the channel.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn len(&self) -> usize {
         match &self.flavor {
             SenderFlavor::Array(chan) => chan.len(),
@@ -204,6 +400,7 @@ pub fn len(&self) -> usize {
     }
 
     /// If the channel is bounded, returns its capacity.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn capacity(&self) -> Option<usize> {
         match &self.flavor {
             SenderFlavor::Array(chan) => chan.capacity(),
@@ -213,6 +410,20 @@ pub fn capacity(&self) -> Option<usize> {
     }
 
     /// Returns `true` if senders belong to the same channel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(mpmc_channel)]
+    /// use std::sync::mpmc::channel;
+    ///
+    /// let (tx1, _) = channel::<i32>();
+    /// let (tx2, _) = channel::<i32>();
+    ///
+    /// assert!(tx1.same_channel(&tx1.clone()));
+    /// assert!(!tx1.same_channel(&tx2));
+    /// ```
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn same_channel(&self, other: &Sender<T>) -> bool {
         match (&self.flavor, &other.flavor) {
             (SenderFlavor::Array(ref a), SenderFlavor::Array(ref b)) => a == b,
@@ -223,6 +434,7 @@ pub fn same_channel(&self, other: &Sender<T>) -> bool {
     }
 }
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T> Drop for Sender<T> {
     fn drop(&mut self) {
         unsafe {
@@ -235,6 +447,7 @@ fn drop(&mut self) {
     }
 }
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T> Clone for Sender<T> {
     fn clone(&self) -> Self {
         let flavor = match &self.flavor {
@@ -247,17 +460,119 @@ fn clone(&self) -> Self {
     }
 }
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T> fmt::Debug for Sender<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.pad("Sender { .. }")
     }
 }
 
+impl<T> select::SelectHandle for Sender<T> {
+    fn is_ready(&self) -> bool {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => chan.is_ready_to_send(),
+            SenderFlavor::List(chan) => chan.is_ready_to_send(),
+            SenderFlavor::Zero(chan) => chan.is_ready_to_send(),
+        }
+    }
+
+    fn watch(&self, oper: select::Operation, cx: &context::Context) -> bool {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => chan.watch_send(oper, cx),
+            SenderFlavor::List(chan) => chan.watch_send(oper, cx),
+            SenderFlavor::Zero(chan) => chan.watch_send(oper, cx),
+        }
+    }
+
+    fn unwatch(&self, oper: select::Operation) {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => chan.unwatch_send(oper),
+            SenderFlavor::List(chan) => chan.unwatch_send(oper),
+            SenderFlavor::Zero(chan) => chan.unwatch_send(oper),
+        }
+    }
+}
+
 /// The receiving side of a channel.
+///
+/// Receivers can be cloned and shared between threads. Each message sent
+/// into the channel is received by only one of them. The channel is
+/// disconnected for the senders once all receivers have been dropped.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(mpmc_channel)]
+/// use std::sync::mpmc::channel;
+/// use std::thread;
+///
+/// let (sender, receiver) = channel();
+///
+/// let workers: Vec<_> = (0..2)
+///     .map(|_| {
+///         let receiver = receiver.clone();
+///         thread::spawn(move || receiver.iter().count())
+///     })
+///     .collect();
+///
+/// for i in 0..10 {
+///     sender.send(i).unwrap();
+/// }
+/// drop(sender);
+///
+/// let received: usize = workers.into_iter().map(|w| w.join().unwrap()).sum();
+/// assert_eq!(received, 10);
+/// ```
+#[unstable(feature = "mpmc_channel", issue = "none")]
 pub struct Receiver<T> {
     flavor: ReceiverFlavor<T>,
 }
 
+/// An iterator over messages on a [`Receiver`], created by [`iter`].
+///
+/// This iterator will block whenever [`next`] is called,
+/// waiting for a new message, and [`None`] will be returned
+/// when the corresponding channel has hung up.
+///
+/// [`iter`]: Receiver::iter
+/// [`next`]: Iterator::next
+#[unstable(feature = "mpmc_channel", issue = "none")]
+#[derive(Debug)]
+pub struct Iter<'a, T: 'a> {
+    rx: &'a Receiver<T>,
+}
+
+/// An iterator that attempts to yield all pending values for a [`Receiver`],
+/// created by [`try_iter`].
+///
+/// [`None`] will be returned when there are no pending values remaining or
+/// if the corresponding channel has hung up.
+///
+/// This iterator will never block the caller in order to wait for data to
+/// become available. Instead, it will return [`None`].
+///
+/// [`try_iter`]: Receiver::try_iter
+#[unstable(feature = "mpmc_channel", issue = "none")]
+#[derive(Debug)]
+pub struct TryIter<'a, T: 'a> {
+    rx: &'a Receiver<T>,
+}
+
+/// An owning iterator over messages on a [`Receiver`],
+/// created by [`into_iter`].
+///
+/// This iterator will block whenever [`next`]
+/// is called, waiting for a new message, and [`None`] will be
+/// returned if the corresponding channel has hung up.
+///
+/// [`into_iter`]: Receiver::into_iter
+/// [`next`]: Iterator::next
+#[unstable(feature = "mpmc_channel", issue = "none")]
+#[derive(Debug)]
+pub struct IntoIter<T> {
+    rx: Receiver<T>,
+}
+
 /// Receiver flavors.
 enum ReceiverFlavor<T> {
     /// Bounded channel based on a preallocated array.
@@ -270,10 +585,14 @@ enum ReceiverFlavor<T> {
     Zero(counter::Receiver<zero::Channel<T>>),
 }
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 unsafe impl<T: Send> Send for Receiver<T> {}
+#[unstable(feature = "mpmc_channel", issue = "none")]
 unsafe impl<T: Send> Sync for Receiver<T> {}
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T> UnwindSafe for Receiver<T> {}
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T> RefUnwindSafe for Receiver<T> {}
 
 impl<T> Receiver<T> {
@@ -284,6 +603,24 @@ impl<T> Receiver<T> {
     ///
     /// If called on a zero-capacity channel, this method will receive a message only if there
     /// happens to be a send operation on the other side of the channel at the same time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(mpmc_channel)]
+    /// use std::sync::mpmc::{channel, TryRecvError};
+    ///
+    /// let (sender, receiver) = channel();
+    ///
+    /// assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+    ///
+    /// sender.send(1).unwrap();
+    /// drop(sender);
+    ///
+    /// assert_eq!(receiver.try_recv(), Ok(1));
+    /// assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+    /// ```
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn try_recv(&self) -> Result<T, TryRecvError> {
         match &self.flavor {
             ReceiverFlavor::Array(chan) => chan.try_recv(),
@@ -301,6 +638,25 @@ pub fn try_recv(&self) -> Result<T, TryRecvError> {
     ///
     /// If called on a zero-capacity channel, this method will wait for a send operation to appear
     /// on the other side of the channel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(mpmc_channel)]
+    /// use std::sync::mpmc::{channel, RecvError};
+    /// use std::thread;
+    ///
+    /// let (sender, receiver) = channel();
+    /// let handle = thread::spawn(move || {
+    ///     sender.send(1u8).unwrap();
+    /// });
+    ///
+    /// handle.join().unwrap();
+    ///
+    /// assert_eq!(Ok(1), receiver.recv());
+    /// assert_eq!(Err(RecvError), receiver.recv());
+    /// ```
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn recv(&self) -> Result<T, RecvError> {
         match &self.flavor {
             ReceiverFlavor::Array(chan) => chan.recv(None),
@@ -318,6 +674,22 @@ pub fn recv(&self) -> Result<T, RecvError> {
     ///
     /// If called on a zero-capacity channel, this method will wait for a send operation to appear
     /// on the other side of the channel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(mpmc_channel)]
+    /// use std::sync::mpmc::{channel, RecvTimeoutError};
+    /// use std::time::Duration;
+    ///
+    /// let (_sender, receiver) = channel::<i32>();
+    ///
+    /// assert_eq!(
+    ///     receiver.recv_timeout(Duration::from_millis(10)),
+    ///     Err(RecvTimeoutError::Timeout),
+    /// );
+    /// ```
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
         match Instant::now().checked_add(timeout) {
             Some(deadline) => self.recv_deadline(deadline),
@@ -326,7 +698,7 @@ pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
         }
     }
 
-    /// Waits for a message to be received from the channel, but only for a limited time.
+    /// Waits for a message to be received from the channel, but only until a given deadline.
     ///
     /// If the channel is empty and not disconnected, this call will block until the receive
     /// operation can proceed or the operation times out. If the channel is empty and becomes
@@ -334,6 +706,7 @@ pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
     ///
     /// If called on a zero-capacity channel, this method will wait for a send operation to appear
     /// on the other side of the channel.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn recv_deadline(&self, deadline: Instant) -> Result<T, RecvTimeoutError> {
         match &self.flavor {
             ReceiverFlavor::Array(chan) => chan.recv(Some(deadline)),
@@ -341,16 +714,11 @@ pub fn recv_deadline(&self, deadline: Instant) -> Result<T, RecvTimeoutError> {
             ReceiverFlavor::Zero(chan) => chan.recv(Some(deadline)),
         }
     }
-}
 
-// The methods below are not used by `sync::mpsc`, but
-// are useful and we'll likely want to expose them
-// eventually
-#[allow(unused)]
-impl<T> Receiver<T> {
     /// Returns `true` if the channel is empty.
     ///
     /// Note: Zero-capacity channels are always empty.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn is_empty(&self) -> bool {
         match &self.flavor {
             ReceiverFlavor::Array(chan) => chan.is_empty(),
@@ -362,6 +730,7 @@ pub fn is_empty(&self) -> bool {
     /// Returns `true` if the channel is full.
     ///
     /// Note: Zero-capacity channels are always full.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn is_full(&self) -> bool {
         match &self.flavor {
             ReceiverFlavor::Array(chan) => chan.is_full(),
@@ -371,6 +740,7 @@ pub fn is_full(&self) -> bool {
     }
 
     /// Returns the number of messages in the channel.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn len(&self) -> usize {
         match &self.flavor {
             ReceiverFlavor::Array(chan) => chan.len(),
@@ -380,6 +750,7 @@ pub fn len(&self) -> usize {
     }
 
     /// If the channel is bounded, returns its capacity.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn capacity(&self) -> Option<usize> {
         match &self.flavor {
             ReceiverFlavor::Array(chan) => chan.capacity(),
@@ -389,6 +760,7 @@ pub fn capacity(&self) -> Option<usize> {
     }
 
     /// Returns `true` if receivers belong to the same channel.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn same_channel(&self, other: &Receiver<T>) -> bool {
         match (&self.flavor, &other.flavor) {
             (ReceiverFlavor::Array(a), ReceiverFlavor::Array(b)) => a == b,
@@ -397,8 +769,67 @@ pub fn same_channel(&self, other: &Receiver<T>) -> bool {
             _ => false,
         }
     }
+
+    /// Returns an iterator that will block waiting for messages, but never
+    /// [`panic!`]. It will return [`None`] when the channel has hung up.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(mpmc_channel)]
+    /// use std::sync::mpmc::channel;
+    /// use std::thread;
+    ///
+    /// let (send, recv) = channel();
+    ///
+    /// thread::spawn(move || {
+    ///     send.send(1).unwrap();
+    ///     send.send(2).unwrap();
+    ///     send.send(3).unwrap();
+    /// });
+    ///
+    /// let mut iter = recv.iter();
+    /// assert_eq!(iter.next(), Some(1));
+    /// assert_eq!(iter.next(), Some(2));
+    /// assert_eq!(iter.next(), Some(3));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { rx: self }
+    }
+
+    /// Returns an iterator that will attempt to yield all pending values.
+    /// It will return `None` if there are no more pending values or if the
+    /// channel has hung up. The iterator will never [`panic!`] or block the
+    /// user by waiting for values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(mpmc_channel)]
+    /// use std::sync::mpmc::channel;
+    ///
+    /// let (sender, receiver) = channel();
+    ///
+    /// // nothing is in the buffer yet
+    /// assert!(receiver.try_iter().next().is_none());
+    ///
+    /// sender.send(1).unwrap();
+    /// sender.send(2).unwrap();
+    ///
+    /// let mut iter = receiver.try_iter();
+    /// assert_eq!(iter.next(), Some(1));
+    /// assert_eq!(iter.next(), Some(2));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn try_iter(&self) -> TryIter<'_, T> {
+        TryIter { rx: self }
+    }
 }
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T> Drop for Receiver<T> {
     fn drop(&mut self) {
         unsafe {
@@ -411,6 +842,7 @@ fn drop(&mut self) {
     }
 }
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T> Clone for Receiver<T> {
     fn clone(&self) -> Self {
         let flavor = match &self.flavor {
@@ -423,8 +855,81 @@ fn clone(&self) -> Self {
     }
 }
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T> fmt::Debug for Receiver<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.pad("Receiver { .. }")
     }
 }
+
+impl<T> select::SelectHandle for Receiver<T> {
+    fn is_ready(&self) -> bool {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.is_ready_to_recv(),
+            ReceiverFlavor::List(chan) => chan.is_ready_to_recv(),
+            ReceiverFlavor::Zero(chan) => chan.is_ready_to_recv(),
+        }
+    }
+
+    fn watch(&self, oper: select::Operation, cx: &context::Context) -> bool {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.watch_recv(oper, cx),
+            ReceiverFlavor::List(chan) => chan.watch_recv(oper, cx),
+            ReceiverFlavor::Zero(chan) => chan.watch_recv(oper, cx),
+        }
+    }
+
+    fn unwatch(&self, oper: select::Operation) {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.unwatch_recv(oper),
+            ReceiverFlavor::List(chan) => chan.unwatch_recv(oper),
+            ReceiverFlavor::Zero(chan) => chan.unwatch_recv(oper),
+        }
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.recv().ok()
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl<'a, T> Iterator for TryIter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.try_recv().ok()
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl<'a, T> IntoIterator for &'a Receiver<T> {
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        self.rx.recv().ok()
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl<T> IntoIterator for Receiver<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { rx: self }
+    }
+}
@@ -1,3 +1,12 @@
+//! Waiting on several channel operations at once.
+
+use super::context::Context;
+use super::error::{ReadyTimeoutError, TryReadyError};
+
+use crate::fmt;
+use crate::thread;
+use crate::time::{Duration, Instant};
+
 /// Temporary data that gets initialized during a blocking operation, and is consumed by
 /// `read` or `write`.
 ///
@@ -69,3 +78,322 @@ fn into(self) -> usize {
         }
     }
 }
+
+/// A channel operation that [`Select`] can wait on.
+pub(crate) trait SelectHandle {
+    /// Returns `true` if the operation can proceed without blocking.
+    fn is_ready(&self) -> bool;
+
+    /// Registers an operation to be notified when this operation becomes ready, and returns
+    /// `true` if it already is.
+    fn watch(&self, oper: Operation, cx: &Context) -> bool;
+
+    /// Unregisters an operation registered with `watch`.
+    fn unwatch(&self, oper: Operation);
+}
+
+/// How long [`Select::run_ready`] may block.
+#[derive(Clone, Copy)]
+enum Timeout {
+    /// Do not block.
+    Now,
+
+    /// Block until an operation is ready.
+    Never,
+
+    /// Block until an operation is ready or the deadline is reached.
+    At(Instant),
+}
+
+/// Waits until one of several channel operations is ready.
+///
+/// Operations are added with [`send`] and [`recv`], each of which returns the
+/// index of the operation. [`ready`], [`try_ready`], [`ready_timeout`] and
+/// [`ready_deadline`] then wait until at least one of them can proceed
+/// without blocking, and return its index.
+///
+/// A receive operation is ready if a message can be received from its
+/// channel, and a send operation is ready if a message can be sent into it.
+/// For zero-capacity channels, that means a thread is blocked on the other
+/// side of the channel. An operation on a disconnected channel is ready as
+/// well, because it would return an error right away.
+///
+/// Being ready is only a snapshot: another thread may receive the message or
+/// take the free slot before the current thread gets to it. The operation
+/// should therefore be attempted with [`Receiver::try_recv`] or
+/// [`Sender::try_send`], and the whole selection retried if that fails with
+/// an `Empty` or `Full` error.
+///
+/// If several operations are ready, a different one is picked on every call,
+/// so that a busy channel cannot starve the others.
+///
+/// [`send`]: Select::send
+/// [`recv`]: Select::recv
+/// [`ready`]: Select::ready
+/// [`try_ready`]: Select::try_ready
+/// [`ready_timeout`]: Select::ready_timeout
+/// [`ready_deadline`]: Select::ready_deadline
+/// [`Receiver::try_recv`]: super::Receiver::try_recv
+/// [`Sender::try_send`]: super::Sender::try_send
+///
+/// # Examples
+///
+/// Receiving from whichever of two channels gets a message first:
+///
+/// ```
+/// #![feature(mpmc_channel)]
+/// use std::sync::mpmc::{self, Select, TryRecvError};
+/// use std::thread;
+///
+/// let (numbers, number_queue) = mpmc::channel();
+/// let (words, word_queue) = mpmc::channel();
+///
+/// thread::spawn(move || numbers.send(42).unwrap());
+/// thread::spawn(move || words.send("hello").unwrap());
+///
+/// let mut sel = Select::new();
+/// let number_index = sel.recv(&number_queue);
+/// let word_index = sel.recv(&word_queue);
+///
+/// let mut received = 0;
+/// while received < 2 {
+///     let index = sel.ready();
+///     if index == number_index {
+///         match number_queue.try_recv() {
+///             Ok(number) => assert_eq!(number, 42),
+///             // Another thread got there first.
+///             Err(TryRecvError::Empty) => continue,
+///             // The sender is gone, so stop waiting on this channel.
+///             Err(TryRecvError::Disconnected) => {
+///                 sel.remove(number_index);
+///                 continue;
+///             }
+///         }
+///     } else {
+///         assert_eq!(index, word_index);
+///         match word_queue.try_recv() {
+///             Ok(word) => assert_eq!(word, "hello"),
+///             Err(TryRecvError::Empty) => continue,
+///             Err(TryRecvError::Disconnected) => {
+///                 sel.remove(word_index);
+///                 continue;
+///             }
+///         }
+///     }
+///     received += 1;
+/// }
+/// ```
+#[unstable(feature = "mpmc_channel", issue = "none")]
+pub struct Select<'a> {
+    /// The added operations, along with their indices.
+    handles: Vec<(&'a dyn SelectHandle, usize)>,
+
+    /// The index the next added operation will get.
+    next_index: usize,
+
+    /// The position in `handles` at which the next search for a ready operation starts.
+    start: usize,
+}
+
+impl<'a> Select<'a> {
+    /// Creates an empty list of channel operations.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    #[must_use]
+    pub fn new() -> Select<'a> {
+        Select { handles: Vec::new(), next_index: 0, start: 0 }
+    }
+
+    /// Adds a send operation and returns its index.
+    ///
+    /// Indices are assigned in the order operations are added, starting at 0.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn send<T>(&mut self, sender: &'a super::Sender<T>) -> usize {
+        self.add(sender)
+    }
+
+    /// Adds a receive operation and returns its index.
+    ///
+    /// Indices are assigned in the order operations are added, starting at 0.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn recv<T>(&mut self, receiver: &'a super::Receiver<T>) -> usize {
+        self.add(receiver)
+    }
+
+    fn add(&mut self, handle: &'a dyn SelectHandle) -> usize {
+        let index = self.next_index;
+        self.handles.push((handle, index));
+        self.next_index += 1;
+        index
+    }
+
+    /// Removes the operation with the given index.
+    ///
+    /// The indices of the other operations stay the same. This is useful to
+    /// stop waiting on a channel after it has been disconnected, as an
+    /// operation on a disconnected channel is always ready.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no operation with this index was added, or if it has
+    /// already been removed.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn remove(&mut self, index: usize) {
+        let i = self
+            .handles
+            .iter()
+            .position(|&(_, idx)| idx == index)
+            .expect("no operation with this index in `Select`");
+        self.handles.swap_remove(i);
+        self.start = 0;
+    }
+
+    /// Returns the index of an operation which is ready, without blocking.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TryReadyError`] if none of the operations are ready.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn try_ready(&mut self) -> Result<usize, TryReadyError> {
+        self.run_ready(Timeout::Now).ok_or(TryReadyError)
+    }
+
+    /// Blocks until one of the operations is ready, and returns its index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no operations have been added, as this would block forever.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn ready(&mut self) -> usize {
+        if self.handles.is_empty() {
+            panic!("no operations have been added to `Select`");
+        }
+        self.run_ready(Timeout::Never).unwrap()
+    }
+
+    /// Blocks for at most `timeout` until one of the operations is ready, and
+    /// returns its index.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReadyTimeoutError`] if none of the operations became ready
+    /// in time.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn ready_timeout(&mut self, timeout: Duration) -> Result<usize, ReadyTimeoutError> {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.ready_deadline(deadline),
+            // So far in the future that it's practically the same as waiting indefinitely.
+            None => Ok(self.ready()),
+        }
+    }
+
+    /// Blocks until `deadline` at most until one of the operations is ready,
+    /// and returns its index.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReadyTimeoutError`] if none of the operations became ready
+    /// before the deadline.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn ready_deadline(&mut self, deadline: Instant) -> Result<usize, ReadyTimeoutError> {
+        self.run_ready(Timeout::At(deadline)).ok_or(ReadyTimeoutError)
+    }
+
+    /// Waits until an operation is ready and returns its index, or returns `None` on timeout.
+    fn run_ready(&mut self, timeout: Timeout) -> Option<usize> {
+        let len = self.handles.len();
+        if len == 0 {
+            if let Timeout::At(deadline) = timeout {
+                // Nothing can become ready, so just wait for the deadline.
+                let mut now = Instant::now();
+                while now < deadline {
+                    thread::sleep(deadline - now);
+                    now = Instant::now();
+                }
+            }
+            return None;
+        }
+
+        // Start looking at a different operation every time, so that an operation which is
+        // always ready cannot starve the others.
+        let start = self.start;
+        self.start = (start + 1) % len;
+        let order = move |k: usize| (start + k) % len;
+
+        loop {
+            // Check whether any of the operations is ready right now.
+            if let Some(i) = (0..len).map(order).find(|&i| self.handles[i].0.is_ready()) {
+                return Some(self.handles[i].1);
+            }
+
+            let deadline = match timeout {
+                Timeout::Now => return None,
+                Timeout::Never => None,
+                Timeout::At(deadline) if Instant::now() >= deadline => return None,
+                Timeout::At(deadline) => Some(deadline),
+            };
+
+            let sel = Context::with(|cx| {
+                // Register all operations, stopping early once one of them is ready.
+                let mut sel = Selected::Waiting;
+                let mut registered = 0;
+                for i in (0..len).map(order) {
+                    let oper = Operation::hook(&mut self.handles[i]);
+                    registered += 1;
+                    if self.handles[i].0.watch(oper, cx) {
+                        sel = match cx.try_select(Selected::Operation(oper)) {
+                            Ok(()) => Selected::Operation(oper),
+                            Err(s) => s,
+                        };
+                        break;
+                    }
+
+                    // Another operation may have been selected in the meantime.
+                    sel = cx.selected();
+                    if sel != Selected::Waiting {
+                        break;
+                    }
+                }
+
+                // Block the current thread.
+                if sel == Selected::Waiting {
+                    sel = cx.wait_until(deadline);
+                }
+
+                for i in (0..len).map(order).take(registered) {
+                    let oper = Operation::hook(&mut self.handles[i]);
+                    self.handles[i].0.unwatch(oper);
+                }
+
+                sel
+            });
+
+            match sel {
+                Selected::Waiting => unreachable!(),
+                Selected::Operation(oper) => {
+                    if let Some(i) =
+                        (0..len).find(|&i| Operation::hook(&mut self.handles[i]) == oper)
+                    {
+                        return Some(self.handles[i].1);
+                    }
+                }
+                // The deadline was reached, or a channel was disconnected. Either way, check the
+                // operations again.
+                Selected::Aborted | Selected::Disconnected => {}
+            }
+        }
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl<'a> Default for Select<'a> {
+    fn default() -> Select<'a> {
+        Select::new()
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl fmt::Debug for Select<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Select").field("operations", &self.handles.len()).finish_non_exhaustive()
+    }
+}
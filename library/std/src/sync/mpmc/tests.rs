@@ -0,0 +1,173 @@
+use super::*;
+use crate::sync::atomic::{AtomicUsize, Ordering};
+use crate::sync::Arc;
+use crate::thread;
+
+#[test]
+fn smoke() {
+    for (tx, rx) in [channel(), sync_channel(1)] {
+        tx.send(1).unwrap();
+        assert_eq!(rx.recv().unwrap(), 1);
+    }
+
+    let (tx, rx) = sync_channel(0);
+    let t = thread::spawn(move || tx.send(1).unwrap());
+    assert_eq!(rx.recv().unwrap(), 1);
+    t.join().unwrap();
+}
+
+#[test]
+fn capacity() {
+    assert_eq!(channel::<()>().0.capacity(), None);
+    assert_eq!(sync_channel::<()>(0).0.capacity(), Some(0));
+    assert_eq!(sync_channel::<()>(3).1.capacity(), Some(3));
+}
+
+#[test]
+fn multiple_consumers() {
+    const N: usize = 1000;
+    const THREADS: usize = 4;
+
+    for cap in [None, Some(0), Some(1), Some(16)] {
+        let (tx, rx) = match cap {
+            None => channel(),
+            Some(cap) => sync_channel(cap),
+        };
+        let sum = Arc::new(AtomicUsize::new(0));
+        let consumers: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let rx = rx.clone();
+                let sum = sum.clone();
+                thread::spawn(move || {
+                    for i in rx {
+                        sum.fetch_add(i, Ordering::Relaxed);
+                    }
+                })
+            })
+            .collect();
+        drop(rx);
+
+        for i in 0..N {
+            tx.send(i).unwrap();
+        }
+        drop(tx);
+
+        for consumer in consumers {
+            consumer.join().unwrap();
+        }
+        assert_eq!(sum.load(Ordering::Relaxed), N * (N - 1) / 2);
+    }
+}
+
+#[test]
+fn disconnect_with_cloned_receiver() {
+    let (tx, rx) = channel::<i32>();
+    let rx2 = rx.clone();
+    drop(rx);
+    assert!(tx.send(1).is_ok());
+    drop(rx2);
+    assert_eq!(tx.send(2), Err(SendError(2)));
+}
+
+#[test]
+fn select_try_ready() {
+    let (tx1, rx1) = channel::<i32>();
+    let (tx2, rx2) = sync_channel::<i32>(1);
+
+    let mut sel = Select::new();
+    let i1 = sel.recv(&rx1);
+    let i2 = sel.recv(&rx2);
+    assert_eq!((i1, i2), (0, 1));
+    assert_eq!(sel.try_ready(), Err(TryReadyError));
+
+    tx2.send(2).unwrap();
+    assert_eq!(sel.try_ready(), Ok(i2));
+    assert_eq!(rx2.try_recv(), Ok(2));
+    assert_eq!(sel.try_ready(), Err(TryReadyError));
+
+    // A disconnected channel is always ready.
+    drop(tx1);
+    assert_eq!(sel.try_ready(), Ok(i1));
+    sel.remove(i1);
+    assert_eq!(sel.try_ready(), Err(TryReadyError));
+}
+
+#[test]
+fn select_send() {
+    let (tx, rx) = sync_channel::<i32>(1);
+    let (tx0, rx0) = sync_channel::<i32>(0);
+
+    let mut sel = Select::new();
+    let i = sel.send(&tx);
+    let i0 = sel.send(&tx0);
+    assert_eq!(sel.try_ready(), Ok(i));
+    tx.send(1).unwrap();
+    assert_eq!(sel.try_ready(), Err(TryReadyError));
+
+    // A zero-capacity channel becomes ready once a receiver is waiting.
+    let t = thread::spawn(move || rx0.recv().unwrap());
+    assert_eq!(sel.ready(), i0);
+    tx0.try_send(2).unwrap();
+    assert_eq!(t.join().unwrap(), 2);
+    assert_eq!(rx.recv(), Ok(1));
+}
+
+#[test]
+fn select_blocks_until_ready() {
+    let (_tx1, rx1) = channel::<i32>();
+    let (tx2, rx2) = sync_channel::<i32>(0);
+
+    let t = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        tx2.send(2).unwrap();
+    });
+
+    let mut sel = Select::new();
+    sel.recv(&rx1);
+    let i2 = sel.recv(&rx2);
+    assert_eq!(sel.ready(), i2);
+    assert_eq!(rx2.try_recv(), Ok(2));
+    t.join().unwrap();
+}
+
+#[test]
+fn select_timeout() {
+    let (_tx, rx) = channel::<i32>();
+    let mut sel = Select::new();
+    sel.recv(&rx);
+
+    let timeout = Duration::from_millis(20);
+    let start = Instant::now();
+    assert_eq!(sel.ready_timeout(timeout), Err(ReadyTimeoutError));
+    assert!(start.elapsed() >= timeout);
+
+    let start = Instant::now();
+    assert_eq!(Select::new().ready_timeout(timeout), Err(ReadyTimeoutError));
+    assert!(start.elapsed() >= timeout);
+}
+
+#[test]
+fn select_is_fair() {
+    let (tx1, rx1) = channel();
+    let (tx2, rx2) = channel();
+    for _ in 0..10 {
+        tx1.send(()).unwrap();
+        tx2.send(()).unwrap();
+    }
+
+    let mut sel = Select::new();
+    let i1 = sel.recv(&rx1);
+    let i2 = sel.recv(&rx2);
+    let mut hits = [0; 2];
+    for _ in 0..10 {
+        let index = sel.ready();
+        if index == i1 {
+            rx1.try_recv().unwrap();
+        } else {
+            rx2.try_recv().unwrap();
+        }
+        hits[index] += 1;
+    }
+    assert_eq!(hits[i1], 5);
+    assert_eq!(hits[i2], 5);
+}
@@ -319,4 +319,44 @@ pub(crate) fn is_empty(&self) -> bool {
     pub(crate) fn is_full(&self) -> bool {
         true
     }
+
+    /// Returns `true` if a send operation can proceed without blocking, that is if a receiver
+    /// on another thread is waiting to pair up with it.
+    pub(crate) fn is_ready_to_send(&self) -> bool {
+        let inner = self.inner.lock().unwrap();
+        inner.receivers.can_select() || inner.is_disconnected
+    }
+
+    /// Returns `true` if a receive operation can proceed without blocking, that is if a sender
+    /// on another thread is waiting to pair up with it.
+    pub(crate) fn is_ready_to_recv(&self) -> bool {
+        let inner = self.inner.lock().unwrap();
+        inner.senders.can_select() || inner.is_disconnected
+    }
+
+    /// Registers an operation waiting for a send operation to become ready, and returns `true`
+    /// if it already is.
+    pub(crate) fn watch_send(&self, oper: Operation, cx: &Context) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        inner.senders.watch(oper, cx);
+        inner.receivers.can_select() || inner.is_disconnected
+    }
+
+    /// Unregisters an operation registered with `watch_send`.
+    pub(crate) fn unwatch_send(&self, oper: Operation) {
+        self.inner.lock().unwrap().senders.unwatch(oper);
+    }
+
+    /// Registers an operation waiting for a receive operation to become ready, and returns
+    /// `true` if it already is.
+    pub(crate) fn watch_recv(&self, oper: Operation, cx: &Context) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        inner.receivers.watch(oper, cx);
+        inner.senders.can_select() || inner.is_disconnected
+    }
+
+    /// Unregisters an operation registered with `watch_recv`.
+    pub(crate) fn unwatch_recv(&self, oper: Operation) {
+        self.inner.lock().unwrap().receivers.unwatch(oper);
+    }
 }
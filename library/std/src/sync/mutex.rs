@@ -9,6 +9,7 @@
 use crate::ptr::NonNull;
 use crate::sync::{poison, LockResult, TryLockError, TryLockResult};
 use crate::sys::sync as sys;
+use crate::time::{Duration, Instant};
 
 /// A mutual exclusion primitive useful for protecting shared data
 ///
@@ -369,6 +370,108 @@ pub fn try_lock(&self) -> TryLockResult<MutexGuard<'_, T>> {
         }
     }
 
+    /// Attempts to acquire this lock, blocking the current thread for at most
+    /// `timeout`.
+    ///
+    /// If the lock could not be acquired before the timeout expired, then
+    /// [`Err`] is returned. Otherwise, an RAII guard is returned. The lock
+    /// will be unlocked when the guard is dropped.
+    ///
+    /// The lock is always attempted at least once, even if `timeout` is zero.
+    /// The thread may be blocked for somewhat longer than `timeout`, for
+    /// example due to scheduling.
+    ///
+    /// # Errors
+    ///
+    /// If another user of this mutex panicked while holding the mutex, then
+    /// this call will return the [`Poisoned`] error if the mutex would
+    /// otherwise be acquired.
+    ///
+    /// If the mutex could not be acquired before the timeout expired, then
+    /// this call will return the [`WouldBlock`] error.
+    ///
+    /// [`Poisoned`]: TryLockError::Poisoned
+    /// [`WouldBlock`]: TryLockError::WouldBlock
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(lock_timeout)]
+    /// use std::sync::{Mutex, TryLockError};
+    /// use std::time::Duration;
+    ///
+    /// let mutex = Mutex::new(0);
+    ///
+    /// let guard = mutex.lock().unwrap();
+    /// assert!(matches!(
+    ///     mutex.try_lock_for(Duration::from_millis(10)),
+    ///     Err(TryLockError::WouldBlock)
+    /// ));
+    ///
+    /// drop(guard);
+    /// *mutex.try_lock_for(Duration::from_millis(10)).unwrap() += 1;
+    /// assert_eq!(*mutex.lock().unwrap(), 1);
+    /// ```
+    #[unstable(feature = "lock_timeout", issue = "none")]
+    pub fn try_lock_for(&self, timeout: Duration) -> TryLockResult<MutexGuard<'_, T>> {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.try_lock_until(deadline),
+            // A timeout this long can never expire.
+            None => Ok(self.lock()?),
+        }
+    }
+
+    /// Attempts to acquire this lock, blocking the current thread until at
+    /// most `deadline`.
+    ///
+    /// If the lock could not be acquired before the deadline, then [`Err`] is
+    /// returned. Otherwise, an RAII guard is returned. The lock will be
+    /// unlocked when the guard is dropped.
+    ///
+    /// The lock is always attempted at least once, even if `deadline` has
+    /// already passed.
+    ///
+    /// # Errors
+    ///
+    /// If another user of this mutex panicked while holding the mutex, then
+    /// this call will return the [`Poisoned`] error if the mutex would
+    /// otherwise be acquired.
+    ///
+    /// If the mutex could not be acquired before the deadline, then this call
+    /// will return the [`WouldBlock`] error.
+    ///
+    /// [`Poisoned`]: TryLockError::Poisoned
+    /// [`WouldBlock`]: TryLockError::WouldBlock
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(lock_timeout)]
+    /// use std::sync::Mutex;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let queue = Mutex::new(Vec::new());
+    /// let deadline = Instant::now() + Duration::from_millis(100);
+    ///
+    /// // Give up on all of the work at once if it cannot be done in time.
+    /// for job in 0..3 {
+    ///     match queue.try_lock_until(deadline) {
+    ///         Ok(mut queue) => queue.push(job),
+    ///         Err(_) => break,
+    ///     }
+    /// }
+    /// ```
+    #[unstable(feature = "lock_timeout", issue = "none")]
+    pub fn try_lock_until(&self, deadline: Instant) -> TryLockResult<MutexGuard<'_, T>> {
+        unsafe {
+            if self.inner.try_lock_until(deadline) {
+                Ok(MutexGuard::new(self)?)
+            } else {
+                Err(TryLockError::WouldBlock)
+            }
+        }
+    }
+
     /// Determines whether the mutex is poisoned.
     ///
     /// If another thread is active, the mutex can still become poisoned at any
@@ -2,6 +2,7 @@
 use crate::sync::mpsc::channel;
 use crate::sync::{Arc, Condvar, MappedMutexGuard, Mutex, MutexGuard, TryLockError};
 use crate::thread;
+use crate::time::{Duration, Instant};
 
 struct Packet<T>(Arc<(Mutex<T>, Condvar)>);
 
@@ -57,6 +58,45 @@ fn try_lock() {
     *m.try_lock().unwrap() = ();
 }
 
+#[test]
+fn try_lock_for() {
+    let m = Arc::new(Mutex::new(0));
+    *m.try_lock_for(Duration::ZERO).unwrap() += 1;
+    *m.try_lock_for(Duration::MAX).unwrap() += 1;
+
+    let guard = m.lock().unwrap();
+    let start = Instant::now();
+    let timeout = Duration::from_millis(20);
+    assert!(matches!(m.try_lock_for(timeout), Err(TryLockError::WouldBlock)));
+    assert!(start.elapsed() >= timeout);
+    assert!(matches!(m.try_lock_until(Instant::now()), Err(TryLockError::WouldBlock)));
+
+    // The lock is acquired as soon as it is released.
+    let m2 = m.clone();
+    let (tx, rx) = channel();
+    let t = thread::spawn(move || {
+        tx.send(()).unwrap();
+        *m2.try_lock_for(Duration::from_secs(60)).unwrap() += 1;
+    });
+    rx.recv().unwrap();
+    thread::sleep(Duration::from_millis(10));
+    drop(guard);
+    t.join().unwrap();
+    assert_eq!(*m.lock().unwrap(), 3);
+}
+
+#[test]
+fn try_lock_for_poison() {
+    let m = Arc::new(Mutex::new(1));
+    let m2 = m.clone();
+    let _ = thread::spawn(move || {
+        let _lock = m2.lock().unwrap();
+        panic!("test panic in inner thread to poison mutex");
+    })
+    .join();
+    assert!(matches!(m.try_lock_for(Duration::from_millis(10)), Err(TryLockError::Poisoned(_))));
+}
+
 #[test]
 fn test_into_inner() {
     let m = Mutex::new(NonCopy(10));
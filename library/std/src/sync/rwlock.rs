@@ -9,6 +9,7 @@
 use crate::ptr::NonNull;
 use crate::sync::{poison, LockResult, TryLockError, TryLockResult};
 use crate::sys::sync as sys;
+use crate::time::{Duration, Instant};
 
 /// A reader-writer lock
 ///
@@ -324,6 +325,90 @@ pub fn try_read(&self) -> TryLockResult<RwLockReadGuard<'_, T>> {
         }
     }
 
+    /// Attempts to acquire this `RwLock` with shared read access, blocking the
+    /// current thread for at most `timeout`.
+    ///
+    /// If the access could not be granted before the timeout expired, then
+    /// `Err` is returned. Otherwise, an RAII guard is returned which will
+    /// release the shared access when it is dropped.
+    ///
+    /// The lock is always attempted at least once, even if `timeout` is zero.
+    /// The thread may be blocked for somewhat longer than `timeout`, for
+    /// example due to scheduling.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the [`Poisoned`] error if the `RwLock` is
+    /// poisoned. An `RwLock` is poisoned whenever a writer panics while holding
+    /// an exclusive lock. `Poisoned` will only be returned if the lock would
+    /// have otherwise been acquired.
+    ///
+    /// This function will return the [`WouldBlock`] error if the `RwLock` could
+    /// not be acquired before the timeout expired.
+    ///
+    /// [`Poisoned`]: TryLockError::Poisoned
+    /// [`WouldBlock`]: TryLockError::WouldBlock
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(lock_timeout)]
+    /// use std::sync::{RwLock, TryLockError};
+    /// use std::time::Duration;
+    ///
+    /// let lock = RwLock::new(1);
+    ///
+    /// let writer = lock.write().unwrap();
+    /// assert!(matches!(
+    ///     lock.try_read_for(Duration::from_millis(10)),
+    ///     Err(TryLockError::WouldBlock)
+    /// ));
+    ///
+    /// drop(writer);
+    /// assert_eq!(*lock.try_read_for(Duration::from_millis(10)).unwrap(), 1);
+    /// ```
+    #[unstable(feature = "lock_timeout", issue = "none")]
+    pub fn try_read_for(&self, timeout: Duration) -> TryLockResult<RwLockReadGuard<'_, T>> {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.try_read_until(deadline),
+            // A timeout this long can never expire.
+            None => Ok(self.read()?),
+        }
+    }
+
+    /// Attempts to acquire this `RwLock` with shared read access, blocking the
+    /// current thread until at most `deadline`.
+    ///
+    /// If the access could not be granted before the deadline, then `Err` is
+    /// returned. Otherwise, an RAII guard is returned which will release the
+    /// shared access when it is dropped.
+    ///
+    /// The lock is always attempted at least once, even if `deadline` has
+    /// already passed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the [`Poisoned`] error if the `RwLock` is
+    /// poisoned. An `RwLock` is poisoned whenever a writer panics while holding
+    /// an exclusive lock. `Poisoned` will only be returned if the lock would
+    /// have otherwise been acquired.
+    ///
+    /// This function will return the [`WouldBlock`] error if the `RwLock` could
+    /// not be acquired before the deadline.
+    ///
+    /// [`Poisoned`]: TryLockError::Poisoned
+    /// [`WouldBlock`]: TryLockError::WouldBlock
+    #[unstable(feature = "lock_timeout", issue = "none")]
+    pub fn try_read_until(&self, deadline: Instant) -> TryLockResult<RwLockReadGuard<'_, T>> {
+        unsafe {
+            if self.inner.try_read_until(deadline) {
+                Ok(RwLockReadGuard::new(self)?)
+            } else {
+                Err(TryLockError::WouldBlock)
+            }
+        }
+    }
+
     /// Locks this `RwLock` with exclusive write access, blocking the current
     /// thread until it can be acquired.
     ///
@@ -413,6 +498,91 @@ pub fn try_write(&self) -> TryLockResult<RwLockWriteGuard<'_, T>> {
         }
     }
 
+    /// Attempts to lock this `RwLock` with exclusive write access, blocking the
+    /// current thread for at most `timeout`.
+    ///
+    /// If the lock could not be acquired before the timeout expired, then
+    /// `Err` is returned. Otherwise, an RAII guard is returned which will
+    /// release the lock when it is dropped.
+    ///
+    /// The lock is always attempted at least once, even if `timeout` is zero.
+    /// The thread may be blocked for somewhat longer than `timeout`, for
+    /// example due to scheduling.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the [`Poisoned`] error if the `RwLock` is
+    /// poisoned. An `RwLock` is poisoned whenever a writer panics while holding
+    /// an exclusive lock. `Poisoned` will only be returned if the lock would
+    /// have otherwise been acquired.
+    ///
+    /// This function will return the [`WouldBlock`] error if the `RwLock` could
+    /// not be acquired before the timeout expired.
+    ///
+    /// [`Poisoned`]: TryLockError::Poisoned
+    /// [`WouldBlock`]: TryLockError::WouldBlock
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(lock_timeout)]
+    /// use std::sync::{RwLock, TryLockError};
+    /// use std::time::Duration;
+    ///
+    /// let lock = RwLock::new(1);
+    ///
+    /// let reader = lock.read().unwrap();
+    /// assert!(matches!(
+    ///     lock.try_write_for(Duration::from_millis(10)),
+    ///     Err(TryLockError::WouldBlock)
+    /// ));
+    ///
+    /// drop(reader);
+    /// *lock.try_write_for(Duration::from_millis(10)).unwrap() += 1;
+    /// assert_eq!(*lock.read().unwrap(), 2);
+    /// ```
+    #[unstable(feature = "lock_timeout", issue = "none")]
+    pub fn try_write_for(&self, timeout: Duration) -> TryLockResult<RwLockWriteGuard<'_, T>> {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.try_write_until(deadline),
+            // A timeout this long can never expire.
+            None => Ok(self.write()?),
+        }
+    }
+
+    /// Attempts to lock this `RwLock` with exclusive write access, blocking the
+    /// current thread until at most `deadline`.
+    ///
+    /// If the lock could not be acquired before the deadline, then `Err` is
+    /// returned. Otherwise, an RAII guard is returned which will release the
+    /// lock when it is dropped.
+    ///
+    /// The lock is always attempted at least once, even if `deadline` has
+    /// already passed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the [`Poisoned`] error if the `RwLock` is
+    /// poisoned. An `RwLock` is poisoned whenever a writer panics while holding
+    /// an exclusive lock. `Poisoned` will only be returned if the lock would
+    /// have otherwise been acquired.
+    ///
+    /// This function will return the [`WouldBlock`] error if the `RwLock` could
+    /// not be acquired before the deadline.
+    ///
+    /// [`Poisoned`]: TryLockError::Poisoned
+    /// [`WouldBlock`]: TryLockError::WouldBlock
+    #[unstable(feature = "lock_timeout", issue = "none")]
+    pub fn try_write_until(&self, deadline: Instant) -> TryLockResult<RwLockWriteGuard<'_, T>> {
+        unsafe {
+            if self.inner.try_write_until(deadline) {
+                Ok(RwLockWriteGuard::new(self)?)
+            } else {
+                Err(TryLockError::WouldBlock)
+            }
+        }
+    }
+
     /// Determines whether the lock is poisoned.
     ///
     /// If another thread is active, the lock can still become poisoned at any
@@ -5,6 +5,7 @@
     TryLockError,
 };
 use crate::thread;
+use crate::time::{Duration, Instant};
 use rand::Rng;
 
 #[derive(Eq, PartialEq, Debug)]
@@ -250,6 +251,51 @@ fn test_rwlock_try_write() {
     drop(mapped_read_guard);
 }
 
+#[test]
+fn test_rwlock_timed() {
+    let lock = Arc::new(RwLock::new(0));
+    let timeout = Duration::from_millis(20);
+
+    let read_guard = lock.read().unwrap();
+    assert_eq!(*lock.try_read_for(timeout).unwrap(), 0);
+    let start = Instant::now();
+    assert!(matches!(lock.try_write_for(timeout), Err(TryLockError::WouldBlock)));
+    assert!(start.elapsed() >= timeout);
+    drop(read_guard);
+
+    let write_guard = lock.write().unwrap();
+    assert!(matches!(lock.try_read_for(timeout), Err(TryLockError::WouldBlock)));
+    assert!(matches!(lock.try_write_until(Instant::now()), Err(TryLockError::WouldBlock)));
+
+    // Timed out writers must not keep readers waiting.
+    let lock2 = lock.clone();
+    let (tx, rx) = channel();
+    let reader = thread::spawn(move || {
+        tx.send(()).unwrap();
+        *lock2.try_read_for(Duration::from_secs(60)).unwrap()
+    });
+    rx.recv().unwrap();
+    thread::sleep(Duration::from_millis(10));
+    drop(write_guard);
+    assert_eq!(reader.join().unwrap(), 0);
+
+    *lock.try_write_for(Duration::MAX).unwrap() += 1;
+    assert_eq!(*lock.try_read_until(Instant::now()).unwrap(), 1);
+}
+
+#[test]
+fn test_rwlock_timed_poison() {
+    let lock = Arc::new(RwLock::new(1));
+    let lock2 = lock.clone();
+    let _ = thread::spawn(move || {
+        let _lock = lock2.write().unwrap();
+        panic!("test panic in inner thread to poison RwLock");
+    })
+    .join();
+    assert!(matches!(lock.try_read_for(Duration::ZERO), Err(TryLockError::Poisoned(_))));
+    assert!(matches!(lock.try_write_for(Duration::ZERO), Err(TryLockError::Poisoned(_))));
+}
+
 #[test]
 fn test_into_inner() {
     let m = RwLock::new(NonCopy(10));
@@ -18,7 +18,9 @@
 use crate::sys::unsupported;
 use crate::sys_common::{AsInner, AsInnerMut, FromInner, IntoInner};
 
-pub use crate::sys_common::fs::{copy, try_exists};
+pub use crate::sys_common::fs::{
+    copy, copy_with, try_exists, write_atomic, CopyOptions, UnsupportedDirHandle as DirHandle,
+};
 
 #[derive(Debug)]
 pub struct File(FileDesc);
@@ -430,6 +432,26 @@ pub fn set_permissions(&self, _perm: FilePermissions) -> io::Result<()> {
     pub fn set_times(&self, _times: FileTimes) -> io::Result<()> {
         Err(Error::from_raw_os_error(22))
     }
+
+    pub fn lock(&self) -> io::Result<()> {
+        unsupported()
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        unsupported()
+    }
+
+    pub fn try_lock(&self) -> io::Result<bool> {
+        unsupported()
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        unsupported()
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        unsupported()
+    }
 }
 
 impl DirBuilder {
@@ -9,6 +9,8 @@
 
 use super::abi::usercalls;
 
+pub use crate::sys_common::socket_builder::SocketBuilder;
+
 const DEFAULT_FAKE_TTL: u32 = 64;
 
 #[derive(Debug, Clone)]
@@ -12,7 +12,9 @@
     sys::unsupported,
 };
 
-pub use crate::sys_common::fs::try_exists;
+pub use crate::sys_common::fs::{
+    copy_with, try_exists, write_atomic, CopyOptions, UnsupportedDirHandle as DirHandle,
+};
 
 /// A file descriptor.
 #[derive(Clone, Copy)]
@@ -466,6 +468,26 @@ pub fn set_permissions(&self, _perm: FilePermissions) -> io::Result<()> {
     pub fn set_times(&self, _times: FileTimes) -> io::Result<()> {
         unsupported()
     }
+
+    pub fn lock(&self) -> io::Result<()> {
+        unsupported()
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        unsupported()
+    }
+
+    pub fn try_lock(&self) -> io::Result<bool> {
+        unsupported()
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        unsupported()
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        unsupported()
+    }
 }
 
 impl Drop for File {
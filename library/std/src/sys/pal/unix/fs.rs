@@ -101,7 +101,7 @@
 ))]
 use libc::{dirent64, fstat64, ftruncate64, lseek64, lstat64, off64_t, open64, stat64};
 
-pub use crate::sys_common::fs::try_exists;
+pub use crate::sys_common::fs::{try_exists, CopyOptions};
 
 pub struct File(FileDesc);
 
@@ -1099,6 +1099,13 @@ pub fn mode(&mut self, mode: u32) {
         self.mode = mode as mode_t;
     }
 
+    fn get_flags(&self) -> io::Result<c_int> {
+        Ok(libc::O_CLOEXEC
+            | self.get_access_mode()?
+            | self.get_creation_mode()?
+            | (self.custom_flags as c_int & !libc::O_ACCMODE))
+    }
+
     fn get_access_mode(&self) -> io::Result<c_int> {
         match (self.read, self.write, self.append) {
             (true, false, false) => Ok(libc::O_RDONLY),
@@ -1158,10 +1165,7 @@ pub fn open(path: &Path, opts: &OpenOptions) -> io::Result<File> {
     }
 
     pub fn open_c(path: &CStr, opts: &OpenOptions) -> io::Result<File> {
-        let flags = libc::O_CLOEXEC
-            | opts.get_access_mode()?
-            | opts.get_creation_mode()?
-            | (opts.custom_flags as c_int & !libc::O_ACCMODE);
+        let flags = opts.get_flags()?;
         // The third argument of `open64` is documented to have type `mode_t`. On
         // some platforms (like macOS, where `open64` is actually `open`), `mode_t` is `u16`.
         // However, since this is a variadic function, C integer promotion rules mean that on
@@ -1311,6 +1315,77 @@ pub fn duplicate(&self) -> io::Result<File> {
         self.0.duplicate().map(File)
     }
 
+    cfg_if::cfg_if! {
+        if #[cfg(any(
+            target_os = "android",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "fuchsia",
+            target_os = "linux",
+            target_os = "netbsd",
+            target_os = "openbsd",
+            target_vendor = "apple",
+        ))] {
+            pub fn lock(&self) -> io::Result<()> {
+                self.flock(libc::LOCK_EX)
+            }
+
+            pub fn lock_shared(&self) -> io::Result<()> {
+                self.flock(libc::LOCK_SH)
+            }
+
+            pub fn try_lock(&self) -> io::Result<bool> {
+                self.try_flock(libc::LOCK_EX)
+            }
+
+            pub fn try_lock_shared(&self) -> io::Result<bool> {
+                self.try_flock(libc::LOCK_SH)
+            }
+
+            pub fn unlock(&self) -> io::Result<()> {
+                self.flock(libc::LOCK_UN)
+            }
+
+            fn flock(&self, operation: c_int) -> io::Result<()> {
+                cvt_r(|| unsafe { libc::flock(self.as_raw_fd(), operation) })?;
+                Ok(())
+            }
+
+            fn try_flock(&self, operation: c_int) -> io::Result<bool> {
+                match self.flock(operation | libc::LOCK_NB) {
+                    Ok(()) => Ok(true),
+                    Err(err) if err.kind() == io::ErrorKind::WouldBlock => Ok(false),
+                    Err(err) => Err(err),
+                }
+            }
+        } else {
+            const LOCK_UNSUPPORTED: io::Error = io::const_io_error!(
+                io::ErrorKind::Unsupported,
+                "file locking is not supported on this platform",
+            );
+
+            pub fn lock(&self) -> io::Result<()> {
+                Err(Self::LOCK_UNSUPPORTED)
+            }
+
+            pub fn lock_shared(&self) -> io::Result<()> {
+                Err(Self::LOCK_UNSUPPORTED)
+            }
+
+            pub fn try_lock(&self) -> io::Result<bool> {
+                Err(Self::LOCK_UNSUPPORTED)
+            }
+
+            pub fn try_lock_shared(&self) -> io::Result<bool> {
+                Err(Self::LOCK_UNSUPPORTED)
+            }
+
+            pub fn unlock(&self) -> io::Result<()> {
+                Err(Self::LOCK_UNSUPPORTED)
+            }
+        }
+    }
+
     pub fn set_permissions(&self, perm: FilePermissions) -> io::Result<()> {
         cvt_r(|| unsafe { libc::fchmod(self.as_raw_fd(), perm.mode) })?;
         Ok(())
@@ -1807,6 +1882,90 @@ pub fn lstat(p: &Path) -> io::Result<FileAttr> {
     })
 }
 
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    #[cfg(target_os = "linux")]
+    let written = write_atomic_tmpfile(path, contents)?;
+    #[cfg(not(target_os = "linux"))]
+    let written = false;
+    if !written {
+        crate::sys_common::fs::write_atomic(path, contents)?;
+    }
+
+    // The new contents are only durable once the directory entry pointing at them is.
+    let mut opts = OpenOptions::new();
+    opts.read(true);
+    File::open(atomic_write_dir(path), &opts)?.fsync()
+}
+
+fn atomic_write_dir(path: &Path) -> &Path {
+    match path.parent() {
+        Some(dir) if dir != Path::new("") => dir,
+        _ => Path::new("."),
+    }
+}
+
+// Writes the contents to an unnamed file, which can't be observed half-written even by a
+// process listing the directory, and only gives it a name once it is complete. Returns
+// `Ok(false)` without writing anything if the kernel or filesystem doesn't support this.
+#[cfg(target_os = "linux")]
+fn write_atomic_tmpfile(path: &Path, contents: &[u8]) -> io::Result<bool> {
+    use crate::sys_common::fs::atomic_temp_path;
+
+    let mut tmp = atomic_temp_path(path)?;
+    let mut opts = OpenOptions::new();
+    opts.write(true);
+    opts.custom_flags(libc::O_TMPFILE);
+    let file = match File::open(atomic_write_dir(path), &opts) {
+        Ok(file) => crate::fs::File::from_inner(file),
+        Err(e)
+            if matches!(
+                e.raw_os_error(),
+                Some(libc::EOPNOTSUPP | libc::EISDIR | libc::EINVAL | libc::ENOENT)
+            ) =>
+        {
+            return Ok(false);
+        }
+        Err(e) => return Err(e),
+    };
+    io::Write::write_all(&mut &file, contents)?;
+    file.sync_all()?;
+
+    // Linking the file through `/proc` works without the privileges that `AT_EMPTY_PATH` needs.
+    // `linkat` can't replace an existing file, so link it to a fresh name and rename that.
+    let src = format!("/proc/self/fd/{}", file.as_raw_fd());
+    let mut attempt = 0;
+    loop {
+        let linked = run_path_with_cstr(Path::new(&src), &|src| {
+            run_path_with_cstr(&tmp, &|dst| {
+                cvt(unsafe {
+                    libc::linkat(
+                        libc::AT_FDCWD,
+                        src.as_ptr(),
+                        libc::AT_FDCWD,
+                        dst.as_ptr(),
+                        libc::AT_SYMLINK_FOLLOW,
+                    )
+                })
+            })
+        });
+        match linked {
+            Ok(_) => break,
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists && attempt < 16 => {
+                attempt += 1;
+                tmp = atomic_temp_path(path)?;
+            }
+            // `/proc` isn't mounted.
+            Err(e) if e.raw_os_error() == Some(libc::ENOENT) => return Ok(false),
+            Err(e) => return Err(e),
+        }
+    }
+    if let Err(e) = rename(&tmp, path) {
+        let _ = unlink(&tmp);
+        return Err(e);
+    }
+    Ok(true)
+}
+
 pub fn canonicalize(p: &Path) -> io::Result<PathBuf> {
     let r = run_path_with_cstr(p, &|path| unsafe {
         Ok(libc::realpath(path.as_ptr(), ptr::null_mut()))
@@ -1980,6 +2139,156 @@ fn fclonefileat(
     Ok(bytes_copied as u64)
 }
 
+pub fn copy_with(from: &Path, to: &Path, opts: &CopyOptions) -> io::Result<u64> {
+    use crate::fs::Reflink;
+    use crate::sys_common::fs::{copy_symlink, copy_times, REFLINK_UNSUPPORTED_ERROR};
+
+    if !opts.follow_symlinks && copy_symlink(from, to)? {
+        return Ok(0);
+    }
+
+    let (mut reader, reader_metadata) = open_from(from)?;
+    let (mut writer, writer_metadata) = if opts.permissions {
+        open_to_and_set_permissions(to, reader_metadata.clone())?
+    } else {
+        let writer = crate::fs::File::create(to)?;
+        let writer_metadata = writer.metadata()?;
+        (writer, writer_metadata)
+    };
+
+    // Pipes/FIFOs and device nodes can neither share extents nor have holes, and
+    // their attributes are left alone.
+    if !writer_metadata.is_file() {
+        if opts.reflink == Reflink::Always {
+            return Err(REFLINK_UNSUPPORTED_ERROR);
+        }
+        return io::copy(&mut reader, &mut writer);
+    }
+
+    let cloned = match opts.reflink {
+        Reflink::Never => false,
+        Reflink::Auto => clone_file(&reader, &writer).is_ok(),
+        Reflink::Always => {
+            clone_file(&reader, &writer)?;
+            true
+        }
+    };
+    let ret = if cloned {
+        reader_metadata.len()
+    } else if opts.sparse {
+        copy_sparse(&reader, &writer, reader_metadata.len(), opts.reflink != Reflink::Never)?
+    } else if opts.reflink == Reflink::Never {
+        // `io::copy` may offload the copy to the kernel, which is free to share extents.
+        io::copy::generic_copy(&mut reader, &mut writer)?
+    } else {
+        io::copy(&mut reader, &mut writer)?
+    };
+
+    if opts.timestamps {
+        copy_times(&writer, &reader_metadata)?;
+    }
+    Ok(ret)
+}
+
+/// Makes `writer` share the extents of `reader`, so that it has the same contents without
+/// any data being copied until either file is modified.
+#[cfg(target_os = "linux")]
+fn clone_file(reader: &crate::fs::File, writer: &crate::fs::File) -> io::Result<()> {
+    // `FICLONE` is `_IOW(0x94, 9, int)`, whose encoding depends on the architecture.
+    #[cfg(any(
+        target_arch = "mips",
+        target_arch = "mips32r6",
+        target_arch = "mips64",
+        target_arch = "mips64r6",
+        target_arch = "powerpc",
+        target_arch = "powerpc64",
+        target_arch = "sparc",
+        target_arch = "sparc64",
+    ))]
+    const FICLONE: u32 = 0x8004_9409;
+    #[cfg(not(any(
+        target_arch = "mips",
+        target_arch = "mips32r6",
+        target_arch = "mips64",
+        target_arch = "mips64r6",
+        target_arch = "powerpc",
+        target_arch = "powerpc64",
+        target_arch = "sparc",
+        target_arch = "sparc64",
+    )))]
+    const FICLONE: u32 = 0x4004_9409;
+
+    cvt(unsafe { libc::ioctl(writer.as_raw_fd(), FICLONE as _, reader.as_raw_fd()) })?;
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn clone_file(_reader: &crate::fs::File, _writer: &crate::fs::File) -> io::Result<()> {
+    Err(crate::sys_common::fs::REFLINK_UNSUPPORTED_ERROR)
+}
+
+/// Copies only the data regions of `reader`, leaving holes in `writer` where `reader` has
+/// them, so that a sparse file doesn't take up more space once copied.
+#[cfg(target_os = "linux")]
+fn copy_sparse(
+    reader: &crate::fs::File,
+    writer: &crate::fs::File,
+    len: u64,
+    offload: bool,
+) -> io::Result<u64> {
+    let fd = reader.as_raw_fd();
+    let mut pos = 0;
+    while pos < len {
+        let data = match cvt(unsafe { lseek64(fd, pos as off64_t, libc::SEEK_DATA) }) {
+            Ok(data) => data as u64,
+            // Only a hole is left until the end of the file.
+            Err(e) if e.raw_os_error() == Some(libc::ENXIO) => break,
+            Err(e) => return Err(e),
+        };
+        let hole = cvt(unsafe { lseek64(fd, data as off64_t, libc::SEEK_HOLE) })? as u64;
+        copy_range(reader, writer, data, hole - data, offload)?;
+        pos = hole;
+    }
+    // Extending the file rather than writing zeros keeps a trailing hole.
+    writer.set_len(len)?;
+    Ok(len)
+}
+
+#[cfg(target_os = "linux")]
+fn copy_range(
+    mut reader: &crate::fs::File,
+    mut writer: &crate::fs::File,
+    offset: u64,
+    len: u64,
+    offload: bool,
+) -> io::Result<()> {
+    use super::kernel_copy::{copy_regular_files, CopyResult};
+    use crate::io::{Read, Seek};
+
+    reader.seek(SeekFrom::Start(offset))?;
+    writer.seek(SeekFrom::Start(offset))?;
+    let mut written = 0;
+    if offload {
+        match copy_regular_files(reader.as_raw_fd(), writer.as_raw_fd(), len) {
+            CopyResult::Ended(_) => return Ok(()),
+            CopyResult::Error(e, _) => return Err(e),
+            CopyResult::Fallback(bytes) => written = bytes,
+        }
+    }
+    io::copy::generic_copy(&mut reader.take(len - written), &mut writer)?;
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn copy_sparse(
+    mut reader: &crate::fs::File,
+    mut writer: &crate::fs::File,
+    _len: u64,
+    _offload: bool,
+) -> io::Result<u64> {
+    io::copy(&mut reader, &mut writer)
+}
+
 pub fn chown(path: &Path, uid: u32, gid: u32) -> io::Result<()> {
     run_path_with_cstr(path, &|path| {
         cvt(unsafe { libc::chown(path.as_ptr(), uid as libc::uid_t, gid as libc::gid_t) })
@@ -2006,6 +2315,28 @@ pub fn chroot(dir: &Path) -> io::Result<()> {
 
 pub use remove_dir_impl::remove_dir_all;
 
+#[cfg(any(
+    target_os = "redox",
+    target_os = "espidf",
+    target_os = "horizon",
+    target_os = "vita",
+    target_os = "nto",
+    target_os = "l4re",
+    miri
+))]
+pub use crate::sys_common::fs::UnsupportedDirHandle as DirHandle;
+
+#[cfg(not(any(
+    target_os = "redox",
+    target_os = "espidf",
+    target_os = "horizon",
+    target_os = "vita",
+    target_os = "nto",
+    target_os = "l4re",
+    miri
+)))]
+pub use dir_handle::DirHandle;
+
 // Fallback for REDOX, ESP-ID, Horizon, Vita and Miri
 #[cfg(any(
     target_os = "redox",
@@ -2049,7 +2380,7 @@ mod remove_dir_impl {
     use macos_weak::{fdopendir, openat, unlinkat};
 
     #[cfg(all(target_os = "macos", not(target_arch = "aarch64")))]
-    mod macos_weak {
+    pub(super) mod macos_weak {
         use crate::sys::weak::weak;
         use libc::{c_char, c_int, DIR};
 
@@ -2216,3 +2547,179 @@ pub fn remove_dir_all(p: &Path) -> io::Result<()> {
         }
     }
 }
+
+// Operations relative to an open directory, using openat() and friends
+#[cfg(not(any(
+    target_os = "redox",
+    target_os = "espidf",
+    target_os = "horizon",
+    target_os = "vita",
+    target_os = "nto",
+    target_os = "l4re",
+    miri
+)))]
+mod dir_handle {
+    #[cfg(any(
+        all(target_os = "linux", not(target_env = "musl")),
+        target_os = "emscripten",
+        target_os = "android",
+        target_os = "hurd"
+    ))]
+    use super::fstatat64;
+    use super::{stat64, Dir, File, FileAttr, InnerReadDir, OpenOptions, ReadDir};
+    use crate::ffi::CStr;
+    use crate::fmt;
+    use crate::io;
+    use crate::mem;
+    use crate::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd};
+    use crate::os::unix::prelude::OwnedFd;
+    use crate::path::{Path, PathBuf};
+    use crate::sys::common::small_c_string::run_path_with_cstr;
+    use crate::sys::fd::FileDesc;
+    use crate::sys::{cvt, cvt_r};
+
+    #[cfg(all(target_os = "macos", not(target_arch = "aarch64")))]
+    use super::remove_dir_impl::macos_weak::{fdopendir, openat, unlinkat};
+    #[cfg(not(any(
+        all(target_os = "linux", not(target_env = "musl")),
+        target_os = "emscripten",
+        target_os = "android",
+        target_os = "hurd"
+    )))]
+    use libc::fstatat as fstatat64;
+    #[cfg(not(any(
+        all(target_os = "linux", target_env = "gnu"),
+        all(target_os = "macos", not(target_arch = "aarch64"))
+    )))]
+    use libc::{fdopendir, openat, unlinkat};
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    use libc::{fdopendir, openat64 as openat, unlinkat};
+
+    pub struct DirHandle {
+        fd: OwnedFd,
+        // Only used to build the paths of `DirEntry`s and for `Debug`.
+        path: PathBuf,
+    }
+
+    impl DirHandle {
+        pub fn open(path: &Path) -> io::Result<DirHandle> {
+            let fd = run_path_with_cstr(path, &|p| open_dir(libc::AT_FDCWD, p, 0))?;
+            Ok(DirHandle { fd, path: path.to_path_buf() })
+        }
+
+        pub fn open_dir_at(&self, path: &Path) -> io::Result<DirHandle> {
+            let fd =
+                run_path_with_cstr(path, &|p| open_dir(self.fd.as_raw_fd(), p, libc::O_NOFOLLOW))?;
+            Ok(DirHandle { fd, path: self.path.join(path) })
+        }
+
+        pub fn open_file_at(&self, path: &Path, opts: &OpenOptions) -> io::Result<File> {
+            let flags = opts.get_flags()?;
+            run_path_with_cstr(path, &|p| {
+                // See `File::open_c` for why the mode is passed as a `c_int`.
+                let fd = cvt_r(|| unsafe {
+                    openat(self.fd.as_raw_fd(), p.as_ptr(), flags, opts.mode as libc::c_int)
+                })?;
+                Ok(File(unsafe { FileDesc::from_raw_fd(fd) }))
+            })
+        }
+
+        pub fn create_dir_at(&self, path: &Path) -> io::Result<()> {
+            run_path_with_cstr(path, &|p| {
+                cvt(unsafe { libc::mkdirat(self.fd.as_raw_fd(), p.as_ptr(), 0o777) }).map(|_| ())
+            })
+        }
+
+        pub fn remove_at(&self, path: &Path) -> io::Result<()> {
+            run_path_with_cstr(path, &|p| {
+                // Don't stat first to pick the flag: the entry could be swapped between the two
+                // calls. Try it as a file and only fall back to removing a directory if `unlinkat`
+                // says it is one (`EISDIR` on Linux, `EPERM` as POSIX allows elsewhere).
+                let fd = self.fd.as_raw_fd();
+                let err = match cvt(unsafe { unlinkat(fd, p.as_ptr(), 0) }) {
+                    Ok(_) => return Ok(()),
+                    Err(e) if matches!(e.raw_os_error(), Some(libc::EISDIR | libc::EPERM)) => e,
+                    Err(e) => return Err(e),
+                };
+                match cvt(unsafe { unlinkat(fd, p.as_ptr(), libc::AT_REMOVEDIR) }) {
+                    Ok(_) => Ok(()),
+                    // Not a directory after all, so `EPERM` was a real permission error.
+                    Err(e) if e.raw_os_error() == Some(libc::ENOTDIR) => Err(err),
+                    Err(e) => Err(e),
+                }
+            })
+        }
+
+        pub fn rename_at(&self, from: &Path, to_dir: &DirHandle, to: &Path) -> io::Result<()> {
+            run_path_with_cstr(from, &|from| {
+                run_path_with_cstr(to, &|to| {
+                    cvt(unsafe {
+                        libc::renameat(
+                            self.fd.as_raw_fd(),
+                            from.as_ptr(),
+                            to_dir.fd.as_raw_fd(),
+                            to.as_ptr(),
+                        )
+                    })
+                    .map(|_| ())
+                })
+            })
+        }
+
+        pub fn stat_at(&self, path: &Path, follow_symlinks: bool) -> io::Result<FileAttr> {
+            run_path_with_cstr(path, &|p| self.stat_at_c(p, follow_symlinks))
+        }
+
+        fn stat_at_c(&self, path: &CStr, follow_symlinks: bool) -> io::Result<FileAttr> {
+            let flags = if follow_symlinks { 0 } else { libc::AT_SYMLINK_NOFOLLOW };
+
+            cfg_has_statx! {
+                if let Some(ret) = unsafe { super::try_statx(
+                    self.fd.as_raw_fd(),
+                    path.as_ptr(),
+                    flags | libc::AT_STATX_SYNC_AS_STAT,
+                    libc::STATX_ALL,
+                ) } {
+                    return ret;
+                }
+            }
+
+            let mut stat: stat64 = unsafe { mem::zeroed() };
+            cvt(unsafe { fstatat64(self.fd.as_raw_fd(), path.as_ptr(), &mut stat, flags) })?;
+            Ok(FileAttr::from_stat64(stat))
+        }
+
+        pub fn read_dir(&self) -> io::Result<ReadDir> {
+            // The directory stream takes ownership of its descriptor, so give it a duplicate.
+            let fd = self.fd.try_clone()?;
+            let ptr = unsafe { fdopendir(fd.as_raw_fd()) };
+            if ptr.is_null() {
+                return Err(io::Error::last_os_error());
+            }
+            // file descriptor is automatically closed by libc::closedir() now, so give up ownership
+            let _ = fd.into_raw_fd();
+            // The duplicate shares its offset with our descriptor, which an earlier `read_dir`
+            // may have moved, so start over from the first entry.
+            unsafe { libc::rewinddir(ptr) };
+            let inner = InnerReadDir { dirp: Dir(ptr), root: self.path.clone() };
+            Ok(ReadDir::new(inner))
+        }
+    }
+
+    fn open_dir(parent_fd: libc::c_int, path: &CStr, flags: libc::c_int) -> io::Result<OwnedFd> {
+        let fd = cvt_r(|| unsafe {
+            openat(
+                parent_fd,
+                path.as_ptr(),
+                libc::O_CLOEXEC | libc::O_RDONLY | libc::O_DIRECTORY | flags,
+            )
+        })?;
+        Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+    }
+
+    impl fmt::Debug for DirHandle {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("DirHandle").field("fd", &self.fd).field("path", &self.path).finish()
+        }
+    }
+}
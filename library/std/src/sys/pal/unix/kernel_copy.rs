@@ -46,8 +46,8 @@
 use crate::fs::{File, Metadata};
 use crate::io::copy::generic_copy;
 use crate::io::{
-    BufRead, BufReader, BufWriter, Error, Read, Result, StderrLock, StdinLock, StdoutLock, Take,
-    Write,
+    BufRead, BufReader, BufWriter, Error, Read, Result, SpliceMethod, StderrLock, StdinLock,
+    StdoutLock, Take, Write,
 };
 use crate::mem::ManuallyDrop;
 use crate::net::TcpStream;
@@ -57,8 +57,9 @@
 use crate::process::{ChildStderr, ChildStdin, ChildStdout};
 use crate::ptr;
 use crate::sync::atomic::{AtomicBool, AtomicU8, Ordering};
-use crate::sys::cvt;
+use crate::sys::pipe::{anon_pipe, AnonPipe};
 use crate::sys::weak::syscall;
+use crate::sys::{cvt, cvt_r};
 #[cfg(not(any(all(target_os = "linux", target_env = "gnu"), target_os = "hurd")))]
 use libc::sendfile as sendfile64;
 #[cfg(any(all(target_os = "linux", target_env = "gnu"), target_os = "hurd"))]
@@ -645,6 +646,25 @@ fn copy_file_range(
     CopyResult::Ended(written)
 }
 
+static HAS_SPLICE: AtomicBool = AtomicBool::new(true);
+
+// Android builds use feature level 14, but the libc wrapper for splice is
+// gated on feature level 21+, so we have to invoke the syscall directly.
+#[cfg(target_os = "android")]
+syscall! {
+    fn splice(
+        srcfd: libc::c_int,
+        src_offset: *const i64,
+        dstfd: libc::c_int,
+        dst_offset: *const i64,
+        len: libc::size_t,
+        flags: libc::c_int
+    ) -> libc::ssize_t
+}
+
+#[cfg(target_os = "linux")]
+use libc::splice;
+
 #[derive(PartialEq)]
 enum SpliceMode {
     Sendfile,
@@ -655,24 +675,6 @@ enum SpliceMode {
 /// Does _not_ fall back to a generic copy loop.
 fn sendfile_splice(mode: SpliceMode, reader: RawFd, writer: RawFd, len: u64) -> CopyResult {
     static HAS_SENDFILE: AtomicBool = AtomicBool::new(true);
-    static HAS_SPLICE: AtomicBool = AtomicBool::new(true);
-
-    // Android builds use feature level 14, but the libc wrapper for splice is
-    // gated on feature level 21+, so we have to invoke the syscall directly.
-    #[cfg(target_os = "android")]
-    syscall! {
-        fn splice(
-            srcfd: libc::c_int,
-            src_offset: *const i64,
-            dstfd: libc::c_int,
-            dst_offset: *const i64,
-            len: libc::size_t,
-            flags: libc::c_int
-        ) -> libc::ssize_t
-    }
-
-    #[cfg(target_os = "linux")]
-    use libc::splice;
 
     match mode {
         SpliceMode::Sendfile if !HAS_SENDFILE.load(Ordering::Relaxed) => {
@@ -728,3 +730,149 @@ fn splice(
     }
     CopyResult::Ended(written)
 }
+
+/// Moves up to `max_len` bytes from `reader` to `writer` for `io::splice`.
+///
+/// This tries the same syscalls as `io::copy` and additionally splices through an intermediate
+/// pipe, which also covers socket-to-socket transfers. Unlike the `io::copy` specializations the
+/// file descriptors come from user types that opted in via `AsSpliceHandle`, so there are no
+/// wrapper buffers to drain.
+///
+/// Returns the number of bytes moved and, if the transfer is complete, the method used.
+/// `None` means the caller has to move the remaining bytes with a read-write loop.
+pub(crate) fn splice_fds(
+    reader: RawFd,
+    writer: RawFd,
+    max_len: u64,
+) -> Result<(u64, Option<SpliceMethod>)> {
+    let input_meta = fd_to_meta(&reader);
+    let output_meta = fd_to_meta(&writer);
+    let mut written = 0u64;
+
+    if input_meta.copy_file_range_candidate(FdHandle::Input)
+        && output_meta.copy_file_range_candidate(FdHandle::Output)
+    {
+        match copy_regular_files(reader, writer, max_len) {
+            CopyResult::Ended(bytes) => return Ok((bytes, Some(SpliceMethod::CopyFileRange))),
+            CopyResult::Error(e, _) => return Err(e),
+            CopyResult::Fallback(bytes) => written += bytes,
+        }
+    }
+
+    if written > 0 || !safe_kernel_copy(&input_meta, &output_meta) {
+        return Ok((written, None));
+    }
+
+    if input_meta.potential_sendfile_source() {
+        match sendfile_splice(SpliceMode::Sendfile, reader, writer, max_len) {
+            CopyResult::Ended(bytes) => return Ok((bytes, Some(SpliceMethod::Sendfile))),
+            CopyResult::Error(e, _) => return Err(e),
+            CopyResult::Fallback(0) => {}
+            CopyResult::Fallback(bytes) => return Ok((bytes, None)),
+        }
+    }
+
+    let result = if input_meta.maybe_fifo() || output_meta.maybe_fifo() {
+        sendfile_splice(SpliceMode::Splice, reader, writer, max_len)
+    } else {
+        splice_through_pipe(reader, writer, max_len)
+    };
+    match result {
+        CopyResult::Ended(bytes) => Ok((bytes, Some(SpliceMethod::Splice))),
+        CopyResult::Error(e, _) => Err(e),
+        CopyResult::Fallback(bytes) => Ok((bytes, None)),
+    }
+}
+
+/// Moves data from `reader` to `writer` with `splice(2)` through an intermediate pipe, since
+/// `splice` requires one side of each call to be a pipe.
+///
+/// Each round splices at most the pipe's default capacity into the pipe, so that the first call
+/// returns as soon as the reader has some data instead of waiting for a fixed amount, and then
+/// drains the pipe completely into the writer. If the writer turns out not to support `splice`,
+/// the bytes already in the pipe are copied through a userspace buffer and `Fallback` is returned
+/// so that the caller continues with a read-write loop.
+fn splice_through_pipe(reader: RawFd, writer: RawFd, len: u64) -> CopyResult {
+    // The default pipe capacity on Linux.
+    const PIPE_CHUNK: u64 = 64 * 1024;
+
+    if !HAS_SPLICE.load(Ordering::Relaxed) {
+        return CopyResult::Fallback(0);
+    }
+
+    let (pipe_reader, pipe_writer) = match anon_pipe() {
+        Ok(pipe) => pipe,
+        Err(err) => return CopyResult::Error(err, 0),
+    };
+
+    let mut written = 0u64;
+    while written < len {
+        let chunk_size = min(len - written, PIPE_CHUNK) as usize;
+        let filled = cvt_r(|| unsafe {
+            splice(
+                reader,
+                ptr::null_mut(),
+                pipe_writer.as_raw_fd(),
+                ptr::null_mut(),
+                chunk_size,
+                libc::SPLICE_F_MOVE as _,
+            )
+        });
+        let mut pending = match filled {
+            Ok(0) => break, // EOF
+            Ok(ret) => ret as usize,
+            Err(err) => {
+                return match err.raw_os_error() {
+                    Some(ENOSYS | EPERM) if written == 0 => {
+                        HAS_SPLICE.store(false, Ordering::Relaxed);
+                        CopyResult::Fallback(0)
+                    }
+                    Some(EINVAL) if written == 0 => CopyResult::Fallback(0),
+                    _ => CopyResult::Error(err, written),
+                };
+            }
+        };
+
+        while pending > 0 {
+            let drained = cvt_r(|| unsafe {
+                splice(
+                    pipe_reader.as_raw_fd(),
+                    ptr::null_mut(),
+                    writer,
+                    ptr::null_mut(),
+                    pending,
+                    libc::SPLICE_F_MOVE as _,
+                )
+            });
+            match drained {
+                Ok(0) => return CopyResult::Error(Error::WRITE_ALL_EOF, written),
+                Ok(ret) => {
+                    pending -= ret as usize;
+                    written += ret as u64;
+                }
+                Err(err) if matches!(err.raw_os_error(), Some(EINVAL)) => {
+                    // The writer does not support splice, copy what is already in the pipe by hand.
+                    return match copy_pipe_contents(&pipe_reader, writer, pending) {
+                        Ok(()) => CopyResult::Fallback(written + pending as u64),
+                        Err(err) => CopyResult::Error(err, written),
+                    };
+                }
+                Err(err) => return CopyResult::Error(err, written),
+            }
+        }
+    }
+    CopyResult::Ended(written)
+}
+
+/// Writes the next `len` bytes buffered in `pipe` to `writer` through a userspace buffer.
+fn copy_pipe_contents(pipe: &AnonPipe, writer: RawFd, mut len: usize) -> Result<()> {
+    let mut writer = ManuallyDrop::new(unsafe { File::from_raw_fd(writer) });
+    let mut buf = [0u8; 8 * 1024];
+    while len > 0 {
+        let chunk_size = min(len, buf.len());
+        let n = pipe.read(&mut buf[..chunk_size])?;
+        writer.write_all(&buf[..n])?;
+        len -= n;
+    }
+    Ok(())
+}
@@ -125,6 +125,77 @@ fn dont_splice_pipes_from_files() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn splice_between_sockets() -> Result<()> {
+    use crate::io::SpliceMethod;
+    use crate::os::unix::net::UnixStream;
+    use crate::sync::atomic::Ordering;
+
+    let (mut source, mut source_peer) = UnixStream::pair()?;
+    let (mut sink, mut sink_peer) = UnixStream::pair()?;
+
+    source_peer.write_all(b"hello world")?;
+    drop(source_peer);
+
+    // sockets are neither regular files nor pipes, so this has to go through an intermediate pipe,
+    // unless `splice` is unsupported or forbidden (e.g. by seccomp) and it falls back to a buffer
+    let check_method = |method: SpliceMethod| match method {
+        SpliceMethod::Splice => {}
+        SpliceMethod::Buffered => assert!(!super::HAS_SPLICE.load(Ordering::Relaxed)),
+        method => panic!("unexpected method {method:?}"),
+    };
+    let (moved, method) = io::splice(&mut source, &mut sink, 5)?;
+    assert_eq!(moved, 5);
+    check_method(method);
+    let (moved, method) = io::splice(&mut source, &mut sink, u64::MAX)?;
+    assert_eq!(moved, 6);
+    check_method(method);
+    drop(sink);
+
+    let mut received = Vec::new();
+    sink_peer.read_to_end(&mut received)?;
+    assert_eq!(received, b"hello world");
+
+    Ok(())
+}
+
+#[test]
+fn splice_falls_back_to_buffered_copy() -> Result<()> {
+    use crate::io::{AsSpliceHandle, SpliceHandle, SpliceMethod};
+    use crate::os::unix::net::UnixStream;
+
+    // A wrapper which transforms the data, and so must not be bypassed.
+    struct Upper(UnixStream);
+
+    impl Read for Upper {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            let n = self.0.read(buf)?;
+            buf[..n].make_ascii_uppercase();
+            Ok(n)
+        }
+    }
+
+    impl AsSpliceHandle for Upper {
+        fn as_splice_handle(&self) -> Option<SpliceHandle<'_>> {
+            None
+        }
+    }
+
+    let (source, mut source_peer) = UnixStream::pair()?;
+    let (mut sink, mut sink_peer) = UnixStream::pair()?;
+    source_peer.write_all(b"hello")?;
+    drop(source_peer);
+
+    assert_eq!(io::splice(&mut Upper(source), &mut sink, 64)?, (5, SpliceMethod::Buffered));
+    drop(sink);
+
+    let mut received = Vec::new();
+    sink_peer.read_to_end(&mut received)?;
+    assert_eq!(received, b"HELLO");
+
+    Ok(())
+}
+
 #[bench]
 fn bench_file_to_file_copy(b: &mut test::Bencher) {
     const BYTES: usize = 128 * 1024;
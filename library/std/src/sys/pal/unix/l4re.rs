@@ -20,6 +20,8 @@ pub mod net {
     #[allow(unused_extern_crates)]
     pub extern crate libc as netc;
 
+    pub use crate::sys_common::socket_builder::SocketBuilder;
+
     pub struct Socket(FileDesc);
     impl Socket {
         pub fn new(_: &SocketAddr, _: libc::c_int) -> io::Result<Socket> {
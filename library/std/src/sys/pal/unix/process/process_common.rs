@@ -646,6 +646,44 @@ fn from(code: u8) -> Self {
     }
 }
 
+/// Waits for a child to exit for at most `timeout` by polling `try_wait`.
+///
+/// This is the fallback of `Process::wait_timeout` where there is no way to be
+/// notified of the exit of a child. The delay between attempts doubles each
+/// time, so short-lived children are noticed quickly without spinning on
+/// long-lived ones.
+#[cfg(not(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "dragonfly",
+)))]
+pub fn wait_timeout_by_polling<T>(
+    timeout: crate::time::Duration,
+    mut try_wait: impl FnMut() -> io::Result<Option<T>>,
+) -> io::Result<Option<T>> {
+    use crate::thread;
+    use crate::time::{Duration, Instant};
+
+    const MAX_POLL_DELAY: Duration = Duration::from_millis(50);
+
+    let start = Instant::now();
+    let mut delay = Duration::from_millis(1);
+    loop {
+        if let Some(status) = try_wait()? {
+            return Ok(Some(status));
+        }
+        let remaining = timeout.saturating_sub(start.elapsed());
+        if remaining.is_zero() {
+            return Ok(None);
+        }
+        thread::sleep(delay.min(remaining));
+        delay = (delay * 2).min(MAX_POLL_DELAY);
+    }
+}
+
 pub struct CommandArgs<'a> {
     iter: crate::slice::Iter<'a, CString>,
 }
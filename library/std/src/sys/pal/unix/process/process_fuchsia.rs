@@ -6,6 +6,7 @@
 
 use crate::sys::process::process_common::*;
 use crate::sys::process::zircon::{zx_handle_t, Handle};
+use crate::time::Duration;
 
 use libc::{c_int, size_t};
 
@@ -233,6 +234,10 @@ pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
         }
         Ok(Some(ExitStatus(proc_info.return_code)))
     }
+
+    pub fn wait_timeout(&mut self, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+        wait_timeout_by_polling(timeout, || self.try_wait())
+    }
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
@@ -9,7 +9,8 @@
 #[cfg(target_os = "linux")]
 use crate::os::linux::process::PidFd;
 #[cfg(target_os = "linux")]
-use crate::os::unix::io::AsRawFd;
+use crate::sys_common::FromInner;
+use crate::time::Duration;
 
 #[cfg(target_os = "vxworks")]
 use libc::RTP_ID as pid_t;
@@ -790,7 +791,6 @@ impl Process {
     #[cfg(target_os = "linux")]
     unsafe fn new(pid: pid_t, pidfd: pid_t) -> Self {
         use crate::os::unix::io::FromRawFd;
-        use crate::sys_common::FromInner;
         // Safety: If `pidfd` is nonnegative, we assume it's valid and otherwise unowned.
         let pidfd = (pidfd >= 0).then(|| PidFd::from_inner(sys::fd::FileDesc::from_raw_fd(pidfd)));
         Process { pid, status: None, pidfd }
@@ -814,17 +814,7 @@ pub fn kill(&mut self) -> io::Result<()> {
         }
         #[cfg(target_os = "linux")]
         if let Some(pid_fd) = self.pidfd.as_ref() {
-            // pidfd_send_signal predates pidfd_open. so if we were able to get an fd then sending signals will work too
-            return cvt(unsafe {
-                libc::syscall(
-                    libc::SYS_pidfd_send_signal,
-                    pid_fd.as_raw_fd(),
-                    libc::SIGKILL,
-                    crate::ptr::null::<()>(),
-                    0,
-                )
-            })
-            .map(drop);
+            return pidfd::kill(pid_fd);
         }
         cvt(unsafe { libc::kill(self.pid, libc::SIGKILL) }).map(drop)
     }
@@ -836,12 +826,7 @@ pub fn wait(&mut self) -> io::Result<ExitStatus> {
         }
         #[cfg(target_os = "linux")]
         if let Some(pid_fd) = self.pidfd.as_ref() {
-            let mut siginfo: libc::siginfo_t = unsafe { crate::mem::zeroed() };
-
-            cvt_r(|| unsafe {
-                libc::waitid(libc::P_PIDFD, pid_fd.as_raw_fd() as u32, &mut siginfo, libc::WEXITED)
-            })?;
-            let status = ExitStatus::from_waitid_siginfo(siginfo);
+            let status = pidfd::wait(pid_fd)?;
             self.status = Some(status);
             return Ok(status);
         }
@@ -857,22 +842,9 @@ pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
         }
         #[cfg(target_os = "linux")]
         if let Some(pid_fd) = self.pidfd.as_ref() {
-            let mut siginfo: libc::siginfo_t = unsafe { crate::mem::zeroed() };
-
-            cvt(unsafe {
-                libc::waitid(
-                    libc::P_PIDFD,
-                    pid_fd.as_raw_fd() as u32,
-                    &mut siginfo,
-                    libc::WEXITED | libc::WNOHANG,
-                )
-            })?;
-            if unsafe { siginfo.si_pid() } == 0 {
-                return Ok(None);
-            }
-            let status = ExitStatus::from_waitid_siginfo(siginfo);
-            self.status = Some(status);
-            return Ok(Some(status));
+            let status = pidfd::try_wait(pid_fd)?;
+            self.status = status;
+            return Ok(status);
         }
         let mut status = 0 as c_int;
         let pid = cvt(unsafe { libc::waitpid(self.pid, &mut status, libc::WNOHANG) })?;
@@ -883,6 +855,185 @@ pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
             Ok(Some(ExitStatus::new(status)))
         }
     }
+
+    pub fn wait_timeout(&mut self, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+        if let Some(status) = self.try_wait()? {
+            return Ok(Some(status));
+        }
+        #[cfg(target_os = "linux")]
+        if self.pidfd.is_some() {
+            return self.pidfd_wait_timeout(timeout);
+        }
+        // The child hasn't been reaped yet, so its pid can't have been reused and a
+        // pidfd opened for it now refers to the right process.
+        #[cfg(target_os = "linux")]
+        if let Some(pid_fd) = pidfd::open(self.pid) {
+            let status = pidfd::wait_timeout(&pid_fd, timeout)?;
+            self.status = status;
+            return Ok(status);
+        }
+        self.wait_exit_timeout(timeout)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn pidfd_wait_timeout(&mut self, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+        if let Some(status) = self.status {
+            return Ok(Some(status));
+        }
+        let pid_fd = self.pidfd.as_ref().ok_or_else(no_pidfd)?;
+        let status = pidfd::wait_timeout(pid_fd, timeout)?;
+        self.status = status;
+        Ok(status)
+    }
+
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+        target_os = "dragonfly",
+    ))]
+    fn wait_exit_timeout(&mut self, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+        use crate::os::unix::io::{AsRawFd, FromRawFd, OwnedFd};
+        use crate::time::Instant;
+
+        let start = Instant::now();
+        let kq = unsafe { OwnedFd::from_raw_fd(cvt(libc::kqueue())?) };
+        let mut change: libc::kevent = unsafe { mem::zeroed() };
+        change.ident = self.pid as libc::uintptr_t;
+        change.filter = libc::EVFILT_PROC;
+        change.flags = libc::EV_ADD | libc::EV_ONESHOT;
+        change.fflags = libc::NOTE_EXIT;
+        loop {
+            let remaining = timeout.saturating_sub(start.elapsed());
+            let timeout = libc::timespec {
+                tv_sec: remaining.as_secs().try_into().unwrap_or(libc::time_t::MAX),
+                tv_nsec: remaining.subsec_nanos() as _,
+            };
+            let mut event: libc::kevent = unsafe { mem::zeroed() };
+            let res = unsafe { libc::kevent(kq.as_raw_fd(), &change, 1, &mut event, 1, &timeout) };
+            match cvt(res) {
+                Ok(0) => return Ok(None),
+                // Registering fails with ESRCH if the child exited in the meantime.
+                Ok(_) if event.flags & libc::EV_ERROR != 0 && event.data as i32 != libc::ESRCH => {
+                    return Err(io::Error::from_raw_os_error(event.data as i32));
+                }
+                Ok(_) => return self.wait().map(Some),
+                Err(e) if e.raw_os_error() == Some(libc::ESRCH) => return self.wait().map(Some),
+                Err(e) if e.is_interrupted() => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    #[cfg(not(any(
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+        target_os = "dragonfly",
+    )))]
+    fn wait_exit_timeout(&mut self, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+        wait_timeout_by_polling(timeout, || self.try_wait())
+    }
+}
+
+/// Operations on the pidfd of a child process.
+///
+/// Waiting through a pidfd reaps the child, just like `waitpid` does, so the
+/// child's `Process` must not be waited on separately afterwards.
+#[cfg(target_os = "linux")]
+pub mod pidfd {
+    use super::ExitStatus;
+    use crate::io;
+    use crate::os::linux::process::PidFd;
+    use crate::os::unix::io::{AsRawFd, FromRawFd};
+    use crate::sys::{cvt, cvt_r};
+    use crate::time::{Duration, Instant};
+
+    pub fn send_signal(pidfd: &PidFd, signal: i32) -> io::Result<()> {
+        // pidfd_send_signal predates pidfd_open. so if we were able to get an fd then sending
+        // signals will work too
+        cvt(unsafe {
+            libc::syscall(
+                libc::SYS_pidfd_send_signal,
+                pidfd.as_raw_fd(),
+                signal,
+                crate::ptr::null::<()>(),
+                0,
+            )
+        })
+        .map(drop)
+    }
+
+    /// Opens a pidfd for `pid`, if the kernel supports it.
+    pub fn open(pid: libc::pid_t) -> Option<PidFd> {
+        // pidfd_open sets CLOEXEC by default
+        let fd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid, 0) };
+        // Safety: a nonnegative return value is a new pidfd that nothing else owns.
+        (fd >= 0).then(|| unsafe { PidFd::from_raw_fd(fd as libc::c_int) })
+    }
+
+    pub fn kill(pidfd: &PidFd) -> io::Result<()> {
+        send_signal(pidfd, libc::SIGKILL)
+    }
+
+    pub fn wait(pidfd: &PidFd) -> io::Result<ExitStatus> {
+        let mut siginfo: libc::siginfo_t = unsafe { crate::mem::zeroed() };
+        cvt_r(|| unsafe {
+            libc::waitid(libc::P_PIDFD, pidfd.as_raw_fd() as u32, &mut siginfo, libc::WEXITED)
+        })?;
+        Ok(ExitStatus::from_waitid_siginfo(siginfo))
+    }
+
+    pub fn try_wait(pidfd: &PidFd) -> io::Result<Option<ExitStatus>> {
+        let mut siginfo: libc::siginfo_t = unsafe { crate::mem::zeroed() };
+        cvt(unsafe {
+            libc::waitid(
+                libc::P_PIDFD,
+                pidfd.as_raw_fd() as u32,
+                &mut siginfo,
+                libc::WEXITED | libc::WNOHANG,
+            )
+        })?;
+        if unsafe { siginfo.si_pid() } == 0 {
+            return Ok(None);
+        }
+        Ok(Some(ExitStatus::from_waitid_siginfo(siginfo)))
+    }
+
+    pub fn wait_timeout(pidfd: &PidFd, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+        // A timeout too large to represent as a deadline is as good as no timeout.
+        let Some(deadline) = Instant::now().checked_add(timeout) else {
+            return wait(pidfd).map(Some);
+        };
+        loop {
+            if let Some(status) = try_wait(pidfd)? {
+                return Ok(Some(status));
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(None);
+            }
+            // A pidfd becomes readable once the process has exited. Round the timeout up
+            // so that a sub-millisecond remainder doesn't turn into a busy loop.
+            let millis = remaining.as_nanos().div_ceil(1_000_000).min(libc::c_int::MAX as u128);
+            let mut pollfd =
+                libc::pollfd { fd: pidfd.as_raw_fd(), events: libc::POLLIN, revents: 0 };
+            match cvt(unsafe { libc::poll(&mut pollfd, 1, millis as libc::c_int) }) {
+                Ok(_) => {}
+                Err(e) if e.is_interrupted() => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn no_pidfd() -> Error {
+    Error::new(ErrorKind::Uncategorized, "No pidfd was created.")
 }
 
 /// Unix exit statuses
@@ -1104,17 +1255,20 @@ pub fn code(self) -> Option<NonZero<i32>> {
 #[unstable(feature = "linux_pidfd", issue = "82971")]
 impl crate::os::linux::process::ChildExt for crate::process::Child {
     fn pidfd(&self) -> io::Result<&PidFd> {
-        self.handle
-            .pidfd
-            .as_ref()
-            .ok_or_else(|| Error::new(ErrorKind::Uncategorized, "No pidfd was created."))
+        self.handle.pidfd.as_ref().ok_or_else(no_pidfd)
     }
 
     fn take_pidfd(&mut self) -> io::Result<PidFd> {
-        self.handle
-            .pidfd
-            .take()
-            .ok_or_else(|| Error::new(ErrorKind::Uncategorized, "No pidfd was created."))
+        self.handle.pidfd.take().ok_or_else(no_pidfd)
+    }
+
+    fn wait_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> io::Result<Option<crate::process::ExitStatus>> {
+        drop(self.stdin.take());
+        let status = self.handle.pidfd_wait_timeout(timeout)?;
+        Ok(status.map(crate::process::ExitStatus::from_inner))
     }
 }
 
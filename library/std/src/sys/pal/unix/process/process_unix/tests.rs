@@ -114,3 +114,33 @@ fn test_command_pidfd() {
         .pidfd()
         .expect_err("pidfd should not have been created");
 }
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_pidfd_wait_timeout_and_kill() {
+    use crate::assert_matches::assert_matches;
+    use crate::os::linux::process::{ChildExt, CommandExt};
+    use crate::process::Command;
+    use crate::time::Duration;
+
+    let mut child = Command::new("sleep").arg("1000").create_pidfd(true).spawn().unwrap();
+    if child.pidfd().is_err() {
+        // pidfds are not supported by this kernel
+        child.kill().unwrap();
+        child.wait().unwrap();
+        return;
+    }
+
+    assert_matches!(child.wait_timeout(Duration::from_millis(10)), Ok(None));
+    child.pidfd().unwrap().kill().expect("failed to kill child");
+    let status = child.wait_timeout(Duration::from_secs(60)).unwrap().unwrap();
+    assert_eq!(status.signal(), Some(libc::SIGKILL));
+    // the status is remembered once the child has been reaped
+    assert_eq!(child.wait().unwrap(), status);
+
+    let mut child = Command::new("true").create_pidfd(true).spawn().unwrap();
+    let pidfd = child.take_pidfd().unwrap();
+    assert_eq!(pidfd.wait().unwrap().code(), Some(0));
+    assert_matches!(pidfd.try_wait(), Err(_));
+    assert!(child.wait_timeout(Duration::ZERO).is_err());
+}
@@ -4,6 +4,8 @@
 use crate::sys::unsupported;
 use crate::time::Duration;
 
+pub use crate::sys_common::socket_builder::SocketBuilder;
+
 pub struct TcpStream(!);
 
 impl TcpStream {
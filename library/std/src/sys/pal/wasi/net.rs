@@ -10,6 +10,8 @@
 use crate::sys_common::{AsInner, FromInner, IntoInner};
 use crate::time::Duration;
 
+pub use crate::sys_common::socket_builder::SocketBuilder;
+
 pub struct Socket(WasiFd);
 
 pub struct TcpStream {
@@ -5,6 +5,7 @@
 use crate::borrow::Cow;
 use crate::ffi::{c_void, OsStr, OsString};
 use crate::fmt;
+use crate::fs::Reflink;
 use crate::io::{self, BorrowedCursor, Error, IoSlice, IoSliceMut, SeekFrom};
 use crate::mem::{self, MaybeUninit};
 use crate::os::windows::io::{AsHandle, BorrowedHandle};
@@ -21,6 +22,8 @@
 use super::{api, to_u16s, IoResult};
 use crate::sys::path::maybe_verbatim;
 
+pub use crate::sys_common::fs::{write_atomic, CopyOptions, UnsupportedDirHandle as DirHandle};
+
 pub struct File {
     handle: Handle,
 }
@@ -632,6 +635,93 @@ pub fn set_times(&self, times: FileTimes) -> io::Result<()> {
         Ok(())
     }
 
+    pub fn lock(&self) -> io::Result<()> {
+        self.acquire_lock(c::LOCKFILE_EXCLUSIVE_LOCK)
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        self.acquire_lock(0)
+    }
+
+    pub fn try_lock(&self) -> io::Result<bool> {
+        self.try_acquire_lock(c::LOCKFILE_EXCLUSIVE_LOCK)
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        self.try_acquire_lock(0)
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        // Releasing a lock that isn't held succeeds, as it does on other platforms. A handle can
+        // hold both a shared and an exclusive lock on the same range, so release a second lock
+        // if there is one.
+        let unlock = || match cvt(unsafe {
+            c::UnlockFile(self.handle.as_raw_handle(), 0, 0, u32::MAX, u32::MAX)
+        }) {
+            Ok(_) => Ok(()),
+            Err(err) if err.raw_os_error() == Some(c::ERROR_NOT_LOCKED as i32) => Ok(()),
+            Err(err) => Err(err),
+        };
+        unlock()?;
+        unlock()
+    }
+
+    // Locks the whole file, including any part of it beyond its current end.
+    fn acquire_lock(&self, flags: c::LOCK_FILE_FLAGS) -> io::Result<()> {
+        let mut overlapped: c::OVERLAPPED = unsafe { mem::zeroed() };
+        let result = cvt(unsafe {
+            c::LockFileEx(
+                self.handle.as_raw_handle(),
+                flags,
+                0,
+                u32::MAX,
+                u32::MAX,
+                &mut overlapped,
+            )
+        });
+        match result {
+            Ok(_) => Ok(()),
+            // The file was opened for asynchronous I/O, so wait for the lock to be granted.
+            Err(err) if err.raw_os_error() == Some(c::ERROR_IO_PENDING as i32) => {
+                let mut transferred = 0;
+                cvt(unsafe {
+                    c::GetOverlappedResult(
+                        self.handle.as_raw_handle(),
+                        &mut overlapped,
+                        &mut transferred,
+                        c::TRUE,
+                    )
+                })?;
+                Ok(())
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn try_acquire_lock(&self, flags: c::LOCK_FILE_FLAGS) -> io::Result<bool> {
+        let mut overlapped: c::OVERLAPPED = unsafe { mem::zeroed() };
+        let result = cvt(unsafe {
+            c::LockFileEx(
+                self.handle.as_raw_handle(),
+                flags | c::LOCKFILE_FAIL_IMMEDIATELY,
+                0,
+                u32::MAX,
+                u32::MAX,
+                &mut overlapped,
+            )
+        });
+        match result {
+            Ok(_) => Ok(true),
+            Err(err)
+                if err.raw_os_error() == Some(c::ERROR_IO_PENDING as i32)
+                    || err.raw_os_error() == Some(c::ERROR_LOCK_VIOLATION as i32) =>
+            {
+                Ok(false)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
     /// Get only basic file information such as attributes and file times.
     fn basic_info(&self) -> io::Result<c::FILE_BASIC_INFO> {
         unsafe {
@@ -1448,6 +1538,28 @@ pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
     Ok(size as u64)
 }
 
+pub fn copy_with(from: &Path, to: &Path, opts: &CopyOptions) -> io::Result<u64> {
+    // `CopyFileExW` copies the permissions and the modification time along
+    // with the data and takes fast paths like server-side copies on network
+    // shares, so use it whenever the options ask for just that. `sparse` is
+    // ignored here.
+    if opts.reflink != Reflink::Always
+        && opts.permissions
+        && opts.timestamps
+        && opts.follow_symlinks
+    {
+        let attr = stat(from)?;
+        let ret = copy(from, to)?;
+        // The access time of the copy is left at the time of the copy.
+        let mut opts = OpenOptions::new();
+        opts.access_mode(c::FILE_WRITE_ATTRIBUTES);
+        let file = File::open(to, &opts)?;
+        file.set_times(FileTimes { accessed: Some(attr.last_access_time), ..Default::default() })?;
+        return Ok(ret);
+    }
+    crate::sys_common::fs::copy_with(from, to, opts)
+}
+
 pub fn junction_point(original: &Path, link: &Path) -> io::Result<()> {
     // Create and open a new directory in one go.
     let mut opts = OpenOptions::new();
@@ -9,6 +9,8 @@
 mod udp;
 pub use udp::*;
 
+pub use crate::sys_common::socket_builder::SocketBuilder;
+
 // this structure needs to be synchronized with what's in net/src/api.rs
 #[repr(C)]
 #[derive(Debug)]
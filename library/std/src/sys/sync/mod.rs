@@ -9,3 +9,25 @@
 pub use once::{Once, OnceState};
 pub use rwlock::RwLock;
 pub use thread_parking::Parker;
+
+use crate::time::{Duration, Instant};
+
+/// Calls `try_lock` until it succeeds or `deadline` has passed, sleeping for
+/// increasingly long periods in between.
+///
+/// This implements timed locking for locks without native support for it.
+#[allow(dead_code)] // not used on all platforms
+fn poll_until(deadline: Instant, mut try_lock: impl FnMut() -> bool) -> bool {
+    let mut backoff = Duration::from_micros(1);
+    loop {
+        if try_lock() {
+            return true;
+        }
+        let remaining = match deadline.checked_duration_since(Instant::now()) {
+            Some(remaining) if !remaining.is_zero() => remaining,
+            _ => return false,
+        };
+        crate::thread::sleep(backoff.min(remaining));
+        backoff = backoff.saturating_mul(2).min(Duration::from_millis(1));
+    }
+}
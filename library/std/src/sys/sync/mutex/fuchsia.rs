@@ -46,6 +46,7 @@
     ZX_ERR_BAD_STATE, ZX_ERR_INVALID_ARGS, ZX_ERR_TIMED_OUT, ZX_ERR_WRONG_TYPE, ZX_OK,
     ZX_TIME_INFINITE,
 };
+use crate::time::Instant;
 
 // The lowest two bits of a `zx_handle_t` are always set, so the lowest bit is used to mark the
 // mutex as contested by clearing it.
@@ -89,6 +90,11 @@ pub fn try_lock(&self) -> bool {
         self.futex.compare_exchange(UNLOCKED, to_state(thread_self), Acquire, Relaxed).is_ok()
     }
 
+    #[inline]
+    pub fn try_lock_until(&self, deadline: Instant) -> bool {
+        super::super::poll_until(deadline, || self.try_lock())
+    }
+
     #[inline]
     pub fn lock(&self) {
         let thread_self = unsafe { zx_thread_self() };
@@ -3,6 +3,7 @@
     Ordering::{Acquire, Relaxed, Release},
 };
 use crate::sys::futex::{futex_wait, futex_wake};
+use crate::time::Instant;
 
 cfg_if::cfg_if! {
 if #[cfg(windows)] {
@@ -34,15 +35,23 @@ pub fn try_lock(&self) -> bool {
         self.futex.compare_exchange(UNLOCKED, LOCKED, Acquire, Relaxed).is_ok()
     }
 
+    #[inline]
+    pub fn try_lock_until(&self, deadline: Instant) -> bool {
+        self.futex.compare_exchange(UNLOCKED, LOCKED, Acquire, Relaxed).is_ok()
+            || self.lock_contended(Some(deadline))
+    }
+
     #[inline]
     pub fn lock(&self) {
         if self.futex.compare_exchange(UNLOCKED, LOCKED, Acquire, Relaxed).is_err() {
-            self.lock_contended();
+            self.lock_contended(None);
         }
     }
 
+    /// Waits for the lock, giving up and returning `false` once `deadline`
+    /// has passed.
     #[cold]
-    fn lock_contended(&self) {
+    fn lock_contended(&self, deadline: Option<Instant>) -> bool {
         // Spin first to speed things up if the lock is released quickly.
         let mut state = self.spin();
 
@@ -50,7 +59,7 @@ fn lock_contended(&self) {
         // without marking it as contended.
         if state == UNLOCKED {
             match self.futex.compare_exchange(UNLOCKED, LOCKED, Acquire, Relaxed) {
-                Ok(_) => return, // Locked!
+                Ok(_) => return true, // Locked!
                 Err(s) => state = s,
             }
         }
@@ -61,11 +70,21 @@ fn lock_contended(&self) {
             // to be friendlier for the caches.
             if state != CONTENDED && self.futex.swap(CONTENDED, Acquire) == UNLOCKED {
                 // We changed it from UNLOCKED to CONTENDED, so we just successfully locked it.
-                return;
+                return true;
             }
 
+            // Give up if the deadline has passed. The lock stays CONTENDED,
+            // so the other waiting threads (if any) still get woken up.
+            let timeout = match deadline {
+                Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                    Some(timeout) if !timeout.is_zero() => Some(timeout),
+                    _ => return false,
+                },
+                None => None,
+            };
+
             // Wait for the futex to change state, assuming it is still CONTENDED.
-            futex_wait(&self.futex, CONTENDED, None);
+            futex_wait(&self.futex, CONTENDED, timeout);
 
             // Spin again after waking up.
             state = self.spin();
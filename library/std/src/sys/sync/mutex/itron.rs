@@ -5,6 +5,7 @@
     error::{expect_success, expect_success_aborting, fail, ItronError},
     spin::SpinIdOnceCell,
 };
+use crate::time::Instant;
 
 pub struct Mutex {
     /// The ID of the underlying mutex object
@@ -57,6 +58,11 @@ pub fn try_lock(&self) -> bool {
             }
         }
     }
+
+    #[inline]
+    pub fn try_lock_until(&self, deadline: Instant) -> bool {
+        super::super::poll_until(deadline, || self.try_lock())
+    }
 }
 
 impl Drop for Mutex {
@@ -3,6 +3,7 @@
 use crate::mem::{forget, MaybeUninit};
 use crate::sys::cvt_nz;
 use crate::sys_common::lazy_box::{LazyBox, LazyInit};
+use crate::time::Instant;
 
 struct AllocatedMutex(UnsafeCell<libc::pthread_mutex_t>);
 
@@ -134,6 +135,11 @@ pub unsafe fn unlock(&self) {
     pub unsafe fn try_lock(&self) -> bool {
         libc::pthread_mutex_trylock(raw(self)) == 0
     }
+
+    #[inline]
+    pub unsafe fn try_lock_until(&self, deadline: Instant) -> bool {
+        super::super::poll_until(deadline, || unsafe { self.try_lock() })
+    }
 }
 
 pub(super) struct PthreadMutexAttr<'a>(pub &'a mut MaybeUninit<libc::pthread_mutexattr_t>);
@@ -1,5 +1,6 @@
 use crate::sys::pal::waitqueue::{try_lock_or_false, SpinMutex, WaitQueue, WaitVariable};
 use crate::sys_common::lazy_box::{LazyBox, LazyInit};
+use crate::time::Instant;
 
 /// FIXME: `UnsafeList` is not movable.
 struct AllocatedMutex(SpinMutex<WaitVariable<bool>>);
@@ -56,4 +57,9 @@ pub fn try_lock(&self) -> bool {
             true
         }
     }
+
+    #[inline]
+    pub fn try_lock_until(&self, deadline: Instant) -> bool {
+        super::super::poll_until(deadline, || self.try_lock())
+    }
 }
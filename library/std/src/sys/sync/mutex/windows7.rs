@@ -16,6 +16,7 @@
 
 use crate::cell::UnsafeCell;
 use crate::sys::c;
+use crate::time::Instant;
 
 pub struct Mutex {
     srwlock: UnsafeCell<c::SRWLOCK>,
@@ -47,6 +48,11 @@ pub fn try_lock(&self) -> bool {
         unsafe { c::TryAcquireSRWLockExclusive(raw(self)) != 0 }
     }
 
+    #[inline]
+    pub fn try_lock_until(&self, deadline: Instant) -> bool {
+        super::super::poll_until(deadline, || self.try_lock())
+    }
+
     #[inline]
     pub unsafe fn unlock(&self) {
         c::ReleaseSRWLockExclusive(raw(self));
@@ -4,6 +4,7 @@
     AtomicBool, AtomicUsize,
     Ordering::{Acquire, Relaxed, Release},
 };
+use crate::time::Instant;
 
 pub struct Mutex {
     /// The "locked" value indicates how many threads are waiting on this
@@ -95,6 +96,11 @@ pub unsafe fn try_lock(&self) -> bool {
         self.locked.compare_exchange(0, 1, Acquire, Relaxed).is_ok()
     }
 
+    #[inline]
+    pub unsafe fn try_lock_until(&self, deadline: Instant) -> bool {
+        super::super::poll_until(deadline, || unsafe { self.try_lock() })
+    }
+
     #[inline]
     pub unsafe fn try_lock_or_poison(&self) -> bool {
         self.locked.fetch_add(1, Acquire) == 0
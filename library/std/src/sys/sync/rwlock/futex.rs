@@ -3,6 +3,7 @@
     Ordering::{Acquire, Relaxed, Release},
 };
 use crate::sys::futex::{futex_wait, futex_wake, futex_wake_all};
+use crate::time::{Duration, Instant};
 
 pub struct RwLock {
     // The state consists of a 30-bit reader counter, a 'readers waiting' flag, and a 'writers waiting' flag.
@@ -61,6 +62,19 @@ fn has_reached_max_readers(state: u32) -> bool {
     state & MASK == MAX_READERS
 }
 
+/// Returns the timeout to pass to `futex_wait` when waiting until `deadline`,
+/// or `Err(())` if the deadline has passed.
+#[inline]
+fn timeout_until(deadline: Option<Instant>) -> Result<Option<Duration>, ()> {
+    match deadline {
+        Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+            Some(timeout) if !timeout.is_zero() => Ok(Some(timeout)),
+            _ => Err(()),
+        },
+        None => Ok(None),
+    }
+}
+
 impl RwLock {
     #[inline]
     pub const fn new() -> Self {
@@ -74,6 +88,11 @@ pub fn try_read(&self) -> bool {
             .is_ok()
     }
 
+    #[inline]
+    pub fn try_read_until(&self, deadline: Instant) -> bool {
+        self.try_read() || self.read_contended(Some(deadline))
+    }
+
     #[inline]
     pub fn read(&self) {
         let state = self.state.load(Relaxed);
@@ -83,7 +102,7 @@ pub fn read(&self) {
                 .compare_exchange_weak(state, state + READ_LOCKED, Acquire, Relaxed)
                 .is_err()
         {
-            self.read_contended();
+            self.read_contended(None);
         }
     }
 
@@ -101,8 +120,10 @@ pub unsafe fn read_unlock(&self) {
         }
     }
 
+    /// Waits for a read lock, giving up and returning `false` once `deadline`
+    /// has passed.
     #[cold]
-    fn read_contended(&self) {
+    fn read_contended(&self, deadline: Option<Instant>) -> bool {
         let mut state = self.spin_read();
 
         loop {
@@ -110,7 +131,7 @@ fn read_contended(&self) {
             if is_read_lockable(state) {
                 match self.state.compare_exchange_weak(state, state + READ_LOCKED, Acquire, Relaxed)
                 {
-                    Ok(_) => return, // Locked!
+                    Ok(_) => return true, // Locked!
                     Err(s) => {
                         state = s;
                         continue;
@@ -133,8 +154,12 @@ fn read_contended(&self) {
                 }
             }
 
+            // Give up if the deadline has passed. Leaving the readers waiting
+            // bit set is harmless: it is cleared again on the next unlock.
+            let Ok(timeout) = timeout_until(deadline) else { return false };
+
             // Wait for the state to change.
-            futex_wait(&self.state, state | READERS_WAITING, None);
+            futex_wait(&self.state, state | READERS_WAITING, timeout);
 
             // Spin again after waking up.
             state = self.spin_read();
@@ -148,10 +173,15 @@ pub fn try_write(&self) -> bool {
             .is_ok()
     }
 
+    #[inline]
+    pub fn try_write_until(&self, deadline: Instant) -> bool {
+        self.try_write() || self.write_contended(Some(deadline))
+    }
+
     #[inline]
     pub fn write(&self) {
         if self.state.compare_exchange_weak(0, WRITE_LOCKED, Acquire, Relaxed).is_err() {
-            self.write_contended();
+            self.write_contended(None);
         }
     }
 
@@ -166,8 +196,10 @@ pub unsafe fn write_unlock(&self) {
         }
     }
 
+    /// Waits for the write lock, giving up and returning `false` once
+    /// `deadline` has passed.
     #[cold]
-    fn write_contended(&self) {
+    fn write_contended(&self, deadline: Option<Instant>) -> bool {
         let mut state = self.spin_write();
 
         let mut other_writers_waiting = 0;
@@ -181,7 +213,7 @@ fn write_contended(&self) {
                     Acquire,
                     Relaxed,
                 ) {
-                    Ok(_) => return, // Locked!
+                    Ok(_) => return true, // Locked!
                     Err(s) => {
                         state = s;
                         continue;
@@ -214,8 +246,13 @@ fn write_contended(&self) {
                 continue;
             }
 
+            // Give up if the deadline has passed. Leaving the writers waiting
+            // bit set is harmless: on the next unlock, readers are woken up
+            // instead if no writer is actually waiting.
+            let Ok(timeout) = timeout_until(deadline) else { return false };
+
             // Wait for the state to change.
-            futex_wait(&self.writer_notify, seq, None);
+            futex_wait(&self.writer_notify, seq, timeout);
 
             // Spin again after waking up.
             state = self.spin_write();
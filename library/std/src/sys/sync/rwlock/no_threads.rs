@@ -1,4 +1,5 @@
 use crate::cell::Cell;
+use crate::time::Instant;
 
 pub struct RwLock {
     // This platform has no threads, so we can use a Cell here.
@@ -36,6 +37,12 @@ pub fn try_read(&self) -> bool {
         }
     }
 
+    #[inline]
+    pub fn try_read_until(&self, _deadline: Instant) -> bool {
+        // Without other threads, nothing can unlock the lock while we wait.
+        self.try_read()
+    }
+
     #[inline]
     pub fn write(&self) {
         if self.mode.replace(-1) != 0 {
@@ -53,6 +60,11 @@ pub fn try_write(&self) -> bool {
         }
     }
 
+    #[inline]
+    pub fn try_write_until(&self, _deadline: Instant) -> bool {
+        self.try_write()
+    }
+
     #[inline]
     pub unsafe fn read_unlock(&self) {
         self.mode.set(self.mode.get() - 1);
@@ -116,6 +116,7 @@
     Ordering::{AcqRel, Acquire, Relaxed, Release},
 };
 use crate::thread::{self, Thread};
+use crate::time::Instant;
 
 // Locking uses exponential backoff. `SPIN_COUNT` indicates how many times the
 // locking operation will be retried.
@@ -287,6 +288,11 @@ pub fn try_read(&self) -> bool {
         self.state.fetch_update(Acquire, Relaxed, read_lock).is_ok()
     }
 
+    #[inline]
+    pub fn try_read_until(&self, deadline: Instant) -> bool {
+        super::super::poll_until(deadline, || self.try_read())
+    }
+
     #[inline]
     pub fn read(&self) {
         if !self.try_read() {
@@ -304,6 +310,11 @@ pub fn try_write(&self) -> bool {
         self.state.fetch_or(LOCKED, Acquire).addr() & LOCKED == 0
     }
 
+    #[inline]
+    pub fn try_write_until(&self, deadline: Instant) -> bool {
+        super::super::poll_until(deadline, || self.try_write())
+    }
+
     #[inline]
     pub fn write(&self) {
         if !self.try_write() {
@@ -6,6 +6,7 @@
         spin::SpinIdOnceCell,
     },
 };
+use crate::time::Instant;
 
 pub struct RwLock {
     /// The ID of the underlying mutex object
@@ -52,6 +53,11 @@ pub fn try_read(&self) -> bool {
         }
     }
 
+    #[inline]
+    pub fn try_read_until(&self, deadline: Instant) -> bool {
+        super::super::poll_until(deadline, || self.try_read())
+    }
+
     #[inline]
     pub fn write(&self) {
         let rwl = self.raw();
@@ -70,6 +76,11 @@ pub fn try_write(&self) -> bool {
         }
     }
 
+    #[inline]
+    pub fn try_write_until(&self, deadline: Instant) -> bool {
+        super::super::poll_until(deadline, || self.try_write())
+    }
+
     #[inline]
     pub unsafe fn read_unlock(&self) {
         let rwl = self.raw();
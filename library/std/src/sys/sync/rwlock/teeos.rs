@@ -1,4 +1,5 @@
 use crate::sys::sync::mutex::Mutex;
+use crate::time::Instant;
 
 /// we do not supported rwlock, so use mutex to simulate rwlock.
 /// it's useful because so many code in std will use rwlock.
@@ -22,6 +23,11 @@ pub fn try_read(&self) -> bool {
         unsafe { self.inner.try_lock() }
     }
 
+    #[inline]
+    pub fn try_read_until(&self, deadline: Instant) -> bool {
+        unsafe { self.inner.try_lock_until(deadline) }
+    }
+
     #[inline]
     pub fn write(&self) {
         unsafe { self.inner.lock() };
@@ -32,6 +38,11 @@ pub unsafe fn try_write(&self) -> bool {
         unsafe { self.inner.try_lock() }
     }
 
+    #[inline]
+    pub unsafe fn try_write_until(&self, deadline: Instant) -> bool {
+        unsafe { self.inner.try_lock_until(deadline) }
+    }
+
     #[inline]
     pub unsafe fn read_unlock(&self) {
         unsafe { self.inner.unlock() };
@@ -1,14 +1,22 @@
 #![allow(dead_code)] // not used on all platforms
 
-use crate::fs;
+use crate::ffi::OsString;
+use crate::fmt;
+use crate::fs::{self, Reflink};
 use crate::io::{self, Error, ErrorKind};
-use crate::path::Path;
+use crate::path::{Path, PathBuf};
+use crate::sys::fs::{File, FileAttr, OpenOptions, ReadDir};
 
 pub(crate) const NOT_FILE_ERROR: Error = io::const_io_error!(
     ErrorKind::InvalidInput,
     "the source path is neither a regular file nor a symlink to a regular file",
 );
 
+pub(crate) const REFLINK_UNSUPPORTED_ERROR: Error = io::const_io_error!(
+    ErrorKind::Unsupported,
+    "copy-on-write clones are not supported for these files on this platform",
+);
+
 pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
     let mut reader = fs::File::open(from)?;
     let metadata = reader.metadata()?;
@@ -25,6 +33,76 @@ pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
     Ok(ret)
 }
 
+#[derive(Clone, Debug)]
+pub struct CopyOptions {
+    pub reflink: Reflink,
+    pub sparse: bool,
+    pub permissions: bool,
+    pub timestamps: bool,
+    pub follow_symlinks: bool,
+}
+
+impl CopyOptions {
+    pub fn new() -> CopyOptions {
+        CopyOptions {
+            reflink: Reflink::Auto,
+            sparse: false,
+            permissions: true,
+            timestamps: false,
+            follow_symlinks: true,
+        }
+    }
+}
+
+pub fn copy_with(from: &Path, to: &Path, opts: &CopyOptions) -> io::Result<u64> {
+    if !opts.follow_symlinks && copy_symlink(from, to)? {
+        return Ok(0);
+    }
+    if opts.reflink == Reflink::Always {
+        return Err(REFLINK_UNSUPPORTED_ERROR);
+    }
+
+    let mut reader = fs::File::open(from)?;
+    let metadata = reader.metadata()?;
+    if !metadata.is_file() {
+        return Err(NOT_FILE_ERROR);
+    }
+
+    let mut writer = fs::File::create(to)?;
+    let ret = io::copy(&mut reader, &mut writer)?;
+    // Don't change the attributes of non-files like pipes/FIFOs or device nodes.
+    if writer.metadata()?.is_file() {
+        if opts.timestamps {
+            copy_times(&writer, &metadata)?;
+        }
+        if opts.permissions {
+            writer.set_permissions(metadata.permissions())?;
+        }
+    }
+    Ok(ret)
+}
+
+/// Recreates `from` at `to` if it is a symbolic link, returning whether it
+/// was one.
+pub fn copy_symlink(from: &Path, to: &Path) -> io::Result<bool> {
+    if !fs::symlink_metadata(from)?.is_symlink() {
+        return Ok(false);
+    }
+    let target = fs::read_link(from)?;
+    match fs::remove_file(to) {
+        Err(e) if e.kind() != ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    crate::sys::fs::symlink(&target, to)?;
+    Ok(true)
+}
+
+pub fn copy_times(writer: &fs::File, metadata: &fs::Metadata) -> io::Result<()> {
+    let times =
+        fs::FileTimes::new().set_accessed(metadata.accessed()?).set_modified(metadata.modified()?);
+    writer.set_times(times)
+}
+
 pub fn remove_dir_all(path: &Path) -> io::Result<()> {
     let filetype = fs::symlink_metadata(path)?.file_type();
     if filetype.is_symlink() { fs::remove_file(path) } else { remove_dir_all_recursive(path) }
@@ -49,3 +127,89 @@ pub fn try_exists(path: &Path) -> io::Result<bool> {
         Err(error) => Err(error),
     }
 }
+
+/// Replaces the contents of `path` by writing them to a temporary file in the
+/// same directory, syncing it and renaming it over `path`.
+///
+/// This doesn't sync the directory, which platforms that can do so must take
+/// care of afterwards.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut attempt = 0;
+    let (mut file, tmp) = loop {
+        let tmp = atomic_temp_path(path)?;
+        match fs::File::options().write(true).create_new(true).open(&tmp) {
+            Ok(file) => break (file, tmp),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists && attempt < 16 => attempt += 1,
+            Err(e) => return Err(e),
+        }
+    };
+    let result = io::Write::write_all(&mut file, contents)
+        .and_then(|()| file.sync_all())
+        .and_then(|()| fs::rename(&tmp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// Returns a fresh, hidden path next to `path` for writing its new contents.
+pub fn atomic_temp_path(path: &Path) -> io::Result<PathBuf> {
+    let Some(name) = path.file_name() else {
+        return Err(io::const_io_error!(
+            ErrorKind::InvalidInput,
+            "path for an atomic write has no file name",
+        ));
+    };
+    let mut tmp = OsString::from(".");
+    tmp.push(name);
+    tmp.push(format!(".{:016x}.tmp", crate::sys::hashmap_random_keys().0));
+    Ok(path.with_file_name(tmp))
+}
+
+/// A directory handle for platforms that can't open files relative to a
+/// directory. It can't be opened, so none of the other operations are
+/// reachable.
+pub struct UnsupportedDirHandle(!);
+
+impl UnsupportedDirHandle {
+    pub fn open(_path: &Path) -> io::Result<UnsupportedDirHandle> {
+        Err(io::const_io_error!(
+            ErrorKind::Unsupported,
+            "directory handles are not supported on this platform",
+        ))
+    }
+
+    pub fn open_dir_at(&self, _path: &Path) -> io::Result<UnsupportedDirHandle> {
+        self.0
+    }
+
+    pub fn open_file_at(&self, _path: &Path, _opts: &OpenOptions) -> io::Result<File> {
+        self.0
+    }
+
+    pub fn create_dir_at(&self, _path: &Path) -> io::Result<()> {
+        self.0
+    }
+
+    pub fn remove_at(&self, _path: &Path) -> io::Result<()> {
+        self.0
+    }
+
+    pub fn rename_at(&self, _from: &Path, _to_dir: &Self, _to: &Path) -> io::Result<()> {
+        self.0
+    }
+
+    pub fn stat_at(&self, _path: &Path, _follow_symlinks: bool) -> io::Result<FileAttr> {
+        self.0
+    }
+
+    pub fn read_dir(&self) -> io::Result<ReadDir> {
+        self.0
+    }
+}
+
+impl fmt::Debug for UnsupportedDirHandle {
+    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0
+    }
+}
@@ -47,6 +47,7 @@
         pub mod net;
     } else {
         pub use crate::sys::net;
+        pub mod socket_builder;
     }
 }
 
@@ -62,6 +62,69 @@
     }
 }
 
+cfg_if::cfg_if! {
+    if #[cfg(target_os = "horizon")] {
+        // The 3DS doesn't support a big connection backlog. Sometimes
+        // it allows up to about 37, but other times it doesn't even
+        // accept 32. There may be a global limitation causing this.
+        const DEFAULT_BACKLOG: c_int = 20;
+    } else if #[cfg(target_os = "haiku")] {
+        // Haiku does not support a queue length > 32
+        // https://github.com/haiku/haiku/blob/979a0bc487864675517fb2fab28f87dc8bf43041/headers/posix/sys/socket.h#L81
+        const DEFAULT_BACKLOG: c_int = 32;
+    } else {
+        // The default for all other platforms
+        const DEFAULT_BACKLOG: c_int = 128;
+    }
+}
+
+// Socket options which only exist on some platforms. `None` means that setting
+// the option reports an `Unsupported` error.
+cfg_if::cfg_if! {
+    if #[cfg(any(unix, windows))] {
+        const SO_KEEPALIVE: Option<c_int> = Some(c::SO_KEEPALIVE);
+        const SO_SNDBUF: Option<c_int> = Some(c::SO_SNDBUF);
+        const SO_RCVBUF: Option<c_int> = Some(c::SO_RCVBUF);
+    } else {
+        const SO_KEEPALIVE: Option<c_int> = None;
+        const SO_SNDBUF: Option<c_int> = None;
+        const SO_RCVBUF: Option<c_int> = None;
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(any(
+        target_os = "linux", target_os = "android",
+        target_os = "fuchsia", target_os = "hurd",
+        target_os = "dragonfly", target_os = "freebsd",
+        target_os = "openbsd", target_os = "netbsd",
+        target_os = "haiku", target_os = "nto",
+        target_vendor = "apple"))] {
+        const SO_REUSEPORT: Option<c_int> = Some(libc::SO_REUSEPORT);
+    } else {
+        const SO_REUSEPORT: Option<c_int> = None;
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(any(
+        target_os = "linux", target_os = "android",
+        target_os = "fuchsia", target_os = "hurd",
+        target_os = "dragonfly", target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "illumos", target_os = "solaris",
+        windows))] {
+        const TCP_KEEPIDLE: Option<c_int> = Some(c::TCP_KEEPIDLE);
+        const TCP_KEEPINTVL: Option<c_int> = Some(c::TCP_KEEPINTVL);
+    } else if #[cfg(target_vendor = "apple")] {
+        const TCP_KEEPIDLE: Option<c_int> = Some(libc::TCP_KEEPALIVE);
+        const TCP_KEEPINTVL: Option<c_int> = Some(libc::TCP_KEEPINTVL);
+    } else {
+        const TCP_KEEPIDLE: Option<c_int> = None;
+        const TCP_KEEPINTVL: Option<c_int> = None;
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // sockaddr and misc bindings
 ////////////////////////////////////////////////////////////////////////////////
@@ -398,45 +461,7 @@ pub struct TcpListener {
 
 impl TcpListener {
     pub fn bind(addr: io::Result<&SocketAddr>) -> io::Result<TcpListener> {
-        let addr = addr?;
-
-        init();
-
-        let sock = Socket::new(addr, c::SOCK_STREAM)?;
-
-        // On platforms with Berkeley-derived sockets, this allows to quickly
-        // rebind a socket, without needing to wait for the OS to clean up the
-        // previous one.
-        //
-        // On Windows, this allows rebinding sockets which are actively in use,
-        // which allows “socket hijacking”, so we explicitly don't set it here.
-        // https://docs.microsoft.com/en-us/windows/win32/winsock/using-so-reuseaddr-and-so-exclusiveaddruse
-        #[cfg(not(windows))]
-        setsockopt(&sock, c::SOL_SOCKET, c::SO_REUSEADDR, 1 as c_int)?;
-
-        // Bind our new socket
-        let (addr, len) = addr.into_inner();
-        cvt(unsafe { c::bind(sock.as_raw(), addr.as_ptr(), len as _) })?;
-
-        cfg_if::cfg_if! {
-            if #[cfg(target_os = "horizon")] {
-                // The 3DS doesn't support a big connection backlog. Sometimes
-                // it allows up to about 37, but other times it doesn't even
-                // accept 32. There may be a global limitation causing this.
-                let backlog = 20;
-            } else if #[cfg(target_os = "haiku")] {
-                // Haiku does not support a queue length > 32
-                // https://github.com/haiku/haiku/blob/979a0bc487864675517fb2fab28f87dc8bf43041/headers/posix/sys/socket.h#L81
-                let backlog = 32;
-            } else {
-                // The default for all other platforms
-                let backlog = 128;
-            }
-        }
-
-        // Start listening
-        cvt(unsafe { c::listen(sock.as_raw(), backlog) })?;
-        Ok(TcpListener { inner: sock })
+        SocketBuilder::new().listen(addr)
     }
 
     #[inline]
@@ -727,6 +752,247 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////
+// Socket builder
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Debug, Default)]
+pub struct SocketBuilder {
+    reuse_address: Option<bool>,
+    reuse_port: Option<bool>,
+    only_v6: Option<bool>,
+    send_buffer_size: Option<usize>,
+    recv_buffer_size: Option<usize>,
+    keepalive: Option<bool>,
+    keepalive_time: Option<Duration>,
+    keepalive_interval: Option<Duration>,
+    nodelay: Option<bool>,
+    ttl: Option<u32>,
+    nonblocking: bool,
+    local_addr: Option<SocketAddr>,
+    backlog: Option<u32>,
+}
+
+impl SocketBuilder {
+    pub fn new() -> SocketBuilder {
+        Default::default()
+    }
+
+    pub fn reuse_address(&mut self, reuse: bool) {
+        self.reuse_address = Some(reuse);
+    }
+
+    pub fn reuse_port(&mut self, reuse: bool) {
+        self.reuse_port = Some(reuse);
+    }
+
+    pub fn only_v6(&mut self, only_v6: bool) {
+        self.only_v6 = Some(only_v6);
+    }
+
+    pub fn send_buffer_size(&mut self, size: usize) {
+        self.send_buffer_size = Some(size);
+    }
+
+    pub fn recv_buffer_size(&mut self, size: usize) {
+        self.recv_buffer_size = Some(size);
+    }
+
+    pub fn keepalive(&mut self, keepalive: bool) {
+        self.keepalive = Some(keepalive);
+    }
+
+    pub fn keepalive_time(&mut self, time: Duration) {
+        self.keepalive_time = Some(time);
+    }
+
+    pub fn keepalive_interval(&mut self, interval: Duration) {
+        self.keepalive_interval = Some(interval);
+    }
+
+    pub fn nodelay(&mut self, nodelay: bool) {
+        self.nodelay = Some(nodelay);
+    }
+
+    pub fn ttl(&mut self, ttl: u32) {
+        self.ttl = Some(ttl);
+    }
+
+    pub fn nonblocking(&mut self, nonblocking: bool) {
+        self.nonblocking = nonblocking;
+    }
+
+    pub fn local_addr(&mut self, addr: SocketAddr) {
+        self.local_addr = Some(addr);
+    }
+
+    pub fn backlog(&mut self, backlog: u32) {
+        self.backlog = Some(backlog);
+    }
+
+    pub fn listen(&self, addr: io::Result<&SocketAddr>) -> io::Result<TcpListener> {
+        let addr = addr?;
+
+        init();
+
+        let sock = Socket::new(addr, c::SOCK_STREAM)?;
+
+        // On platforms with Berkeley-derived sockets, this allows to quickly
+        // rebind a socket, without needing to wait for the OS to clean up the
+        // previous one.
+        //
+        // On Windows, this allows rebinding sockets which are actively in use,
+        // which allows “socket hijacking”, so we explicitly don't set it here
+        // unless asked to.
+        // https://docs.microsoft.com/en-us/windows/win32/winsock/using-so-reuseaddr-and-so-exclusiveaddruse
+        let reuse_address = self.reuse_address.or(if cfg!(windows) { None } else { Some(true) });
+        self.set_options(&sock, addr, reuse_address)?;
+
+        // Bind our new socket
+        let (addr, len) = addr.into_inner();
+        cvt(unsafe { c::bind(sock.as_raw(), addr.as_ptr(), len as _) })?;
+
+        // Start listening
+        let backlog = match self.backlog {
+            Some(backlog) => cmp::min(backlog, c_int::MAX as u32) as c_int,
+            None => DEFAULT_BACKLOG,
+        };
+        cvt(unsafe { c::listen(sock.as_raw(), backlog) })?;
+        if self.nonblocking {
+            sock.set_nonblocking(true)?;
+        }
+        Ok(TcpListener { inner: sock })
+    }
+
+    pub fn connect(&self, addr: io::Result<&SocketAddr>) -> io::Result<TcpStream> {
+        let addr = addr?;
+
+        init();
+
+        let sock = self.stream_socket(addr)?;
+        sock.connect(addr)?;
+        if self.nonblocking {
+            sock.set_nonblocking(true)?;
+        }
+        Ok(TcpStream { inner: sock })
+    }
+
+    pub fn connect_timeout(&self, addr: &SocketAddr, timeout: Duration) -> io::Result<TcpStream> {
+        init();
+
+        let sock = self.stream_socket(addr)?;
+        sock.connect_timeout(addr, timeout)?;
+        if self.nonblocking {
+            sock.set_nonblocking(true)?;
+        }
+        Ok(TcpStream { inner: sock })
+    }
+
+    pub fn bind_udp(&self, addr: io::Result<&SocketAddr>) -> io::Result<UdpSocket> {
+        let addr = addr?;
+
+        if self.keepalive.is_some()
+            || self.keepalive_time.is_some()
+            || self.keepalive_interval.is_some()
+            || self.nodelay.is_some()
+        {
+            return Err(io::const_io_error!(
+                ErrorKind::InvalidInput,
+                "TCP socket options cannot be set on a UDP socket",
+            ));
+        }
+
+        init();
+
+        let sock = Socket::new(addr, c::SOCK_DGRAM)?;
+        self.set_options(&sock, addr, self.reuse_address)?;
+        let (addr, len) = addr.into_inner();
+        cvt(unsafe { c::bind(sock.as_raw(), addr.as_ptr(), len as _) })?;
+        if self.nonblocking {
+            sock.set_nonblocking(true)?;
+        }
+        Ok(UdpSocket { inner: sock })
+    }
+
+    /// Creates a socket for an outgoing TCP connection to `addr`, binding it to
+    /// the requested local address if there is one.
+    fn stream_socket(&self, addr: &SocketAddr) -> io::Result<Socket> {
+        let sock = Socket::new(addr, c::SOCK_STREAM)?;
+        self.set_options(&sock, addr, self.reuse_address)?;
+        if let Some(ref local) = self.local_addr {
+            let (local, len) = local.into_inner();
+            cvt(unsafe { c::bind(sock.as_raw(), local.as_ptr(), len as _) })?;
+        }
+        Ok(sock)
+    }
+
+    /// Applies the options which have to be set before the socket is bound.
+    fn set_options(
+        &self,
+        sock: &Socket,
+        addr: &SocketAddr,
+        reuse_address: Option<bool>,
+    ) -> io::Result<()> {
+        if let Some(reuse) = reuse_address {
+            setsockopt(sock, c::SOL_SOCKET, c::SO_REUSEADDR, reuse as c_int)?;
+        }
+        if let Some(reuse) = self.reuse_port {
+            setsockopt_if(sock, c::SOL_SOCKET, SO_REUSEPORT, reuse as c_int)?;
+        }
+        if let Some(only_v6) = self.only_v6 {
+            // `IPV6_V6ONLY` only makes sense for IPv6 sockets; most platforms
+            // reject it on IPv4 ones, so ignore it there.
+            if addr.is_ipv6() {
+                setsockopt(sock, c::IPPROTO_IPV6, c::IPV6_V6ONLY, only_v6 as c_int)?;
+            }
+        }
+        if let Some(size) = self.send_buffer_size {
+            setsockopt_if(sock, c::SOL_SOCKET, SO_SNDBUF, saturating_c_int(size as u64))?;
+        }
+        if let Some(size) = self.recv_buffer_size {
+            setsockopt_if(sock, c::SOL_SOCKET, SO_RCVBUF, saturating_c_int(size as u64))?;
+        }
+        if let Some(keepalive) = self.keepalive {
+            setsockopt_if(sock, c::SOL_SOCKET, SO_KEEPALIVE, keepalive as c_int)?;
+        }
+        if let Some(time) = self.keepalive_time {
+            let secs = saturating_c_int(time.as_secs());
+            setsockopt_if(sock, c::IPPROTO_TCP, TCP_KEEPIDLE, secs)?;
+        }
+        if let Some(interval) = self.keepalive_interval {
+            let secs = saturating_c_int(interval.as_secs());
+            setsockopt_if(sock, c::IPPROTO_TCP, TCP_KEEPINTVL, secs)?;
+        }
+        if let Some(nodelay) = self.nodelay {
+            sock.set_nodelay(nodelay)?;
+        }
+        if let Some(ttl) = self.ttl {
+            setsockopt(sock, c::IPPROTO_IP, c::IP_TTL, ttl as c_int)?;
+        }
+        Ok(())
+    }
+}
+
+/// Sets a socket option which may not exist on this platform.
+fn setsockopt_if(
+    sock: &Socket,
+    level: c_int,
+    option_name: Option<c_int>,
+    option_value: c_int,
+) -> io::Result<()> {
+    match option_name {
+        Some(option_name) => setsockopt(sock, level, option_name, option_value),
+        None => Err(io::const_io_error!(
+            ErrorKind::Unsupported,
+            "socket option is not supported on this platform",
+        )),
+    }
+}
+
+fn saturating_c_int(value: u64) -> c_int {
+    cmp::min(value, c_int::MAX as u64) as c_int
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // Converting SocketAddr to libc representation
 ////////////////////////////////////////////////////////////////////////////////
@@ -0,0 +1,148 @@
+//! A `SocketBuilder` for platforms whose networking stack is not based on
+//! BSD-style sockets.
+//!
+//! Options which have to be set before the socket is bound cannot be honoured
+//! here, so asking for them reports an `Unsupported` error. Everything else is
+//! applied through the platform's `TcpListener`, `TcpStream` and `UdpSocket`.
+
+use crate::io::{self, ErrorKind};
+use crate::net::SocketAddr;
+use crate::sys::net::{TcpListener, TcpStream, UdpSocket};
+use crate::time::Duration;
+
+#[derive(Clone, Debug, Default)]
+pub struct SocketBuilder {
+    // Set when an option that this platform cannot apply was requested.
+    unsupported: bool,
+    // Set when a TCP-only option was requested, which `bind_udp` rejects.
+    tcp_only: bool,
+    nodelay: Option<bool>,
+    ttl: Option<u32>,
+    nonblocking: bool,
+}
+
+impl SocketBuilder {
+    pub fn new() -> SocketBuilder {
+        Default::default()
+    }
+
+    pub fn reuse_address(&mut self, _: bool) {
+        self.unsupported = true;
+    }
+
+    pub fn reuse_port(&mut self, _: bool) {
+        self.unsupported = true;
+    }
+
+    pub fn only_v6(&mut self, _: bool) {
+        self.unsupported = true;
+    }
+
+    pub fn send_buffer_size(&mut self, _: usize) {
+        self.unsupported = true;
+    }
+
+    pub fn recv_buffer_size(&mut self, _: usize) {
+        self.unsupported = true;
+    }
+
+    pub fn keepalive(&mut self, _: bool) {
+        self.unsupported = true;
+        self.tcp_only = true;
+    }
+
+    pub fn keepalive_time(&mut self, _: Duration) {
+        self.unsupported = true;
+        self.tcp_only = true;
+    }
+
+    pub fn keepalive_interval(&mut self, _: Duration) {
+        self.unsupported = true;
+        self.tcp_only = true;
+    }
+
+    pub fn nodelay(&mut self, nodelay: bool) {
+        self.nodelay = Some(nodelay);
+        self.tcp_only = true;
+    }
+
+    pub fn ttl(&mut self, ttl: u32) {
+        self.ttl = Some(ttl);
+    }
+
+    pub fn nonblocking(&mut self, nonblocking: bool) {
+        self.nonblocking = nonblocking;
+    }
+
+    pub fn local_addr(&mut self, _: SocketAddr) {
+        self.unsupported = true;
+    }
+
+    pub fn backlog(&mut self, _: u32) {
+        self.unsupported = true;
+    }
+
+    pub fn listen(&self, addr: io::Result<&SocketAddr>) -> io::Result<TcpListener> {
+        self.check()?;
+        let listener = TcpListener::bind(addr)?;
+        if let Some(ttl) = self.ttl {
+            listener.set_ttl(ttl)?;
+        }
+        if self.nonblocking {
+            listener.set_nonblocking(true)?;
+        }
+        Ok(listener)
+    }
+
+    pub fn connect(&self, addr: io::Result<&SocketAddr>) -> io::Result<TcpStream> {
+        self.check()?;
+        self.configure_stream(TcpStream::connect(addr)?)
+    }
+
+    pub fn connect_timeout(&self, addr: &SocketAddr, timeout: Duration) -> io::Result<TcpStream> {
+        self.check()?;
+        self.configure_stream(TcpStream::connect_timeout(addr, timeout)?)
+    }
+
+    pub fn bind_udp(&self, addr: io::Result<&SocketAddr>) -> io::Result<UdpSocket> {
+        if self.tcp_only {
+            return Err(io::const_io_error!(
+                ErrorKind::InvalidInput,
+                "TCP socket options cannot be set on a UDP socket",
+            ));
+        }
+        self.check()?;
+        let socket = UdpSocket::bind(addr)?;
+        if let Some(ttl) = self.ttl {
+            socket.set_ttl(ttl)?;
+        }
+        if self.nonblocking {
+            socket.set_nonblocking(true)?;
+        }
+        Ok(socket)
+    }
+
+    fn configure_stream(&self, stream: TcpStream) -> io::Result<TcpStream> {
+        if let Some(nodelay) = self.nodelay {
+            stream.set_nodelay(nodelay)?;
+        }
+        if let Some(ttl) = self.ttl {
+            stream.set_ttl(ttl)?;
+        }
+        if self.nonblocking {
+            stream.set_nonblocking(true)?;
+        }
+        Ok(stream)
+    }
+
+    fn check(&self) -> io::Result<()> {
+        if self.unsupported {
+            Err(io::const_io_error!(
+                ErrorKind::Unsupported,
+                "socket option is not supported on this platform",
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -503,6 +503,8 @@ unsafe fn spawn_unchecked_<'a, 'scope, F, T>(
 
         let output_capture = crate::io::set_output_capture(None);
         crate::io::set_output_capture(output_capture.clone());
+        let error_capture = crate::io::set_error_capture(None);
+        crate::io::set_error_capture(error_capture.clone());
 
         // Pass `f` in `MaybeUninit` because actually that closure might *run longer than the lifetime of `F`*.
         // See <https://github.com/rust-lang/rust/issues/101983> for more details.
@@ -535,6 +537,7 @@ fn drop(&mut self) {
             }
 
             crate::io::set_output_capture(output_capture);
+            crate::io::set_error_capture(error_capture);
 
             let f = f.into_inner();
             set_current(their_thread);
@@ -0,0 +1,101 @@
+#![feature(can_vector, core_io_borrowed_buf, read_buf)]
+#![feature(io_read_into_deque, vec_deque_spare_capacity)]
+
+use std::collections::VecDeque;
+use std::io::{self, BorrowedBuf, IoSliceMut, Read};
+use std::mem::MaybeUninit;
+
+/// Returns a deque holding `4..12` whose contents wrap around the end of its buffer.
+fn wrapping_deque() -> VecDeque<u8> {
+    let mut deque = VecDeque::with_capacity(8);
+    deque.extend(0..6);
+    deque.drain(..4);
+    deque.extend(6..12);
+    assert!(!deque.as_slices().1.is_empty());
+    deque
+}
+
+#[test]
+fn read_wrapping() {
+    let mut deque = wrapping_deque();
+    let mut buf = [0; 5];
+    assert_eq!(deque.read(&mut buf).unwrap(), 5);
+    assert_eq!(buf, [4, 5, 6, 7, 8]);
+    assert_eq!(deque, [9, 10, 11]);
+
+    let mut deque = wrapping_deque();
+    let mut buf = [MaybeUninit::uninit(); 7];
+    let mut buf: BorrowedBuf<'_> = buf.as_mut_slice().into();
+    deque.read_buf(buf.unfilled()).unwrap();
+    assert_eq!(buf.filled(), [4, 5, 6, 7, 8, 9, 10]);
+    assert_eq!(deque, [11]);
+}
+
+#[test]
+fn read_vectored() {
+    let mut deque = wrapping_deque();
+    assert!(deque.is_read_vectored());
+    let (mut a, mut b) = ([0; 3], [0; 10]);
+    let mut bufs = [IoSliceMut::new(&mut a), IoSliceMut::new(&mut b)];
+    assert_eq!(deque.read_vectored(&mut bufs).unwrap(), 8);
+    assert_eq!(a, [4, 5, 6]);
+    assert_eq!(b[..5], [7, 8, 9, 10, 11]);
+    assert!(deque.is_empty());
+    assert_eq!(deque.read_vectored(&mut bufs).unwrap(), 0);
+}
+
+#[test]
+fn read_into_deque() {
+    let input: Vec<u8> = (100..120).collect();
+
+    // The spare capacity wraps, so a vectored reader fills both halves at once.
+    let mut deque = wrapping_deque();
+    deque.drain(..6);
+    assert_eq!(io::read_into_deque(&mut &input[..], &mut deque, 6).unwrap(), 6);
+    assert_eq!(deque, [10, 11, 100, 101, 102, 103, 104, 105]);
+
+    // Readers without vectored support only fill the first half.
+    struct NotVectored<'a>(&'a [u8]);
+    impl Read for NotVectored<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.0.read(buf)
+        }
+    }
+    let mut deque = wrapping_deque();
+    deque.drain(..6);
+    let (first, _) = deque.spare_capacity_mut();
+    let first_len = first.len();
+    assert_eq!(io::read_into_deque(&mut NotVectored(&input), &mut deque, 6).unwrap(), first_len);
+    assert!(deque.iter().copied().eq([10, 11].into_iter().chain(100..100 + first_len as u8)));
+
+    // Reading more than fits grows the deque, and EOF is reported as `Ok(0)`.
+    let mut reader = &input[..];
+    let mut deque = VecDeque::new();
+    while io::read_into_deque(&mut reader, &mut deque, 7).unwrap() != 0 {}
+    assert_eq!(deque, input);
+    assert_eq!(io::read_into_deque(&mut reader, &mut deque, 0).unwrap(), 0);
+
+    // Large reads only fill the first half, so that it need not be zeroed.
+    let mut deque = VecDeque::<u8>::with_capacity(64 * 1024);
+    deque.resize(deque.capacity() / 2, 0);
+    deque.drain(..deque.len() / 2);
+    let (first, second) = deque.spare_capacity_mut();
+    let (first_len, second_len) = (first.len(), second.len());
+    assert!(second_len > 0);
+    let input = vec![1; first_len + second_len];
+    assert_eq!(io::read_into_deque(&mut &input[..], &mut deque, input.len()).unwrap(), first_len);
+}
+
+#[test]
+fn read_into_deque_error() {
+    struct ErrRead;
+    impl Read for ErrRead {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::other("oh no"))
+        }
+    }
+
+    let mut deque = wrapping_deque();
+    assert!(io::read_into_deque(&mut ErrRead, &mut deque, 4).is_err());
+    assert_eq!(deque, (4..12).collect::<VecDeque<_>>());
+}
@@ -0,0 +1,269 @@
+//! Saving benchmark results as named baselines, and comparing against them.
+//!
+//! A baseline is stored in a text file named after it, in a directory next to
+//! the test executable. After a header line, the file has one line per
+//! benchmark with four fields separated by tabs:
+//!
+//! * the name of the benchmark, with backslashes, tabs and line breaks escaped
+//!   as `\\`, `\t`, `\n` and `\r`,
+//! * its throughput in MB/s,
+//! * its summary: `sum`, `min`, `max`, `mean`, `median`, `var`, `std_dev`,
+//!   `std_dev_pct`, `median_abs_dev`, `median_abs_dev_pct`, the three
+//!   quartiles and `iqr`, separated by spaces,
+//! * its samples in ns/iter, separated by spaces.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::PathBuf;
+
+use crate::bench::BenchSamples;
+use crate::stats::{bootstrap_median_change, MedianChange, Summary};
+use crate::types::TestDesc;
+
+/// Confidence level of the interval reported for the change of a benchmark.
+const CONFIDENCE: f64 = 0.95;
+/// Number of bootstrap resamples used to compute the confidence interval.
+const RESAMPLES: usize = 10_000;
+/// Changes smaller than this are considered noise, even if they are
+/// statistically significant.
+const NOISE_THRESHOLD: f64 = 0.02;
+
+/// How a benchmark changed compared to the baseline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verdict {
+    Improved,
+    Regressed,
+    NoChange,
+}
+
+impl Verdict {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Verdict::Improved => "improved",
+            Verdict::Regressed => "regressed",
+            Verdict::NoChange => "no change",
+        }
+    }
+}
+
+/// Change of a benchmark compared to its result in a baseline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchChange<'a> {
+    /// Name of the baseline.
+    pub baseline: &'a str,
+    /// Median in the baseline, in ns/iter.
+    pub base_median: f64,
+    /// Relative change of the median.
+    pub change: MedianChange,
+    pub verdict: Verdict,
+}
+
+pub fn fmt_bench_change(change: &BenchChange<'_>) -> String {
+    let MedianChange { estimate, lower, upper } = change.change;
+    format!("change: {:+.2}% [{:+.2}%, {:+.2}%]", estimate * 100.0, lower * 100.0, upper * 100.0)
+}
+
+/// Benchmark results loaded from a saved baseline.
+#[derive(Debug)]
+pub struct Baseline {
+    pub name: String,
+    pub benches: BTreeMap<String, BenchSamples>,
+}
+
+impl Baseline {
+    /// Loads the baseline called `name`. A baseline which has not been saved
+    /// yet is empty, so nothing is compared with it.
+    pub fn load(name: &str) -> io::Result<Baseline> {
+        let path = baseline_path(name)?;
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                eprintln!(
+                    "note: baseline `{name}` has not been saved yet, \
+                     so there are no results to compare with"
+                );
+                return Ok(Baseline { name: name.to_owned(), benches: BTreeMap::new() });
+            }
+            Err(e) => {
+                return Err(io::Error::new(
+                    e.kind(),
+                    format!("could not read baseline `{name}` from {} ({e})", path.display()),
+                ));
+            }
+        };
+        let benches = decode(&contents).map_err(|e| {
+            io::Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid baseline `{name}` in {} ({e})", path.display()),
+            )
+        })?;
+        Ok(Baseline { name: name.to_owned(), benches })
+    }
+
+    /// Compares the result of the benchmark called `name` with its result in
+    /// the baseline. Returns `None` if the baseline has no samples for it.
+    pub fn compare(&self, name: &str, bs: &BenchSamples) -> Option<BenchChange<'_>> {
+        let base = self.benches.get(name)?;
+        let (base_samples, new_samples) = (&base.ns_iter_samples, &bs.ns_iter_samples);
+        if base_samples.is_empty() || new_samples.is_empty() || base.ns_iter_summ.median <= 0.0 {
+            return None;
+        }
+
+        let change = bootstrap_median_change(base_samples, new_samples, CONFIDENCE, RESAMPLES, 0);
+        let verdict = if change.lower > 0.0 && change.estimate > NOISE_THRESHOLD {
+            Verdict::Regressed
+        } else if change.upper < 0.0 && change.estimate < -NOISE_THRESHOLD {
+            Verdict::Improved
+        } else {
+            Verdict::NoChange
+        };
+        Some(BenchChange {
+            baseline: &self.name,
+            base_median: base.ns_iter_summ.median,
+            change,
+            verdict,
+        })
+    }
+}
+
+/// Saves `results` in the baseline called `name`. Benchmarks already in the
+/// baseline that were not run this time are kept.
+pub fn save_baseline(name: &str, results: &[(TestDesc, BenchSamples)]) -> io::Result<()> {
+    let path = baseline_path(name)?;
+    let mut benches = match fs::read_to_string(&path) {
+        // Start afresh if the old baseline can't be read, it is being replaced anyway.
+        Ok(contents) => decode(&contents).unwrap_or_default(),
+        Err(e) if e.kind() == ErrorKind::NotFound => BTreeMap::new(),
+        Err(e) => return Err(e),
+    };
+    for (desc, bs) in results {
+        benches.insert(desc.name.as_slice().to_owned(), bs.clone());
+    }
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, encode(&benches))
+}
+
+// Baselines live next to the test executable, which usually is in the build
+// directory, so that each test executable has its own.
+fn baseline_path(name: &str) -> io::Result<PathBuf> {
+    let mut dir = env::current_exe()?.into_os_string();
+    dir.push(".baselines");
+    Ok(PathBuf::from(dir).join(format!("{name}.txt")))
+}
+
+pub(crate) fn encode(benches: &BTreeMap<String, BenchSamples>) -> String {
+    let mut out = String::from(HEADER);
+    out.push('\n');
+    for (name, bs) in benches {
+        let s = &bs.ns_iter_summ;
+        let summary = [
+            s.sum,
+            s.min,
+            s.max,
+            s.mean,
+            s.median,
+            s.var,
+            s.std_dev,
+            s.std_dev_pct,
+            s.median_abs_dev,
+            s.median_abs_dev_pct,
+            s.quartiles.0,
+            s.quartiles.1,
+            s.quartiles.2,
+            s.iqr,
+        ];
+        write!(out, "{}\t{}\t", escape_name(name), bs.mb_s).unwrap();
+        write_numbers(&mut out, &summary);
+        out.push('\t');
+        write_numbers(&mut out, &bs.ns_iter_samples);
+        out.push('\n');
+    }
+    out
+}
+
+pub(crate) fn decode(contents: &str) -> Result<BTreeMap<String, BenchSamples>, String> {
+    let mut lines = contents.lines().enumerate();
+    if lines.next().map(|(_, line)| line) != Some(HEADER) {
+        return Err(format!("expected `{HEADER}` on the first line"));
+    }
+
+    let mut benches = BTreeMap::new();
+    for (i, line) in lines {
+        let error = |msg: &str| format!("line {}: {msg}", i + 1);
+        let [name, mb_s, summary, samples] = line.split('\t').collect::<Vec<_>>()[..] else {
+            return Err(error("expected four tab-separated fields"));
+        };
+        let mb_s = mb_s.parse().map_err(|_| error("invalid MB/s"))?;
+        let summary = parse_numbers(summary).ok_or_else(|| error("invalid summary"))?;
+        let [sum, min, max, mean, median, var, std_dev, std_dev_pct, mad, mad_pct, q1, q2, q3, iqr] =
+            summary[..]
+        else {
+            return Err(error("expected 14 numbers in the summary"));
+        };
+        let ns_iter_summ = Summary {
+            sum,
+            min,
+            max,
+            mean,
+            median,
+            var,
+            std_dev,
+            std_dev_pct,
+            median_abs_dev: mad,
+            median_abs_dev_pct: mad_pct,
+            quartiles: (q1, q2, q3),
+            iqr,
+        };
+        let ns_iter_samples = parse_numbers(samples).ok_or_else(|| error("invalid samples"))?;
+        let name = unescape_name(name).ok_or_else(|| error("invalid escape in name"))?;
+        benches.insert(name, BenchSamples { ns_iter_summ, ns_iter_samples, mb_s });
+    }
+    Ok(benches)
+}
+
+/// First line of a baseline file, so that files in another format are not
+/// mistaken for one.
+const HEADER: &str = "libtest baseline v1";
+
+fn write_numbers(out: &mut String, numbers: &[f64]) {
+    for (i, n) in numbers.iter().enumerate() {
+        let separator = if i == 0 { "" } else { " " };
+        // `f64`'s `Display` round-trips through `parse`, including NaN and infinities.
+        write!(out, "{separator}{n}").unwrap();
+    }
+}
+
+fn parse_numbers(field: &str) -> Option<Vec<f64>> {
+    field.split(' ').filter(|n| !n.is_empty()).map(|n| n.parse().ok()).collect()
+}
+
+// Test names may contain anything, but the name has to stay on one line and
+// must not contain the field separator.
+fn escape_name(name: &str) -> String {
+    name.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n").replace('\r', "\\r")
+}
+
+fn unescape_name(name: &str) -> Option<String> {
+    let mut out = String::with_capacity(name.len());
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        out.push(match chars.next()? {
+            '\\' => '\\',
+            't' => '\t',
+            'n' => '\n',
+            'r' => '\r',
+            _ => return None,
+        });
+    }
+    Some(out)
+}
@@ -2,8 +2,9 @@
 
 use std::env;
 use std::path::PathBuf;
+use std::time::Duration;
 
-use super::options::{ColorConfig, Options, OutputFormat, RunIgnored};
+use super::options::{ColorConfig, Options, OutputFormat, RunIgnored, TestShard};
 use super::time::TestTimeOptions;
 use std::io::{self, IsTerminal};
 
@@ -23,9 +24,25 @@ pub struct TestOpts {
     pub format: OutputFormat,
     pub shuffle: bool,
     pub shuffle_seed: Option<u64>,
+    pub shard: Option<TestShard>,
     pub test_threads: Option<usize>,
     pub skip: Vec<String>,
     pub time_options: Option<TestTimeOptions>,
+    /// Run each test in its own process, and kill it if it is still
+    /// running after this long.
+    pub test_timeout: Option<Duration>,
+    /// Number of times a failing test is run again before it is reported as
+    /// failed.
+    pub retries: usize,
+    /// File the names of the failed tests are written to at the end of the run.
+    pub failed_state: Option<PathBuf>,
+    /// Only run the tests recorded as failed in `failed_state`, or all tests
+    /// if none were recorded.
+    pub rerun_failed: bool,
+    /// Save the benchmark results as the baseline with this name.
+    pub save_baseline: Option<String>,
+    /// Compare the benchmark results with the baseline with this name.
+    pub baseline: Option<String>,
     /// Stop at first failing test.
     /// May run a few more tests due to threading, but will
     /// abort as soon as possible.
@@ -102,8 +119,9 @@ fn optgroups() -> getopts::Options {
             pretty = Print verbose output;
             terse  = Display one character per test;
             json   = Output a json document;
-            junit  = Output a JUnit document",
-            "pretty|terse|json|junit",
+            junit  = Output a JUnit document;
+            tap    = Output a TAP (Test Anything Protocol) stream",
+            "pretty|terse|json|junit|tap",
         )
         .optflag("", "show-output", "Show captured stdout of successful tests")
         .optopt(
@@ -142,12 +160,59 @@ fn optgroups() -> getopts::Options {
             `CRITICAL_TIME` here means the limit that should not be exceeded by test.
             ",
         )
+        .optopt(
+            "",
+            "test-timeout",
+            "Run each test in a separate process, and kill it and report it as
+            failed if it is still running after SECS seconds.",
+            "SECS",
+        )
+        .optopt(
+            "",
+            "retries",
+            "Run a failing test up to N more times. Tests that pass on a retry
+            are reported as flaky instead of passed.",
+            "N",
+        )
+        .optopt(
+            "",
+            "failed-state",
+            "Write the names of the failed tests to PATH at the end of the run",
+            "PATH",
+        )
+        .optflag(
+            "",
+            "rerun-failed",
+            "Only run the tests that failed in the previous run, as recorded in
+            the --failed-state file (by default, next to the test binary). All
+            tests are run if none were recorded.",
+        )
+        .optopt(
+            "",
+            "save-baseline",
+            "Save the benchmark results as the baseline called NAME",
+            "NAME",
+        )
+        .optopt(
+            "",
+            "baseline",
+            "Compare the benchmark results with the baseline called NAME,
+            previously saved with --save-baseline",
+            "NAME",
+        )
         .optflag("", "shuffle", "Run tests in random order")
         .optopt(
             "",
             "shuffle-seed",
             "Run tests in random order; seed the random number generator with SEED",
             "SEED",
+        )
+        .optopt(
+            "",
+            "shard",
+            "Split the tests into COUNT shards and only run the tests of shard INDEX
+            (counting from 1). Tests are assigned to shards by a hash of their name.",
+            "INDEX/COUNT",
         );
     opts
 }
@@ -259,8 +324,16 @@ fn parse_opts_impl(matches: getopts::Matches) -> OptRes {
     let force_run_in_process = unstable_optflag!(matches, allow_unstable, "force-run-in-process");
     let exclude_should_panic = unstable_optflag!(matches, allow_unstable, "exclude-should-panic");
     let time_options = get_time_options(&matches, allow_unstable)?;
+    let test_timeout = get_test_timeout(&matches, allow_unstable)?;
+    let retries = get_retries(&matches, allow_unstable)?;
+    let rerun_failed = unstable_optflag!(matches, allow_unstable, "rerun-failed");
+    let failed_state = get_failed_state(&matches, allow_unstable, rerun_failed)?;
+    let save_baseline =
+        get_baseline_name(unstable_optopt!(matches, allow_unstable, "save-baseline"))?;
+    let baseline = get_baseline_name(unstable_optopt!(matches, allow_unstable, "baseline"))?;
     let shuffle = get_shuffle(&matches, allow_unstable)?;
     let shuffle_seed = get_shuffle_seed(&matches, allow_unstable)?;
+    let shard = get_shard(&matches, allow_unstable)?;
 
     let include_ignored = matches.opt_present("include-ignored");
     let quiet = matches.opt_present("quiet");
@@ -296,9 +369,16 @@ fn parse_opts_impl(matches: getopts::Matches) -> OptRes {
         format,
         shuffle,
         shuffle_seed,
+        shard,
         test_threads,
         skip,
         time_options,
+        test_timeout,
+        retries,
+        failed_state,
+        rerun_failed,
+        save_baseline,
+        baseline,
         options,
         fail_fast: false,
     };
@@ -336,6 +416,82 @@ fn get_time_options(
     Ok(options)
 }
 
+fn get_test_timeout(
+    matches: &getopts::Matches,
+    allow_unstable: bool,
+) -> OptPartRes<Option<Duration>> {
+    let test_timeout = match unstable_optopt!(matches, allow_unstable, "test-timeout") {
+        Some(secs_str) => match secs_str.parse::<f64>().map(Duration::try_from_secs_f64) {
+            Ok(Ok(timeout)) if !timeout.is_zero() => Some(timeout),
+            _ => {
+                return Err(format!(
+                    "argument for --test-timeout must be a positive number of seconds \
+                     (was {secs_str})"
+                ));
+            }
+        },
+        None => None,
+    };
+
+    Ok(test_timeout)
+}
+
+fn get_retries(matches: &getopts::Matches, allow_unstable: bool) -> OptPartRes<usize> {
+    let retries = match unstable_optopt!(matches, allow_unstable, "retries") {
+        Some(n_str) => match n_str.parse::<usize>() {
+            Ok(n) => n,
+            Err(e) => {
+                return Err(format!(
+                    "argument for --retries must be a number \
+                     (error: {e})"
+                ));
+            }
+        },
+        None => 0,
+    };
+
+    Ok(retries)
+}
+
+fn get_failed_state(
+    matches: &getopts::Matches,
+    allow_unstable: bool,
+    rerun_failed: bool,
+) -> OptPartRes<Option<PathBuf>> {
+    if let Some(path) = unstable_optopt!(matches, allow_unstable, "failed-state") {
+        return Ok(Some(PathBuf::from(path)));
+    }
+    if !rerun_failed {
+        return Ok(None);
+    }
+
+    // Keep the state next to the test binary, which usually lives in the
+    // build directory, so that each test binary has its own.
+    let mut path = env::current_exe()
+        .map_err(|e| format!("--rerun-failed requires --failed-state (error: {e})"))?
+        .into_os_string();
+    path.push(".failed-tests");
+    Ok(Some(PathBuf::from(path)))
+}
+
+fn get_baseline_name(name: Option<String>) -> OptPartRes<Option<String>> {
+    // The name is used as a file name, so keep it to characters that are safe
+    // to use in one.
+    match name {
+        Some(name)
+            if name.is_empty()
+                || name.starts_with('.')
+                || !name.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c)) =>
+        {
+            Err(format!(
+                "baseline names may only contain ASCII letters, digits, `-`, `_` and `.`, \
+                 and must not start with `.` (was `{name}`)"
+            ))
+        }
+        name => Ok(name),
+    }
+}
+
 fn get_shuffle(matches: &getopts::Matches, allow_unstable: bool) -> OptPartRes<bool> {
     let mut shuffle = unstable_optflag!(matches, allow_unstable, "shuffle");
     if !shuffle && allow_unstable {
@@ -375,6 +531,30 @@ fn get_shuffle_seed(matches: &getopts::Matches, allow_unstable: bool) -> OptPart
     Ok(shuffle_seed)
 }
 
+fn get_shard(matches: &getopts::Matches, allow_unstable: bool) -> OptPartRes<Option<TestShard>> {
+    let shard_str = match unstable_optopt!(matches, allow_unstable, "shard") {
+        Some(shard_str) => shard_str,
+        None => return Ok(None),
+    };
+
+    let invalid = |reason: &str| {
+        format!("argument for --shard must be of the form INDEX/COUNT ({reason}, was {shard_str})")
+    };
+    let Some((index, count)) = shard_str.split_once('/') else {
+        return Err(invalid("missing `/`"));
+    };
+    let index = index.parse::<usize>().map_err(|e| invalid(&format!("bad INDEX: {e}")))?;
+    let count = count.parse::<usize>().map_err(|e| invalid(&format!("bad COUNT: {e}")))?;
+    if count == 0 {
+        return Err(invalid("COUNT must not be 0"));
+    }
+    if index == 0 || index > count {
+        return Err(invalid("INDEX must be between 1 and COUNT"));
+    }
+
+    Ok(Some(TestShard { index, count }))
+}
+
 fn get_test_threads(matches: &getopts::Matches) -> OptPartRes<Option<usize>> {
     let test_threads = match matches.opt_str("test-threads") {
         Some(n_str) => match n_str.parse::<usize>() {
@@ -414,9 +594,15 @@ fn get_format(
             }
             OutputFormat::Junit
         }
+        Some("tap") => {
+            if !allow_unstable {
+                return Err("The \"tap\" format is only accepted on the nightly compiler with -Z unstable-options".into());
+            }
+            OutputFormat::Tap
+        }
         Some(v) => {
             return Err(format!(
-                "argument for --format must be pretty, terse, json or junit (was \
+                "argument for --format must be pretty, terse, json, junit or tap (was \
                  {v})"
             ));
         }
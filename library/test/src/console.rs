@@ -6,16 +6,20 @@
 use std::time::Instant;
 
 use super::{
-    bench::fmt_bench_samples,
+    baseline::{save_baseline, Baseline},
+    bench::{fmt_bench_samples, BenchSamples},
     cli::TestOpts,
     event::{CompletedTest, TestEvent},
     filter_tests,
-    formatters::{JsonFormatter, JunitFormatter, OutputFormatter, PrettyFormatter, TerseFormatter},
-    helpers::{concurrency::get_concurrency, metrics::MetricMap},
+    formatters::{
+        JsonFormatter, JunitFormatter, OutputFormatter, PrettyFormatter, TapFormatter,
+        TerseFormatter,
+    },
+    helpers::{concurrency::get_concurrency, failed_state::{retain_failed_tests, write_failed_tests}, metrics::MetricMap},
     options::{Options, OutputFormat},
     run_tests, term,
     test_result::TestResult,
-    time::{TestExecTime, TestSuiteExecTime},
+    time::{is_instant_supported, TestExecTime, TestSuiteExecTime},
     types::{NamePadding, TestDesc, TestDescAndFn},
 };
 
@@ -80,6 +84,7 @@ pub struct ConsoleTestState {
     pub passed: usize,
     pub failed: usize,
     pub ignored: usize,
+    pub flaky: usize,
     pub filtered_out: usize,
     pub measured: usize,
     pub exec_time: Option<TestSuiteExecTime>,
@@ -88,6 +93,10 @@ pub struct ConsoleTestState {
     pub not_failures: Vec<(TestDesc, Vec<u8>)>,
     pub ignores: Vec<(TestDesc, Vec<u8>)>,
     pub time_failures: Vec<(TestDesc, Vec<u8>)>,
+    pub flaky_tests: Vec<(TestDesc, Vec<u8>)>,
+    pub bench_results: Vec<(TestDesc, BenchSamples)>,
+    /// Baseline the benchmark results are compared with.
+    pub baseline: Option<Baseline>,
     pub options: Options,
 }
 
@@ -97,6 +106,10 @@ pub fn new(opts: &TestOpts) -> io::Result<ConsoleTestState> {
             Some(ref path) => Some(File::create(path)?),
             None => None,
         };
+        let baseline = match opts.baseline {
+            Some(ref name) => Some(Baseline::load(name)?),
+            None => None,
+        };
 
         Ok(ConsoleTestState {
             log_out,
@@ -104,6 +117,7 @@ pub fn new(opts: &TestOpts) -> io::Result<ConsoleTestState> {
             passed: 0,
             failed: 0,
             ignored: 0,
+            flaky: 0,
             filtered_out: 0,
             measured: 0,
             exec_time: None,
@@ -112,6 +126,9 @@ pub fn new(opts: &TestOpts) -> io::Result<ConsoleTestState> {
             not_failures: Vec::new(),
             ignores: Vec::new(),
             time_failures: Vec::new(),
+            flaky_tests: Vec::new(),
+            bench_results: Vec::new(),
+            baseline,
             options: opts.options,
         })
     }
@@ -154,6 +171,8 @@ pub fn write_log_result(
                     }
                     TestResult::TrBench(ref bs) => fmt_bench_samples(bs),
                     TestResult::TrTimedFail => "failed (time limit exceeded)".to_owned(),
+                    TestResult::TrTimedOut => "failed (timed out)".to_owned(),
+                    TestResult::TrFlaky(attempt) => format!("flaky (passed on attempt {attempt})"),
                 },
                 name,
             )
@@ -165,7 +184,7 @@ pub fn write_log_result(
     }
 
     fn current_test_count(&self) -> usize {
-        self.passed + self.failed + self.ignored + self.measured
+        self.passed + self.failed + self.ignored + self.measured + self.flaky
     }
 }
 
@@ -177,7 +196,7 @@ pub fn list_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Res
     };
 
     let mut out: Box<dyn OutputFormatter> = match opts.format {
-        OutputFormat::Pretty | OutputFormat::Junit => {
+        OutputFormat::Pretty | OutputFormat::Junit | OutputFormat::Tap => {
             Box::new(PrettyFormatter::new(output, false, 0, false, None))
         }
         OutputFormat::Terse => Box::new(TerseFormatter::new(output, false, 0, false)),
@@ -230,7 +249,8 @@ fn handle_test_result(st: &mut ConsoleTestState, completed_test: CompletedTest)
                 bs.ns_iter_summ.median,
                 bs.ns_iter_summ.max - bs.ns_iter_summ.min,
             );
-            st.measured += 1
+            st.measured += 1;
+            st.bench_results.push((test, bs));
         }
         TestResult::TrFailed => {
             st.failed += 1;
@@ -246,6 +266,19 @@ fn handle_test_result(st: &mut ConsoleTestState, completed_test: CompletedTest)
             st.failed += 1;
             st.time_failures.push((test, stdout));
         }
+        TestResult::TrTimedOut => {
+            st.failed += 1;
+            let mut stdout = stdout;
+            if !stdout.is_empty() && !stdout.ends_with(b"\n") {
+                stdout.push(b'\n');
+            }
+            stdout.extend_from_slice(b"note: test did not finish in time and was killed");
+            st.failures.push((test, stdout));
+        }
+        TestResult::TrFlaky(_) => {
+            st.flaky += 1;
+            st.flaky_tests.push((test, stdout));
+        }
     }
 }
 
@@ -270,10 +303,9 @@ fn on_test_event(
             let test = &completed_test.desc;
             let result = &completed_test.result;
             let exec_time = &completed_test.exec_time;
-            let stdout = &completed_test.stdout;
 
             st.write_log_result(test, result, exec_time.as_ref())?;
-            out.write_result(test, result, exec_time.as_ref(), stdout, st)?;
+            out.write_completed_test(&completed_test, st)?;
             handle_test_result(st, completed_test);
         }
     }
@@ -283,7 +315,11 @@ fn on_test_event(
 
 /// A simple console test runner.
 /// Runs provided tests reporting process and results to the stdout.
-pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Result<bool> {
+pub fn run_tests_console(opts: &TestOpts, mut tests: Vec<TestDescAndFn>) -> io::Result<bool> {
+    if let Some(path) = opts.failed_state.as_ref().filter(|_| opts.rerun_failed) {
+        retain_failed_tests(path, &mut tests)?;
+    }
+
     let output = match term::stdout() {
         None => OutputLocation::Raw(io::stdout()),
         Some(t) => OutputLocation::Pretty(t),
@@ -310,21 +346,27 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
         }
         OutputFormat::Json => Box::new(JsonFormatter::new(output)),
         OutputFormat::Junit => Box::new(JunitFormatter::new(output)),
+        OutputFormat::Tap => Box::new(TapFormatter::new(output)),
     };
     let mut st = ConsoleTestState::new(opts)?;
 
-    // Prevent the usage of `Instant` in some cases:
-    // - It's currently not supported for wasm targets.
-    let is_instant_unsupported =
-        (cfg!(target_family = "wasm") && !cfg!(target_os = "wasi")) || cfg!(target_os = "zkvm");
-
-    let start_time = (!is_instant_unsupported).then(Instant::now);
+    let start_time = is_instant_supported().then(Instant::now);
     run_tests(opts, tests, |x| on_test_event(&x, &mut st, &mut *out))?;
     st.exec_time = start_time.map(|t| TestSuiteExecTime(t.elapsed()));
 
     assert!(opts.fail_fast || st.current_test_count() == st.total);
 
-    out.write_run_finish(&st)
+    let success = out.write_run_finish(&st)?;
+
+    if let Some(ref path) = opts.failed_state {
+        let failed = st.failures.iter().chain(&st.time_failures);
+        write_failed_tests(path, failed.map(|(desc, _)| desc.name.as_slice()))?;
+    }
+    if let Some(ref name) = opts.save_baseline {
+        save_baseline(name, &st.bench_results)?;
+    }
+
+    Ok(success)
 }
 
 // Calculates padding for given test description.
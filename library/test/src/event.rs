@@ -2,9 +2,18 @@
 //! during tests execution process.
 
 use super::test_result::TestResult;
-use super::time::TestExecTime;
+use super::time::{TestExecTime, TestTimestamps};
 use super::types::{TestDesc, TestId};
 
+/// Where a test was run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestRunner {
+    /// A thread of the test harness process, identified by its `ThreadId`.
+    Thread(u64),
+    /// A child process, identified by its process id.
+    Process(u32),
+}
+
 #[derive(Debug, Clone)]
 pub struct CompletedTest {
     pub id: TestId,
@@ -12,6 +21,11 @@ pub struct CompletedTest {
     pub result: TestResult,
     pub exec_time: Option<TestExecTime>,
     pub stdout: Vec<u8>,
+    /// A copy of the output of the test on stderr, if it was captured on its
+    /// own as well. `stdout` holds all output of the test either way.
+    pub stderr: Option<Vec<u8>>,
+    pub timestamps: Option<TestTimestamps>,
+    pub runner: Option<TestRunner>,
 }
 
 impl CompletedTest {
@@ -22,7 +36,7 @@ pub fn new(
         exec_time: Option<TestExecTime>,
         stdout: Vec<u8>,
     ) -> Self {
-        Self { id, desc, result, exec_time, stdout }
+        Self { id, desc, result, exec_time, stdout, stderr: None, timestamps: None, runner: None }
     }
 }
 
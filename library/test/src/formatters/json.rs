@@ -1,20 +1,29 @@
-use std::{borrow::Cow, io, io::prelude::Write};
+use std::{borrow::Cow, fmt::Write as _, io, io::prelude::Write, time::Instant};
 
 use super::OutputFormatter;
 use crate::{
     console::{ConsoleTestDiscoveryState, ConsoleTestState, OutputLocation},
+    event::{CompletedTest, TestRunner},
     test_result::TestResult,
-    time,
+    time::{self, is_instant_supported},
     types::TestDesc,
 };
 
 pub(crate) struct JsonFormatter<T> {
     out: OutputLocation<T>,
+    /// When the test run started. The start and end times of the tests are
+    /// reported relative to it.
+    run_start: Option<Instant>,
 }
 
 impl<T: Write> JsonFormatter<T> {
     pub fn new(out: OutputLocation<T>) -> Self {
-        Self { out }
+        Self { out, run_start: None }
+    }
+
+    #[cfg(test)]
+    pub fn output_location(&self) -> &OutputLocation<T> {
+        &self.out
     }
 
     fn writeln_message(&mut self, s: &str) -> io::Result<()> {
@@ -34,6 +43,7 @@ fn write_event(
         exec_time: Option<&time::TestExecTime>,
         stdout: Option<Cow<'_, str>>,
         extra: Option<&str>,
+        details: &str,
     ) -> io::Result<()> {
         // A doc test's name includes a filename which must be escaped for correct json.
         let name = EscapedString(name);
@@ -52,10 +62,172 @@ fn write_event(
         let newline = "\n";
 
         self.writeln_message(&format!(
-                r#"{{ "type": "{ty}", "name": "{name}", "event": "{event}"{exec_time_json}{stdout_json}{extra_json} }}{newline}"#))
+                r#"{{ "type": "{ty}", "name": "{name}", "event": "{event}"{exec_time_json}{stdout_json}{extra_json}{details} }}{newline}"#))
+    }
+
+    fn write_test_result(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        exec_time: Option<&time::TestExecTime>,
+        stdout: &[u8],
+        test: Option<&CompletedTest>,
+        state: &ConsoleTestState,
+    ) -> io::Result<()> {
+        let display_stdout = state.options.display_output || *result != TestResult::TrOk;
+        let stdout = if display_stdout && !stdout.is_empty() {
+            Some(String::from_utf8_lossy(stdout))
+        } else {
+            None
+        };
+
+        let mut details = location_json(desc);
+        if let Some(test) = test {
+            if let Some(stderr) = test.stderr.as_deref().filter(|stderr| !stderr.is_empty()) {
+                if display_stdout {
+                    let stderr = String::from_utf8_lossy(stderr);
+                    write!(details, r#", "stderr": "{}""#, EscapedString(stderr)).unwrap();
+                }
+            }
+            if let (Some(run_start), Some(timestamps)) = (self.run_start, test.timestamps) {
+                let start = timestamps.start.saturating_duration_since(run_start);
+                let end = timestamps.end.saturating_duration_since(run_start);
+                write!(
+                    details,
+                    r#", "start_time": {}, "end_time": {}"#,
+                    start.as_secs_f64(),
+                    end.as_secs_f64()
+                )
+                .unwrap();
+            }
+            match test.runner {
+                Some(TestRunner::Thread(id)) => write!(details, r#", "thread_id": {id}"#).unwrap(),
+                Some(TestRunner::Process(id)) => {
+                    write!(details, r#", "process_id": {id}"#).unwrap()
+                }
+                None => {}
+            }
+        }
+        let details = details.as_str();
+
+        match *result {
+            TestResult::TrOk => self.write_event(
+                "test",
+                desc.name.as_slice(),
+                "ok",
+                exec_time,
+                stdout,
+                None,
+                details,
+            ),
+
+            TestResult::TrFailed => self.write_event(
+                "test",
+                desc.name.as_slice(),
+                "failed",
+                exec_time,
+                stdout,
+                None,
+                details,
+            ),
+
+            TestResult::TrTimedFail => self.write_event(
+                "test",
+                desc.name.as_slice(),
+                "failed",
+                exec_time,
+                stdout,
+                Some(r#""reason": "time limit exceeded""#),
+                details,
+            ),
+
+            TestResult::TrFlaky(attempt) => self.write_event(
+                "test",
+                desc.name.as_slice(),
+                "flaky",
+                exec_time,
+                stdout,
+                Some(&*format!(r#""attempts": {attempt}"#)),
+                details,
+            ),
+
+            TestResult::TrTimedOut => self.write_event(
+                "test",
+                desc.name.as_slice(),
+                "failed",
+                exec_time,
+                stdout,
+                Some(r#""reason": "timed out""#),
+                details,
+            ),
+
+            TestResult::TrFailedMsg(ref m) => self.write_event(
+                "test",
+                desc.name.as_slice(),
+                "failed",
+                exec_time,
+                stdout,
+                Some(&*format!(r#""message": "{}""#, EscapedString(m))),
+                details,
+            ),
+
+            TestResult::TrIgnored => self.write_event(
+                "test",
+                desc.name.as_slice(),
+                "ignored",
+                exec_time,
+                stdout,
+                desc.ignore_message
+                    .map(|msg| format!(r#""message": "{}""#, EscapedString(msg)))
+                    .as_deref(),
+                details,
+            ),
+
+            TestResult::TrBench(ref bs) => {
+                let median = bs.ns_iter_summ.median;
+                let deviation = bs.ns_iter_summ.max - bs.ns_iter_summ.min;
+
+                let mbps = if bs.mb_s == 0 {
+                    String::new()
+                } else {
+                    format!(r#", "mib_per_second": {}"#, bs.mb_s)
+                };
+                let baseline = state.baseline.as_ref();
+                let change = match baseline.and_then(|b| b.compare(desc.name.as_slice(), bs)) {
+                    Some(change) => format!(
+                        r#", "baseline": {{ "name": "{}", "median": {}, "change": {}, "change_lower": {}, "change_upper": {}, "verdict": "{}" }}"#,
+                        EscapedString(change.baseline),
+                        change.base_median,
+                        change.change.estimate,
+                        change.change.lower,
+                        change.change.upper,
+                        change.verdict.as_str(),
+                    ),
+                    None => String::new(),
+                };
+                let name = EscapedString(desc.name.as_slice());
+
+                self.writeln_message(&format!(
+                    "{{ \"type\": \"bench\", \
+                     \"name\": \"{name}\", \
+                     \"median\": {median}, \
+                     \"deviation\": {deviation}{mbps}{change} }}\n",
+                ))
+            }
+        }
     }
 }
 
+/// Fields locating the definition of a test.
+fn location_json(desc: &TestDesc) -> String {
+    format!(
+        r#", "source_path": "{}", "start_line": {}, "start_col": {}"#,
+        EscapedString(desc.source_file),
+        desc.start_line,
+        desc.start_col
+    )
+}
+
 impl<T: Write> OutputFormatter for JsonFormatter<T> {
     fn write_discovery_start(&mut self) -> io::Result<()> {
         self.writeln_message(concat!(r#"{ "type": "suite", "event": "discovery" }"#, "\n"))
@@ -95,6 +267,7 @@ fn write_discovery_finish(&mut self, state: &ConsoleTestDiscoveryState) -> io::R
     }
 
     fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>) -> io::Result<()> {
+        self.run_start = if is_instant_supported() { Some(Instant::now()) } else { None };
         let shuffle_seed_json = if let Some(shuffle_seed) = shuffle_seed {
             format!(r#", "shuffle_seed": {shuffle_seed}"#)
         } else {
@@ -108,9 +281,10 @@ fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>) -> i
 
     fn write_test_start(&mut self, desc: &TestDesc) -> io::Result<()> {
         let name = EscapedString(desc.name.as_slice());
+        let location_json = location_json(desc);
         let newline = "\n";
         self.writeln_message(&format!(
-            r#"{{ "type": "test", "event": "started", "name": "{name}" }}{newline}"#
+            r#"{{ "type": "test", "event": "started", "name": "{name}"{location_json} }}{newline}"#
         ))
     }
 
@@ -122,69 +296,16 @@ fn write_result(
         stdout: &[u8],
         state: &ConsoleTestState,
     ) -> io::Result<()> {
-        let display_stdout = state.options.display_output || *result != TestResult::TrOk;
-        let stdout = if display_stdout && !stdout.is_empty() {
-            Some(String::from_utf8_lossy(stdout))
-        } else {
-            None
-        };
-        match *result {
-            TestResult::TrOk => {
-                self.write_event("test", desc.name.as_slice(), "ok", exec_time, stdout, None)
-            }
-
-            TestResult::TrFailed => {
-                self.write_event("test", desc.name.as_slice(), "failed", exec_time, stdout, None)
-            }
-
-            TestResult::TrTimedFail => self.write_event(
-                "test",
-                desc.name.as_slice(),
-                "failed",
-                exec_time,
-                stdout,
-                Some(r#""reason": "time limit exceeded""#),
-            ),
-
-            TestResult::TrFailedMsg(ref m) => self.write_event(
-                "test",
-                desc.name.as_slice(),
-                "failed",
-                exec_time,
-                stdout,
-                Some(&*format!(r#""message": "{}""#, EscapedString(m))),
-            ),
-
-            TestResult::TrIgnored => self.write_event(
-                "test",
-                desc.name.as_slice(),
-                "ignored",
-                exec_time,
-                stdout,
-                desc.ignore_message
-                    .map(|msg| format!(r#""message": "{}""#, EscapedString(msg)))
-                    .as_deref(),
-            ),
-
-            TestResult::TrBench(ref bs) => {
-                let median = bs.ns_iter_summ.median;
-                let deviation = bs.ns_iter_summ.max - bs.ns_iter_summ.min;
-
-                let mbps = if bs.mb_s == 0 {
-                    String::new()
-                } else {
-                    format!(r#", "mib_per_second": {}"#, bs.mb_s)
-                };
-                let name = EscapedString(desc.name.as_slice());
+        self.write_test_result(desc, result, exec_time, stdout, None, state)
+    }
 
-                self.writeln_message(&format!(
-                    "{{ \"type\": \"bench\", \
-                     \"name\": \"{name}\", \
-                     \"median\": {median}, \
-                     \"deviation\": {deviation}{mbps} }}\n",
-                ))
-            }
-        }
+    fn write_completed_test(
+        &mut self,
+        test: &CompletedTest,
+        state: &ConsoleTestState,
+    ) -> io::Result<()> {
+        let exec_time = test.exec_time.as_ref();
+        self.write_test_result(&test.desc, &test.result, exec_time, &test.stdout, Some(test), state)
     }
 
     fn write_timeout(&mut self, desc: &TestDesc) -> io::Result<()> {
@@ -202,6 +323,11 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
         let ignored = state.ignored;
         let measured = state.measured;
         let filtered_out = state.filtered_out;
+        let flaky_json = if state.flaky != 0 {
+            format!(r#", "flaky": {}"#, state.flaky)
+        } else {
+            String::from("")
+        };
         let exec_time_json = if let Some(ref exec_time) = state.exec_time {
             format!(r#", "exec_time": {}"#, exec_time.0.as_secs_f64())
         } else {
@@ -210,7 +336,7 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
         let newline = "\n";
 
         self.writeln_message(&format!(
-            r#"{{ "type": "suite", "event": "{event}", "passed": {passed}, "failed": {failed}, "ignored": {ignored}, "measured": {measured}, "filtered_out": {filtered_out}{exec_time_json} }}{newline}"#
+            r#"{{ "type": "suite", "event": "{event}", "passed": {passed}, "failed": {failed}{flaky_json}, "ignored": {ignored}, "measured": {measured}, "filtered_out": {filtered_out}{exec_time_json} }}{newline}"#
         ))?;
 
         Ok(state.failed == 0)
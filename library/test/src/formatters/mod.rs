@@ -2,6 +2,7 @@
 
 use crate::{
     console::{ConsoleTestDiscoveryState, ConsoleTestState},
+    event::CompletedTest,
     test_result::TestResult,
     time,
     types::{TestDesc, TestName},
@@ -10,11 +11,13 @@
 mod json;
 mod junit;
 mod pretty;
+mod tap;
 mod terse;
 
 pub(crate) use self::json::JsonFormatter;
 pub(crate) use self::junit::JunitFormatter;
 pub(crate) use self::pretty::PrettyFormatter;
+pub(crate) use self::tap::TapFormatter;
 pub(crate) use self::terse::TerseFormatter;
 
 pub(crate) trait OutputFormatter {
@@ -33,9 +36,31 @@ fn write_result(
         stdout: &[u8],
         state: &ConsoleTestState,
     ) -> io::Result<()>;
+    /// Writes the result of a completed test. Formatters that report more
+    /// about the test than `write_result` receives override this.
+    fn write_completed_test(
+        &mut self,
+        test: &CompletedTest,
+        state: &ConsoleTestState,
+    ) -> io::Result<()> {
+        self.write_result(&test.desc, &test.result, test.exec_time.as_ref(), &test.stdout, state)
+    }
     fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool>;
 }
 
+pub(crate) fn write_attempt_delimiter(
+    test_output: &mut Vec<u8>,
+    test_name: &TestName,
+    attempt: usize,
+) {
+    match test_output.last() {
+        Some(b'\n') => (),
+        Some(_) => test_output.push(b'\n'),
+        None => (),
+    }
+    writeln!(test_output, "---- {test_name} attempt {attempt} ----").unwrap();
+}
+
 pub(crate) fn write_stderr_delimiter(test_output: &mut Vec<u8>, test_name: &TestName) {
     match test_output.last() {
         Some(b'\n') => (),
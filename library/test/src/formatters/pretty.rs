@@ -2,6 +2,7 @@
 
 use super::OutputFormatter;
 use crate::{
+    baseline::{fmt_bench_change, BenchChange, Verdict},
     bench::fmt_bench_samples,
     console::{ConsoleTestDiscoveryState, ConsoleTestState, OutputLocation},
     term,
@@ -57,10 +58,31 @@ pub fn write_time_failed(&mut self) -> io::Result<()> {
         self.write_short_result("FAILED (time limit exceeded)", term::color::RED)
     }
 
+    pub fn write_timed_out(&mut self) -> io::Result<()> {
+        self.write_short_result("FAILED (timed out)", term::color::RED)
+    }
+
+    pub fn write_flaky(&mut self, attempt: usize) -> io::Result<()> {
+        self.write_short_result(
+            &format!("FLAKY (passed on attempt {attempt})"),
+            term::color::YELLOW,
+        )
+    }
+
     pub fn write_bench(&mut self) -> io::Result<()> {
         self.write_pretty("bench", term::color::CYAN)
     }
 
+    pub fn write_bench_change(&mut self, change: &BenchChange<'_>) -> io::Result<()> {
+        self.write_plain(format!(" {} (", fmt_bench_change(change)))?;
+        match change.verdict {
+            Verdict::Regressed => self.write_pretty(change.verdict.as_str(), term::color::RED)?,
+            Verdict::Improved => self.write_pretty(change.verdict.as_str(), term::color::GREEN)?,
+            Verdict::NoChange => self.write_plain(change.verdict.as_str())?,
+        }
+        self.write_plain(")")
+    }
+
     pub fn write_short_result(
         &mut self,
         result: &str,
@@ -168,6 +190,10 @@ pub fn write_time_failures(&mut self, state: &ConsoleTestState) -> io::Result<()
         self.write_results(&state.time_failures, "failures (time limit exceeded)")
     }
 
+    pub fn write_flaky_tests(&mut self, state: &ConsoleTestState) -> io::Result<()> {
+        self.write_results(&state.flaky_tests, "flaky")
+    }
+
     fn write_test_name(&mut self, desc: &TestDesc) -> io::Result<()> {
         let name = desc.padded_name(self.max_name_len, desc.name.padding());
         if let Some(test_mode) = desc.test_mode() {
@@ -236,7 +262,7 @@ fn write_result(
         result: &TestResult,
         exec_time: Option<&time::TestExecTime>,
         _: &[u8],
-        _: &ConsoleTestState,
+        state: &ConsoleTestState,
     ) -> io::Result<()> {
         if self.is_multithreaded {
             self.write_test_name(desc)?;
@@ -249,8 +275,14 @@ fn write_result(
             TestResult::TrBench(ref bs) => {
                 self.write_bench()?;
                 self.write_plain(format!(": {}", fmt_bench_samples(bs)))?;
+                let baseline = state.baseline.as_ref();
+                if let Some(change) = baseline.and_then(|b| b.compare(desc.name.as_slice(), bs)) {
+                    self.write_bench_change(&change)?;
+                }
             }
             TestResult::TrTimedFail => self.write_time_failed()?,
+            TestResult::TrTimedOut => self.write_timed_out()?,
+            TestResult::TrFlaky(attempt) => self.write_flaky(attempt)?,
         }
 
         self.write_time(desc, exec_time)?;
@@ -269,6 +301,9 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
         if state.options.display_output {
             self.write_successes(state)?;
         }
+        if !state.flaky_tests.is_empty() {
+            self.write_flaky_tests(state)?;
+        }
         let success = state.failed == 0;
         if !success {
             if !state.failures.is_empty() {
@@ -289,8 +324,10 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
             self.write_pretty("FAILED", term::color::RED)?;
         }
 
+        let flaky =
+            if state.flaky != 0 { format!("; {} flaky", state.flaky) } else { String::new() };
         let s = format!(
-            ". {} passed; {} failed; {} ignored; {} measured; {} filtered out",
+            ". {} passed; {} failed{flaky}; {} ignored; {} measured; {} filtered out",
             state.passed, state.failed, state.ignored, state.measured, state.filtered_out
         );
 
@@ -0,0 +1,245 @@
+use std::{io, io::prelude::Write};
+
+use super::OutputFormatter;
+use crate::{
+    console::{ConsoleTestDiscoveryState, ConsoleTestState, OutputLocation},
+    test_result::TestResult,
+    time,
+    types::TestDesc,
+};
+
+/// Formatter emitting a [TAP 14](https://testanything.org/tap-version-14-specification.html)
+/// stream: a plan line, one test point per test and YAML diagnostic blocks for
+/// failures and captured output.
+pub(crate) struct TapFormatter<T> {
+    out: OutputLocation<T>,
+    /// Number of the next test point, TAP numbers them starting from 1.
+    test_number: usize,
+}
+
+impl<T: Write> TapFormatter<T> {
+    pub fn new(out: OutputLocation<T>) -> Self {
+        Self { out, test_number: 1 }
+    }
+
+    #[cfg(test)]
+    pub fn output_location(&self) -> &OutputLocation<T> {
+        &self.out
+    }
+
+    fn write_message(&mut self, s: &str) -> io::Result<()> {
+        // As with the json formatter, issue one `write_all` per line so that the
+        // stream can't end up with lines torn apart by another writer.
+        assert_eq!(s.chars().last(), Some('\n'));
+
+        self.out.write_all(s.as_ref())
+    }
+
+    fn write_test_point(
+        &mut self,
+        ok: bool,
+        desc: &TestDesc,
+        directive: Option<&str>,
+    ) -> io::Result<()> {
+        let status = if ok { "ok" } else { "not ok" };
+        let number = self.test_number;
+        let name = EscapedDescription(desc.name.as_slice());
+        let directive =
+            if let Some(directive) = directive { format!(" # {directive}") } else { String::new() };
+        self.test_number += 1;
+        self.write_message(&format!("{status} {number} - {name}{directive}\n"))
+    }
+
+    /// Writes the YAML diagnostic block attached to the test point that was
+    /// just written. Nothing is written if there is nothing to report.
+    fn write_diagnostics(&mut self, fields: &[(&str, String)], stdout: &[u8]) -> io::Result<()> {
+        if fields.is_empty() && stdout.is_empty() {
+            return Ok(());
+        }
+
+        self.write_message("  ---\n")?;
+        for (key, value) in fields {
+            self.write_message(&format!("  {key}: {value}\n"))?;
+        }
+        if !stdout.is_empty() {
+            // A literal block scalar keeps the output readable and needs no
+            // escaping besides the indentation.
+            self.write_message("  stdout: |\n")?;
+            for line in String::from_utf8_lossy(stdout).lines() {
+                self.write_message(&format!("    {line}\n"))?;
+            }
+        }
+        self.write_message("  ...\n")
+    }
+}
+
+impl<T: Write> OutputFormatter for TapFormatter<T> {
+    fn write_discovery_start(&mut self) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::NotFound, "Not yet implemented!"))
+    }
+
+    fn write_test_discovered(&mut self, _desc: &TestDesc, _test_type: &str) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::NotFound, "Not yet implemented!"))
+    }
+
+    fn write_discovery_finish(&mut self, _state: &ConsoleTestDiscoveryState) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::NotFound, "Not yet implemented!"))
+    }
+
+    fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>) -> io::Result<()> {
+        self.write_message("TAP version 14\n")?;
+        self.write_message(&format!("1..{test_count}\n"))?;
+        if let Some(shuffle_seed) = shuffle_seed {
+            self.write_message(&format!("# shuffle seed: {shuffle_seed}\n"))?;
+        }
+        Ok(())
+    }
+
+    fn write_test_start(&mut self, _desc: &TestDesc) -> io::Result<()> {
+        // We do not output anything on test start.
+        Ok(())
+    }
+
+    fn write_timeout(&mut self, desc: &TestDesc) -> io::Result<()> {
+        self.write_message(&format!(
+            "# test {} has been running for over {} seconds\n",
+            desc.name,
+            time::TEST_WARN_TIMEOUT_S
+        ))
+    }
+
+    fn write_result(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        exec_time: Option<&time::TestExecTime>,
+        stdout: &[u8],
+        state: &ConsoleTestState,
+    ) -> io::Result<()> {
+        let display_stdout = state.options.display_output || *result != TestResult::TrOk;
+        let stdout = if display_stdout { stdout } else { &[] };
+
+        let mut fields = Vec::new();
+        if let Some(exec_time) = exec_time {
+            fields.push(("duration_ms", format!("{}", exec_time.0.as_secs_f64() * 1000.0)));
+        }
+
+        match *result {
+            TestResult::TrOk => {
+                self.write_test_point(true, desc, None)?;
+                self.write_diagnostics(&fields, stdout)
+            }
+
+            TestResult::TrFailed => {
+                self.write_test_point(false, desc, None)?;
+                fields.insert(0, ("severity", String::from("fail")));
+                self.write_diagnostics(&fields, stdout)
+            }
+
+            TestResult::TrFailedMsg(ref m) => {
+                self.write_test_point(false, desc, None)?;
+                fields.insert(0, ("message", format!("\"{}\"", EscapedYamlString(m))));
+                fields.insert(1, ("severity", String::from("fail")));
+                self.write_diagnostics(&fields, stdout)
+            }
+
+            TestResult::TrTimedFail => {
+                self.write_test_point(false, desc, None)?;
+                fields.insert(0, ("message", String::from("\"time limit exceeded\"")));
+                fields.insert(1, ("severity", String::from("fail")));
+                self.write_diagnostics(&fields, stdout)
+            }
+
+            TestResult::TrTimedOut => {
+                self.write_test_point(false, desc, None)?;
+                fields.insert(0, ("message", String::from("\"timed out\"")));
+                fields.insert(1, ("severity", String::from("fail")));
+                self.write_diagnostics(&fields, stdout)
+            }
+
+            TestResult::TrFlaky(attempt) => {
+                self.write_test_point(true, desc, None)?;
+                fields.insert(0, ("flaky", String::from("true")));
+                fields.insert(1, ("attempts", format!("{attempt}")));
+                self.write_diagnostics(&fields, stdout)
+            }
+
+            TestResult::TrIgnored => {
+                let directive = match desc.ignore_message {
+                    Some(msg) => format!("SKIP {}", EscapedDescription(msg)),
+                    None => String::from("SKIP"),
+                };
+                self.write_test_point(true, desc, Some(&directive))
+            }
+
+            TestResult::TrBench(ref bs) => {
+                self.write_test_point(true, desc, None)?;
+                let median = bs.ns_iter_summ.median;
+                let deviation = bs.ns_iter_summ.max - bs.ns_iter_summ.min;
+                fields.push(("median_ns", format!("{median}")));
+                fields.push(("deviation_ns", format!("{deviation}")));
+                if bs.mb_s != 0 {
+                    fields.push(("mib_per_second", format!("{}", bs.mb_s)));
+                }
+                self.write_diagnostics(&fields, &[])
+            }
+        }
+    }
+
+    fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
+        let result = if state.failed == 0 { "ok" } else { "FAILED" };
+        let exec_time = if let Some(ref exec_time) = state.exec_time {
+            format!("; finished in {exec_time}")
+        } else {
+            String::new()
+        };
+        let flaky =
+            if state.flaky != 0 { format!("; {} flaky", state.flaky) } else { String::new() };
+        self.write_message(&format!(
+            "# test result: {result}. {} passed; {} failed{flaky}; {} ignored; {} measured; {} filtered out{exec_time}\n",
+            state.passed, state.failed, state.ignored, state.measured, state.filtered_out
+        ))?;
+
+        Ok(state.failed == 0)
+    }
+}
+
+/// Escapes the characters that have a special meaning in the description of a
+/// TAP test point: `#` starts a directive, and `\` is the escape character.
+/// Newlines would break the line-based protocol, so they are escaped as well.
+struct EscapedDescription<S: AsRef<str>>(S);
+
+impl<S: AsRef<str>> std::fmt::Display for EscapedDescription<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for c in self.0.as_ref().chars() {
+            match c {
+                '#' => f.write_str("\\#")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                c => std::fmt::Write::write_char(f, c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Escapes a string for use inside a double-quoted YAML scalar.
+struct EscapedYamlString<S: AsRef<str>>(S);
+
+impl<S: AsRef<str>> std::fmt::Display for EscapedYamlString<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for c in self.0.as_ref().chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                c if c.is_control() => write!(f, "\\x{:02x}", c as u32)?,
+                c => std::fmt::Write::write_char(f, c)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -50,8 +50,21 @@ pub fn write_ok(&mut self) -> io::Result<()> {
     }
 
     pub fn write_failed(&mut self, name: &str) -> io::Result<()> {
-        // Put failed tests on their own line and include the test name, so that it's faster
-        // to see which test failed without having to wait for them all to run.
+        self.write_named_result(name, "FAILED", term::color::RED)
+    }
+
+    pub fn write_flaky(&mut self, name: &str) -> io::Result<()> {
+        self.write_named_result(name, "FLAKY", term::color::YELLOW)
+    }
+
+    fn write_named_result(
+        &mut self,
+        name: &str,
+        result: &str,
+        color: term::color::Color,
+    ) -> io::Result<()> {
+        // Put failed and flaky tests on their own line and include the test name, so that it's
+        // faster to see which test failed without having to wait for them all to run.
 
         // normally, we write the progress unconditionally, even if the previous line was cut short.
         // but if this is the very first column, no short results will have been printed and we'll end up with *only* the progress on the line.
@@ -61,7 +74,7 @@ pub fn write_failed(&mut self, name: &str) -> io::Result<()> {
         }
         self.test_count += 1;
         self.write_plain(format!("{name} --- "))?;
-        self.write_pretty("FAILED", term::color::RED)?;
+        self.write_pretty(result, color)?;
         self.write_plain("\n")
     }
 
@@ -233,9 +246,11 @@ fn write_result(
     ) -> io::Result<()> {
         match *result {
             TestResult::TrOk => self.write_ok(),
-            TestResult::TrFailed | TestResult::TrFailedMsg(_) | TestResult::TrTimedFail => {
-                self.write_failed(desc.name.as_slice())
-            }
+            TestResult::TrFailed
+            | TestResult::TrFailedMsg(_)
+            | TestResult::TrTimedFail
+            | TestResult::TrTimedOut => self.write_failed(desc.name.as_slice()),
+            TestResult::TrFlaky(_) => self.write_flaky(desc.name.as_slice()),
             TestResult::TrIgnored => self.write_ignored(),
             TestResult::TrBench(ref bs) => {
                 if self.is_multithreaded {
@@ -273,8 +288,10 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
             self.write_pretty("FAILED", term::color::RED)?;
         }
 
+        let flaky =
+            if state.flaky != 0 { format!("; {} flaky", state.flaky) } else { String::new() };
         let s = format!(
-            ". {} passed; {} failed; {} ignored; {} measured; {} filtered out",
+            ". {} passed; {} failed{flaky}; {} ignored; {} measured; {} filtered out",
             state.passed, state.failed, state.ignored, state.measured, state.filtered_out
         );
 
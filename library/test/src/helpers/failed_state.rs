@@ -0,0 +1,47 @@
+//! Persistence of the names of failed tests, used by `--rerun-failed`.
+//!
+//! The state file holds one test name per line.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+
+use crate::types::TestDescAndFn;
+
+/// Reads the names of the tests recorded as failed in `path`.
+///
+/// A missing state file is not an error: no test has been recorded as failed yet.
+pub fn read_failed_tests(path: &Path) -> io::Result<Vec<String>> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(contents.lines().map(str::to_owned).collect()),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Keeps only the tests recorded as failed in `path`, or all of them if none
+/// were recorded.
+pub fn retain_failed_tests(path: &Path, tests: &mut Vec<TestDescAndFn>) -> io::Result<()> {
+    let failed = read_failed_tests(path).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!("could not read failed tests from {} (error: {e})", path.display()),
+        )
+    })?;
+    if !failed.is_empty() {
+        let failed: HashSet<&str> = failed.iter().map(String::as_str).collect();
+        tests.retain(|test| failed.contains(test.desc.name.as_slice()));
+    }
+    Ok(())
+}
+
+/// Replaces the contents of `path` with the names of the failed tests.
+pub fn write_failed_tests<'a>(path: &Path, names: impl Iterator<Item = &'a str>) -> io::Result<()> {
+    let mut contents = String::new();
+    for name in names {
+        contents.push_str(name);
+        contents.push('\n');
+    }
+    fs::write(path, contents)
+}
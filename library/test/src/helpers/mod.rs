@@ -2,5 +2,7 @@
 //! but used in `libtest`.
 
 pub mod concurrency;
+pub mod failed_state;
 pub mod metrics;
+pub mod shard;
 pub mod shuffle;
@@ -0,0 +1,21 @@
+use crate::options::TestShard;
+use crate::types::TestName;
+
+/// Returns whether the test named `name` belongs to `shard`.
+///
+/// Tests are assigned to shards by hashing their names, so the partition only
+/// depends on the set of test names and not on the order the tests are listed
+/// in, and every invocation sharing a `count` agrees on the assignment.
+pub fn is_in_shard(shard: TestShard, name: &TestName) -> bool {
+    let bucket = stable_hash(name.as_slice()) % shard.count as u64;
+    bucket as usize == shard.index - 1
+}
+
+// 64-bit FNV-1a. Unlike `DefaultHasher`, its output is fixed, so shards computed
+// by test binaries built with different toolchains still line up.
+fn stable_hash(s: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    s.bytes().fold(OFFSET_BASIS, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(PRIME))
+}
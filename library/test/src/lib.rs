@@ -21,14 +21,17 @@
 #![feature(internal_output_capture)]
 #![feature(staged_api)]
 #![feature(process_exitcode_internals)]
+#![feature(process_internals)]
+#![feature(child_wait_timeout)]
 #![feature(panic_can_unwind)]
+#![feature(thread_id_value)]
 #![feature(test)]
 #![allow(internal_features)]
 
 // Public reexports
 pub use self::bench::{black_box, Bencher};
 pub use self::console::run_tests_console;
-pub use self::options::{ColorConfig, Options, OutputFormat, RunIgnored, ShouldPanic};
+pub use self::options::{ColorConfig, Options, OutputFormat, RunIgnored, ShouldPanic, TestShard};
 pub use self::types::TestName::*;
 pub use self::types::*;
 pub use self::ColorConfig::*;
@@ -42,7 +45,7 @@ pub mod test {
         cli::{parse_opts, TestOpts},
         filter_tests,
         helpers::metrics::{Metric, MetricMap},
-        options::{Options, RunIgnored, RunStrategy, ShouldPanic},
+        options::{Options, RunIgnored, RunStrategy, ShouldPanic, TestShard},
         run_test, test_main, test_main_static,
         test_result::{TestResult, TrFailed, TrFailedMsg, TrIgnored, TrOk},
         time::{TestExecTime, TestTimeOptions},
@@ -66,6 +69,7 @@ pub mod test {
     time::{Duration, Instant},
 };
 
+mod baseline;
 pub mod bench;
 mod cli;
 mod console;
@@ -83,12 +87,13 @@ pub mod test {
 mod tests;
 
 use core::any::Any;
-use event::{CompletedTest, TestEvent};
+use event::{CompletedTest, TestEvent, TestRunner};
 use helpers::concurrency::get_concurrency;
+use helpers::shard::is_in_shard;
 use helpers::shuffle::{get_shuffle_seed, shuffle_tests};
 use options::RunStrategy;
 use test_result::*;
-use time::TestExecTime;
+use time::{is_instant_supported, TestExecTime, TestTimestamps};
 
 // Process exit code to be used to indicate test failures.
 const ERROR_EXIT_CODE: i32 = 101;
@@ -159,6 +164,10 @@ pub fn test_main(args: &[String], tests: Vec<TestDescAndFn>, options: Option<Opt
 /// This is the entry point for the main function generated by `rustc --test`
 /// when panic=unwind.
 pub fn test_main_static(tests: &[&TestDescAndFn]) {
+    // With `--test-timeout`, each test is run in a child process spawned from
+    // this same binary.
+    run_spawned_secondary_test(tests, false);
+
     let args = env::args().collect::<Vec<_>>();
     let owned_tests: Vec<_> = tests.iter().map(make_owned_test).collect();
     test_main(&args, owned_tests, None)
@@ -175,6 +184,17 @@ pub fn test_main_static(tests: &[&TestDescAndFn]) {
 pub fn test_main_static_abort(tests: &[&TestDescAndFn]) {
     // If we're being run in SpawnedSecondary mode, run the test here. run_test
     // will then exit the process.
+    run_spawned_secondary_test(tests, true);
+
+    let args = env::args().collect::<Vec<_>>();
+    let owned_tests: Vec<_> = tests.iter().map(make_owned_test).collect();
+    test_main(&args, owned_tests, Some(Options::new().panic_abort(true)))
+}
+
+/// If this process was spawned by the test runner to run a single test (either
+/// because of panic=abort or because of `--test-timeout`), runs that test and
+/// exits the process. Otherwise, does nothing.
+fn run_spawned_secondary_test(tests: &[&TestDescAndFn], panic_abort: bool) {
     if let Ok(name) = env::var(SECONDARY_TEST_INVOKER_VAR) {
         env::remove_var(SECONDARY_TEST_INVOKER_VAR);
 
@@ -196,17 +216,13 @@ pub fn test_main_static_abort(tests: &[&TestDescAndFn]) {
                 if runnable_test.is_dynamic() {
                     panic!("only static tests are supported");
                 }
-                run_test_in_spawned_subprocess(desc, runnable_test);
+                run_test_in_spawned_subprocess(desc, runnable_test, panic_abort);
             }
             Runnable::Bench(_) => {
                 panic!("benchmarks should not be executed into child processes")
             }
         }
     }
-
-    let args = env::args().collect::<Vec<_>>();
-    let owned_tests: Vec<_> = tests.iter().map(make_owned_test).collect();
-    test_main(&args, owned_tests, Some(Options::new().panic_abort(true)))
 }
 
 /// Clones static values for putting into a dynamic vector, which test_main()
@@ -258,6 +274,40 @@ fn total_len(&self) -> usize {
     }
 }
 
+struct TimeoutEntry {
+    id: TestId,
+    desc: TestDesc,
+    timeout: Instant,
+}
+
+/// Pops the entries whose deadline has passed off the front of the queue, and
+/// returns the ones that belong to tests that are still running.
+fn get_timed_out_tests(
+    is_running: impl Fn(TestId) -> bool,
+    timeout_queue: &mut VecDeque<TimeoutEntry>,
+) -> Vec<TestDesc> {
+    let now = Instant::now();
+    let mut timed_out = Vec::new();
+    while let Some(timeout_entry) = timeout_queue.front() {
+        if now < timeout_entry.timeout {
+            break;
+        }
+        let timeout_entry = timeout_queue.pop_front().unwrap();
+        if is_running(timeout_entry.id) {
+            timed_out.push(timeout_entry.desc);
+        }
+    }
+    timed_out
+}
+
+/// Queues the deadline of a test that is run again. A retry keeps the id of
+/// the earlier attempt, so that attempt's deadline is dropped first, or it
+/// would be reported against the retry.
+fn requeue_timeout(timeout_queue: &mut VecDeque<TimeoutEntry>, entry: TimeoutEntry) {
+    timeout_queue.retain(|e| e.id != entry.id);
+    timeout_queue.push_back(entry);
+}
+
 pub fn run_tests<F>(
     opts: &TestOpts,
     tests: Vec<TestDescAndFn>,
@@ -290,12 +340,23 @@ fn join(self, completed_test: &mut CompletedTest) {
     // Use a deterministic hasher
     type TestMap = HashMap<TestId, RunningTest, BuildHasherDefault<DefaultHasher>>;
 
-    struct TimeoutEntry {
-        id: TestId,
-        desc: TestDesc,
-        timeout: Instant,
+    // A test that may be run again if it fails, because of `--retries`.
+    struct RetryEntry {
+        /// A copy of the test to run on the next attempt.
+        test: TestDescAndFn,
+        /// Number of the attempt that is currently running, starting from 1.
+        attempt: usize,
+        /// Output of the previous attempts.
+        output: Vec<u8>,
+        /// Output of the previous attempts on stderr, if it is captured
+        /// separately.
+        stderr: Vec<u8>,
+        /// When the first attempt started.
+        start: Option<Instant>,
     }
 
+    type RetryMap = HashMap<TestId, RetryEntry, BuildHasherDefault<DefaultHasher>>;
+
     let tests_len = tests.len();
 
     let mut filtered = FilteredTests { tests: Vec::new(), benches: Vec::new(), next_id: 0 };
@@ -340,7 +401,8 @@ struct TimeoutEntry {
     let mut pending = 0;
 
     let (tx, rx) = channel::<CompletedTest>();
-    let run_strategy = if opts.options.panic_abort && !opts.force_run_in_process {
+    let needs_subprocess = opts.options.panic_abort || opts.test_timeout.is_some();
+    let run_strategy = if needs_subprocess && !opts.force_run_in_process {
         RunStrategy::SpawnPrimary
     } else {
         RunStrategy::InProcess
@@ -348,24 +410,7 @@ struct TimeoutEntry {
 
     let mut running_tests: TestMap = HashMap::default();
     let mut timeout_queue: VecDeque<TimeoutEntry> = VecDeque::new();
-
-    fn get_timed_out_tests(
-        running_tests: &TestMap,
-        timeout_queue: &mut VecDeque<TimeoutEntry>,
-    ) -> Vec<TestDesc> {
-        let now = Instant::now();
-        let mut timed_out = Vec::new();
-        while let Some(timeout_entry) = timeout_queue.front() {
-            if now < timeout_entry.timeout {
-                break;
-            }
-            let timeout_entry = timeout_queue.pop_front().unwrap();
-            if running_tests.contains_key(&timeout_entry.id) {
-                timed_out.push(timeout_entry.desc);
-            }
-        }
-        timed_out
-    }
+    let mut retries: RetryMap = HashMap::default();
 
     fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
         timeout_queue.front().map(|&TimeoutEntry { timeout: next_timeout, .. }| {
@@ -374,19 +419,100 @@ fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
         })
     }
 
+    fn track_retries(opts: &TestOpts, retries: &mut RetryMap, id: TestId, test: &TestDescAndFn) {
+        if opts.retries == 0 {
+            return;
+        }
+        // Dynamic tests can only be run once, so they are never retried.
+        if let Some(test) = clone_static_test(test) {
+            let entry = RetryEntry {
+                test,
+                attempt: 1,
+                output: Vec::new(),
+                stderr: Vec::new(),
+                start: None,
+            };
+            retries.insert(id, entry);
+        }
+    }
+
+    // Returns the test to run again if `completed_test` failed and has retries
+    // left. Otherwise, `completed_test` is final: if it was retried, the output
+    // of all attempts is gathered into it, and it is marked as flaky if it
+    // eventually passed.
+    fn next_attempt(
+        opts: &TestOpts,
+        retries: &mut RetryMap,
+        completed_test: &mut CompletedTest,
+    ) -> Option<TestDescAndFn> {
+        let entry = retries.get_mut(&completed_test.id)?;
+        let failed = match completed_test.result {
+            TrIgnored | TrOk | TrBench(_) | TrFlaky(_) => false,
+            TrFailed | TrFailedMsg(_) | TrTimedFail | TrTimedOut => true,
+        };
+        if failed && entry.attempt <= opts.retries {
+            let output = &mut entry.output;
+            formatters::write_attempt_delimiter(output, &completed_test.desc.name, entry.attempt);
+            output.extend_from_slice(&completed_test.stdout);
+            if let TrFailedMsg(msg) = &completed_test.result {
+                output.extend_from_slice(format!("note: {msg}").as_bytes());
+            }
+            if let Some(stderr) = &completed_test.stderr {
+                let name = &completed_test.desc.name;
+                formatters::write_attempt_delimiter(&mut entry.stderr, name, entry.attempt);
+                entry.stderr.extend_from_slice(stderr);
+            }
+            if entry.attempt == 1 {
+                entry.start = completed_test.timestamps.map(|timestamps| timestamps.start);
+            }
+            entry.attempt += 1;
+            return clone_static_test(&entry.test);
+        }
+
+        let RetryEntry { attempt, mut output, stderr: mut stderr_output, start, .. } =
+            retries.remove(&completed_test.id).unwrap();
+        if attempt > 1 {
+            let name = &completed_test.desc.name;
+            formatters::write_attempt_delimiter(&mut output, name, attempt);
+            output.append(&mut completed_test.stdout);
+            completed_test.stdout = output;
+            if let Some(stderr) = &mut completed_test.stderr {
+                formatters::write_attempt_delimiter(&mut stderr_output, name, attempt);
+                stderr_output.append(stderr);
+                *stderr = stderr_output;
+            }
+            if let (Some(timestamps), Some(start)) = (&mut completed_test.timestamps, start) {
+                timestamps.start = start;
+            }
+            if let TrOk = completed_test.result {
+                completed_test.result = TrFlaky(attempt);
+            }
+        }
+        None
+    }
+
     if concurrency == 1 {
         while !remaining.is_empty() {
-            let (id, test) = remaining.pop_front().unwrap();
+            let (id, mut test) = remaining.pop_front().unwrap();
             let event = TestEvent::TeWait(test.desc.clone());
             notify_about_test_event(event)?;
-            let join_handle = run_test(opts, !opts.run_tests, id, test, run_strategy, tx.clone());
-            // Wait for the test to complete.
-            let mut completed_test = rx.recv().unwrap();
-            RunningTest { join_handle }.join(&mut completed_test);
+            track_retries(opts, &mut retries, id, &test);
+            let completed_test = loop {
+                let join_handle =
+                    run_test(opts, !opts.run_tests, id, test, run_strategy, tx.clone());
+                // Wait for the test to complete.
+                let mut completed_test = rx.recv().unwrap();
+                RunningTest { join_handle }.join(&mut completed_test);
+
+                match next_attempt(opts, &mut retries, &mut completed_test) {
+                    Some(next_test) => test = next_test,
+                    None => break completed_test,
+                }
+            };
 
             let fail_fast = match completed_test.result {
-                TrIgnored | TrOk | TrBench(_) => false,
-                TrFailed | TrFailedMsg(_) | TrTimedFail => opts.fail_fast,
+                TrIgnored | TrOk | TrBench(_) | TrFlaky(_) => false,
+                TrFailed | TrFailedMsg(_) | TrTimedFail | TrTimedOut => opts.fail_fast,
             };
 
             let event = TestEvent::TeResult(completed_test);
@@ -405,6 +531,7 @@ fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
 
                 let event = TestEvent::TeWait(desc.clone());
                 notify_about_test_event(event)?; //here no pad
+                track_retries(opts, &mut retries, id, &test);
                 let join_handle =
                     run_test(opts, !opts.run_tests, id, test, run_strategy, tx.clone());
                 running_tests.insert(id, RunningTest { join_handle });
@@ -416,7 +543,8 @@ fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
             loop {
                 if let Some(timeout) = calc_timeout(&timeout_queue) {
                     res = rx.recv_timeout(timeout);
-                    for test in get_timed_out_tests(&running_tests, &mut timeout_queue) {
+                    let is_running = |id| running_tests.contains_key(&id);
+                    for test in get_timed_out_tests(is_running, &mut timeout_queue) {
                         let event = TestEvent::TeTimeout(test);
                         notify_about_test_event(event)?;
                     }
@@ -440,9 +568,22 @@ fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
             let running_test = running_tests.remove(&completed_test.id).unwrap();
             running_test.join(&mut completed_test);
 
+            if let Some(test) = next_attempt(opts, &mut retries, &mut completed_test) {
+                // Run the test again in place of the failed attempt, so it keeps
+                // counting as pending.
+                let id = completed_test.id;
+                let timeout = time::get_default_test_timeout();
+                let desc = test.desc.clone();
+                let join_handle =
+                    run_test(opts, !opts.run_tests, id, test, run_strategy, tx.clone());
+                running_tests.insert(id, RunningTest { join_handle });
+                requeue_timeout(&mut timeout_queue, TimeoutEntry { id, desc, timeout });
+                continue;
+            }
+
             let fail_fast = match completed_test.result {
-                TrIgnored | TrOk | TrBench(_) => false,
-                TrFailed | TrFailedMsg(_) | TrTimedFail => opts.fail_fast,
+                TrIgnored | TrOk | TrBench(_) | TrFlaky(_) => false,
+                TrFailed | TrFailedMsg(_) | TrTimedFail | TrTimedOut => opts.fail_fast,
             };
 
             let event = TestEvent::TeResult(completed_test);
@@ -474,6 +615,17 @@ fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
     Ok(())
 }
 
+/// Makes a copy of a static test, so that it can be run again. Returns `None`
+/// for dynamic tests, which can't be cloned.
+fn clone_static_test(test: &TestDescAndFn) -> Option<TestDescAndFn> {
+    let testfn = match test.testfn {
+        StaticTestFn(f) => StaticTestFn(f),
+        StaticBenchAsTestFn(f) => StaticBenchAsTestFn(f),
+        _ => return None,
+    };
+    Some(TestDescAndFn { desc: test.desc.clone(), testfn })
+}
+
 pub fn filter_tests(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> Vec<TestDescAndFn> {
     let mut filtered = tests;
     let matches_filter = |test: &TestDescAndFn, filter: &str| {
@@ -500,6 +652,11 @@ pub fn filter_tests(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> Vec<TestDescA
         filtered.retain(|test| test.desc.should_panic == ShouldPanic::No);
     }
 
+    // Only keep the tests assigned to this shard
+    if let Some(shard) = opts.shard {
+        filtered.retain(|test| is_in_shard(shard, &test.desc.name));
+    }
+
     // maybe unignore tests
     match opts.run_ignored {
         RunIgnored::Yes => {
@@ -553,6 +710,17 @@ pub fn run_test(
 
     match testfn.into_runnable() {
         Runnable::Test(runnable_test) => {
+            // Dynamic tests can't be looked up again by a child process. Unless we
+            // have to isolate tests because of panic=abort, run them in-process and
+            // give up on enforcing `--test-timeout` for them.
+            let strategy = match strategy {
+                RunStrategy::SpawnPrimary
+                    if runnable_test.is_dynamic() && !opts.options.panic_abort =>
+                {
+                    RunStrategy::InProcess
+                }
+                strategy => strategy,
+            };
             if runnable_test.is_dynamic() {
                 match strategy {
                     RunStrategy::InProcess => (),
@@ -562,7 +730,10 @@ pub fn run_test(
 
             let name = desc.name.clone();
             let nocapture = opts.nocapture;
+            // Only the json output reports stderr on its own as well.
+            let separate_stderr = opts.format == OutputFormat::Json;
             let time_options = opts.time_options;
+            let test_timeout = opts.test_timeout;
             let bench_benchmarks = opts.bench_benchmarks;
 
             let runtest = move || match strategy {
@@ -570,6 +741,7 @@ pub fn run_test(
                     id,
                     desc,
                     nocapture,
+                    separate_stderr,
                     time_options.is_some(),
                     runnable_test,
                     monitor_ch,
@@ -579,9 +751,11 @@ pub fn run_test(
                     id,
                     desc,
                     nocapture,
+                    separate_stderr,
                     time_options.is_some(),
                     monitor_ch,
                     time_options,
+                    test_timeout,
                     bench_benchmarks,
                 ),
             };
@@ -632,6 +806,7 @@ fn run_test_in_process(
     id: TestId,
     desc: TestDesc,
     nocapture: bool,
+    separate_stderr: bool,
     report_time: bool,
     runnable_test: RunnableTest,
     monitor_ch: Sender<CompletedTest>,
@@ -639,26 +814,35 @@ fn run_test_in_process(
 ) {
     // Buffer for capturing standard I/O
     let data = Arc::new(Mutex::new(Vec::new()));
+    // Buffer for a copy of stderr, if it is reported on its own
+    let error_data = separate_stderr.then(|| Arc::new(Mutex::new(Vec::new())));
 
     if !nocapture {
         io::set_output_capture(Some(data.clone()));
+        io::set_error_capture(error_data.clone());
     }
 
+    let started = is_instant_supported().then(Instant::now);
     let start = report_time.then(Instant::now);
     let result = fold_err(catch_unwind(AssertUnwindSafe(|| runnable_test.run())));
     let exec_time = start.map(|start| {
         let duration = start.elapsed();
         TestExecTime(duration)
     });
+    let timestamps = started.map(|start| TestTimestamps { start, end: Instant::now() });
 
     io::set_output_capture(None);
+    io::set_error_capture(None);
 
     let test_result = match result {
         Ok(()) => calc_result(&desc, Ok(()), &time_opts, &exec_time),
         Err(e) => calc_result(&desc, Err(e.as_ref()), &time_opts, &exec_time),
     };
     let stdout = data.lock().unwrap_or_else(|e| e.into_inner()).to_vec();
-    let message = CompletedTest::new(id, desc, test_result, exec_time, stdout);
+    let mut message = CompletedTest::new(id, desc, test_result, exec_time, stdout);
+    message.stderr = error_data.map(|data| data.lock().unwrap_or_else(|e| e.into_inner()).to_vec());
+    message.timestamps = timestamps;
+    message.runner = Some(TestRunner::Thread(thread::current().id().as_u64().get()));
     monitor_ch.send(message).unwrap();
 }
 
@@ -679,11 +863,16 @@ fn spawn_test_subprocess(
     id: TestId,
     desc: TestDesc,
     nocapture: bool,
+    separate_stderr: bool,
     report_time: bool,
     monitor_ch: Sender<CompletedTest>,
     time_opts: Option<time::TestTimeOptions>,
+    test_timeout: Option<Duration>,
     bench_benchmarks: bool,
 ) {
+    let mut stderr_output = None;
+    let mut timestamps = None;
+    let mut runner = None;
     let (result, test_output, exec_time) = (|| {
         let args = env::args().collect::<Vec<_>>();
         let current_exe = &args[0];
@@ -698,8 +887,9 @@ fn spawn_test_subprocess(
             command.stderr(process::Stdio::inherit());
         }
 
+        let started = is_instant_supported().then(Instant::now);
         let start = report_time.then(Instant::now);
-        let output = match command.output() {
+        let (output, pid, timed_out) = match child_output(&mut command, nocapture, test_timeout) {
             Ok(out) => out,
             Err(e) => {
                 let err = format!("Failed to spawn {} as child for test: {:?}", args[0], e);
@@ -710,21 +900,101 @@ fn spawn_test_subprocess(
             let duration = start.elapsed();
             TestExecTime(duration)
         });
+        timestamps = started.map(|start| TestTimestamps { start, end: Instant::now() });
+        runner = Some(TestRunner::Process(pid));
 
         let std::process::Output { stdout, stderr, status } = output;
         let mut test_output = stdout;
         formatters::write_stderr_delimiter(&mut test_output, &desc.name);
         test_output.extend_from_slice(&stderr);
+        if separate_stderr {
+            stderr_output = Some(stderr);
+        }
 
-        let result = get_result_from_exit_code(&desc, status, &time_opts, &exec_time);
+        let result = if timed_out {
+            TrTimedOut
+        } else {
+            get_result_from_exit_code(&desc, status, &time_opts, &exec_time)
+        };
         (result, test_output, exec_time)
     })();
 
-    let message = CompletedTest::new(id, desc, result, exec_time, test_output);
+    let mut message = CompletedTest::new(id, desc, result, exec_time, test_output);
+    message.stderr = stderr_output;
+    message.timestamps = timestamps;
+    message.runner = runner;
     monitor_ch.send(message).unwrap();
 }
 
-fn run_test_in_spawned_subprocess(desc: TestDesc, runnable_test: RunnableTest) -> ! {
+/// Like `Command::output`, but also returns the id of the child process, and
+/// kills the child if it is still running once `timeout` has elapsed. Also
+/// returns whether the child had to be killed.
+fn child_output(
+    command: &mut Command,
+    nocapture: bool,
+    timeout: Option<Duration>,
+) -> io::Result<(process::Output, u32, bool)> {
+    // How long to keep collecting output from the pipes after a kill.
+    const KILL_DRAIN_TIMEOUT: Duration = Duration::from_millis(500);
+
+    command.stdin(process::Stdio::null());
+    if !nocapture {
+        command.stdout(process::Stdio::piped());
+        command.stderr(process::Stdio::piped());
+    }
+    let mut child = command.spawn()?;
+    let pid = child.id();
+    let Some(timeout) = timeout else {
+        return child.wait_with_output().map(|output| (output, pid, false));
+    };
+
+    // Drain both pipes while waiting, so that a chatty test can't block on a
+    // full pipe and be mistaken for a hung one.
+    let (stdout, stderr) = (child.stdout.take(), child.stderr.take());
+    let (tx, rx) = channel();
+    thread::spawn(move || {
+        let _ = tx.send(process::read_output(stdout, stderr));
+    });
+
+    let (status, timed_out) = match child.wait_timeout(timeout)? {
+        Some(status) => (status, false),
+        None => {
+            // The child may exit on its own right before we kill it, in which
+            // case `kill` fails. Either way, `wait` reaps it.
+            let _ = child.kill();
+            (child.wait()?, true)
+        }
+    };
+
+    // Only the child itself is killed. Any process it spawned may have
+    // inherited the pipes and keep them open indefinitely, so after a kill
+    // only wait a little for the output and then leave the reader thread
+    // behind instead of hanging the harness.
+    let output = if timed_out { rx.recv_timeout(KILL_DRAIN_TIMEOUT).ok() } else { rx.recv().ok() };
+    let (stdout, stderr) = output.transpose()?.unwrap_or_default();
+
+    Ok((process::Output { status, stdout, stderr }, pid, timed_out))
+}
+
+fn run_test_in_spawned_subprocess(
+    desc: TestDesc,
+    runnable_test: RunnableTest,
+    panic_abort: bool,
+) -> ! {
+    if !panic_abort {
+        // With panic=unwind, the test may catch its own panics, so we can only
+        // tell whether it failed once it returns.
+        let result = fold_err(catch_unwind(AssertUnwindSafe(|| runnable_test.run())));
+        let test_result = match result {
+            Ok(()) => calc_result(&desc, Ok(()), &None, &None),
+            Err(e) => calc_result(&desc, Err(e.as_ref()), &None, &None),
+        };
+        if let TrFailedMsg(msg) = &test_result {
+            eprintln!("{msg}");
+        }
+        exit_spawned_subprocess(&test_result);
+    }
+
     let builtin_panic_hook = panic::take_hook();
     let record_result = Arc::new(move |panic_info: Option<&'_ PanicInfo<'_>>| {
         let test_result = match panic_info {
@@ -742,11 +1012,7 @@ fn run_test_in_spawned_subprocess(desc: TestDesc, runnable_test: RunnableTest) -
             builtin_panic_hook(info);
         }
 
-        if let TrOk = test_result {
-            process::exit(test_result::TR_OK);
-        } else {
-            process::abort();
-        }
+        exit_spawned_subprocess(&test_result);
     });
     let record_result2 = record_result.clone();
     panic::set_hook(Box::new(move |info| record_result2(Some(info))));
@@ -756,3 +1022,13 @@ fn run_test_in_spawned_subprocess(desc: TestDesc, runnable_test: RunnableTest) -
     record_result(None);
     unreachable!("panic=abort callback should have exited the process")
 }
+
+/// Reports the result of a test run in a spawned subprocess to the primary
+/// process through the exit status.
+fn exit_spawned_subprocess(test_result: &TestResult) -> ! {
+    if let TrOk = test_result {
+        process::exit(test_result::TR_OK);
+    } else {
+        process::abort();
+    }
+}
@@ -36,6 +36,18 @@ pub enum OutputFormat {
     Json,
     /// JUnit output
     Junit,
+    /// TAP (Test Anything Protocol) output
+    Tap,
+}
+
+/// The part of the test suite to run when it is split across several
+/// invocations, e.g. on different CI machines.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TestShard {
+    /// 1-based index of this shard, between 1 and `count` inclusive.
+    pub index: usize,
+    /// Total number of shards the tests are split into.
+    pub count: usize,
 }
 
 /// Whether ignored test should be run or not
@@ -300,3 +300,63 @@ pub fn winsorize(samples: &mut [f64], pct: f64) {
         }
     }
 }
+
+/// Estimated relative change between the medians of two sample sets, with a
+/// confidence interval. A value of `0.05` means the median got 5% larger.
+#[derive(Debug, Clone, PartialEq, Copy)]
+pub struct MedianChange {
+    /// Relative change between the medians of the sample sets themselves.
+    pub estimate: f64,
+    /// Lower bound of the confidence interval.
+    pub lower: f64,
+    /// Upper bound of the confidence interval.
+    pub upper: f64,
+}
+
+/// Estimate the relative change of the median from `base` to `new`, with a
+/// `confidence` (e.g. `0.95`) interval computed by bootstrap resampling: both
+/// sample sets are resampled with replacement `resamples` times, and the
+/// interval is given by the percentiles of the resulting changes. The
+/// resampling is seeded with `seed`, so that the result is reproducible.
+///
+/// See: <https://en.wikipedia.org/wiki/Bootstrapping_(statistics)>
+pub fn bootstrap_median_change(
+    base: &[f64],
+    new: &[f64],
+    confidence: f64,
+    resamples: usize,
+    seed: u64,
+) -> MedianChange {
+    assert!(!base.is_empty() && !new.is_empty() && resamples > 0);
+
+    // SplitMix64, which is plenty for picking sample indices.
+    let mut state = seed;
+    let mut next_index = |len: usize| {
+        state = state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        ((z ^ (z >> 31)) % len as u64) as usize
+    };
+    let mut resample = |samples: &[f64], out: &mut Vec<f64>| {
+        out.clear();
+        out.extend((0..samples.len()).map(|_| samples[next_index(samples.len())]));
+    };
+
+    let mut changes = Vec::with_capacity(resamples);
+    let mut base_resample = Vec::with_capacity(base.len());
+    let mut new_resample = Vec::with_capacity(new.len());
+    for _ in 0..resamples {
+        resample(base, &mut base_resample);
+        resample(new, &mut new_resample);
+        changes.push(new_resample.median() / base_resample.median() - 1.0);
+    }
+    local_sort(&mut changes);
+
+    let tail = (1.0 - confidence) / 2.0 * 100.0;
+    MedianChange {
+        estimate: new.median() / base.median() - 1.0,
+        lower: percentile_of_sorted(&changes, tail),
+        upper: percentile_of_sorted(&changes, 100.0 - tail),
+    }
+}
@@ -571,6 +571,24 @@ fn test_sum_f64_between_ints_that_sum_to_0() {
     assert_eq!([1e30f64, 1.2f64, -1e30f64].sum(), 1.2);
 }
 
+#[test]
+fn test_bootstrap_median_change() {
+    let base: Vec<f64> = (0..50).map(|i| 1000.0 + (i % 7) as f64).collect();
+    let slower: Vec<f64> = base.iter().map(|x| x * 1.5).collect();
+
+    let same = bootstrap_median_change(&base, &base, 0.95, 1000, 0);
+    assert_eq!(same.estimate, 0.0);
+    assert!(same.lower <= 0.0 && 0.0 <= same.upper);
+
+    let change = bootstrap_median_change(&base, &slower, 0.95, 1000, 0);
+    assert_approx_eq!(change.estimate, 0.5);
+    assert!(0.0 < change.lower && change.lower <= change.estimate);
+    assert!(change.estimate <= change.upper);
+
+    // The resampling is deterministic for a given seed.
+    assert_eq!(change, bootstrap_median_change(&base, &slower, 0.95, 1000, 0));
+}
+
 #[bench]
 pub fn sum_three_items(b: &mut Bencher) {
     b.iter(|| {
@@ -29,6 +29,11 @@ pub enum TestResult {
     TrIgnored,
     TrBench(BenchSamples),
     TrTimedFail,
+    /// The test was still running when `--test-timeout` expired, and was killed.
+    TrTimedOut,
+    /// The test failed, but passed when it was retried because of `--retries`.
+    /// Holds the number of the attempt that passed.
+    TrFlaky(usize),
 }
 
 /// Creates a `TestResult` depending on the raw result of test execution
@@ -1,8 +1,12 @@
 use super::*;
 
+use std::path::PathBuf;
+
 use crate::{
     console::OutputLocation,
-    formatters::PrettyFormatter,
+    event::{CompletedTest, TestRunner},
+    formatters::{JsonFormatter, OutputFormatter, PrettyFormatter, TapFormatter},
+    helpers::failed_state::{retain_failed_tests, write_failed_tests},
     test::{
         parse_opts,
         MetricMap,
@@ -30,9 +34,16 @@ fn new() -> TestOpts {
             format: OutputFormat::Pretty,
             shuffle: false,
             shuffle_seed: None,
+            shard: None,
             test_threads: None,
             skip: vec![],
             time_options: None,
+            test_timeout: None,
+            retries: 0,
+            failed_state: None,
+            rerun_failed: false,
+            save_baseline: None,
+            baseline: None,
             options: Options::new(),
             fail_fast: false,
         }
@@ -478,6 +489,43 @@ fn parse_include_ignored_flag() {
     assert_eq!(opts.run_ignored, RunIgnored::Yes);
 }
 
+#[test]
+fn parse_test_timeout_option() {
+    let parse = |timeout: &str| {
+        let args = vec![
+            "progname".to_string(),
+            "-Zunstable-options".to_string(),
+            "--test-timeout".to_string(),
+            timeout.to_string(),
+        ];
+        parse_opts(&args).unwrap().map(|opts| opts.test_timeout)
+    };
+
+    assert_eq!(parse("30"), Ok(Some(Duration::from_secs(30))));
+    assert_eq!(parse("0.5"), Ok(Some(Duration::from_millis(500))));
+    assert!(parse("0").is_err());
+    assert!(parse("-1").is_err());
+    assert!(parse("soon").is_err());
+}
+
+#[test]
+fn parse_retries_option() {
+    let parse = |retries: &str| {
+        let args = vec![
+            "progname".to_string(),
+            "-Zunstable-options".to_string(),
+            "--retries".to_string(),
+            retries.to_string(),
+        ];
+        parse_opts(&args).unwrap().map(|opts| opts.retries)
+    };
+
+    assert_eq!(parse("0"), Ok(0));
+    assert_eq!(parse("3"), Ok(3));
+    assert!(parse("-1").is_err());
+    assert!(parse("many").is_err());
+}
+
 #[test]
 pub fn filter_for_ignored_option() {
     // When we run ignored tests the test filter should filter out all the
@@ -731,6 +779,54 @@ pub fn order_depends_on_more_than_seed() {
     assert!(left.iter().zip(right).any(|(a, b)| a.0 != b.0));
 }
 
+#[test]
+pub fn shards_partition_tests() {
+    let count = 3;
+    let mut seen = Vec::new();
+    for index in 1..=count {
+        let shard = Some(TestShard { index, count });
+        let opts = TestOpts { shard, ..TestOpts::new() };
+        let filtered = filter_tests(&opts, sample_tests());
+        // The assignment must not depend on the order of the tests.
+        let mut reversed_tests = sample_tests();
+        reversed_tests.reverse();
+        let mut reversed = filter_tests(&opts, reversed_tests);
+        reversed.reverse();
+        assert!(filtered.iter().zip(&reversed).all(|(a, b)| a.desc.name == b.desc.name));
+        seen.extend(filtered.into_iter().map(|test| test.desc.name.to_string()));
+    }
+
+    let mut expected: Vec<_> =
+        sample_tests().into_iter().map(|t| t.desc.name.to_string()).collect();
+    expected.sort();
+    seen.sort();
+    assert_eq!(seen, expected);
+}
+
+#[test]
+fn parse_shard_option() {
+    let parse = |shard: &str| {
+        let args = vec![
+            "progname".to_string(),
+            "-Zunstable-options".to_string(),
+            "--shard".to_string(),
+            shard.to_string(),
+        ];
+        parse_opts(&args).unwrap().map(|opts| opts.shard)
+    };
+
+    assert_eq!(parse("2/3"), Ok(Some(TestShard { index: 2, count: 3 })));
+    assert_eq!(parse("3/3"), Ok(Some(TestShard { index: 3, count: 3 })));
+    assert!(parse("0/3").is_err());
+    assert!(parse("4/3").is_err());
+    assert!(parse("1/0").is_err());
+    assert!(parse("1").is_err());
+    assert!(parse("a/b").is_err());
+
+    let args = vec!["progname".to_string(), "--shard".to_string(), "1/2".to_string()];
+    assert!(parse_opts(&args).unwrap().is_err());
+}
+
 #[test]
 pub fn test_metricmap_compare() {
     let mut m1 = MetricMap::new();
@@ -866,6 +962,7 @@ fn should_sort_failures_before_printing_them() {
         passed: 0,
         failed: 0,
         ignored: 0,
+        flaky: 0,
         filtered_out: 0,
         measured: 0,
         exec_time: None,
@@ -875,6 +972,9 @@ fn should_sort_failures_before_printing_them() {
         not_failures: Vec::new(),
         ignores: Vec::new(),
         time_failures: Vec::new(),
+        flaky_tests: Vec::new(),
+        bench_results: Vec::new(),
+        baseline: None,
     };
 
     out.write_failures(&st).unwrap();
@@ -888,6 +988,104 @@ fn should_sort_failures_before_printing_them() {
     assert!(apos < bpos);
 }
 
+#[test]
+fn tap_formatter_writes_plan_directives_and_diagnostics() {
+    fn desc(name: &'static str, ignore_message: Option<&'static str>) -> TestDesc {
+        TestDesc {
+            name: StaticTestName(name),
+            ignore: ignore_message.is_some(),
+            ignore_message,
+            source_file: "",
+            start_line: 0,
+            start_col: 0,
+            end_line: 0,
+            end_col: 0,
+            should_panic: ShouldPanic::No,
+            compile_fail: false,
+            no_run: false,
+            test_type: TestType::Unknown,
+        }
+    }
+
+    let mut out = TapFormatter::new(OutputLocation::Raw(Vec::new()));
+    let mut st = console::ConsoleTestState::new(&TestOpts::new()).unwrap();
+
+    out.write_run_start(4, None).unwrap();
+    out.write_result(&desc("a", None), &TrOk, None, b"hidden", &st).unwrap();
+    out.write_result(&desc("b#1", None), &TrFailed, None, b"line 1\nline 2\n", &st).unwrap();
+    let msg = TrFailedMsg("\"quoted\"".to_string());
+    out.write_result(&desc("c", None), &msg, None, &[], &st).unwrap();
+    out.write_result(&desc("d", Some("not today")), &TrIgnored, None, &[], &st).unwrap();
+    st.passed = 1;
+    st.failed = 2;
+    st.ignored = 1;
+    assert!(!out.write_run_finish(&st).unwrap());
+
+    let s = match out.output_location() {
+        &OutputLocation::Raw(ref m) => String::from_utf8_lossy(&m[..]).into_owned(),
+        &OutputLocation::Pretty(_) => unreachable!(),
+    };
+    assert_eq!(
+        s,
+        "TAP version 14
+1..4
+ok 1 - a
+not ok 2 - b\\#1
+  ---
+  severity: fail
+  stdout: |
+    line 1
+    line 2
+  ...
+not ok 3 - c
+  ---
+  message: \"\\\"quoted\\\"\"
+  severity: fail
+  ...
+ok 4 - d # SKIP not today
+# test result: FAILED. 1 passed; 2 failed; 1 ignored; 0 measured; 0 filtered out
+"
+    );
+}
+
+#[test]
+fn json_formatter_reports_location_runner_and_stderr() {
+    let desc = TestDesc {
+        name: StaticTestName("a"),
+        ignore: false,
+        ignore_message: None,
+        source_file: "src/a.rs",
+        start_line: 12,
+        start_col: 4,
+        end_line: 12,
+        end_col: 5,
+        should_panic: ShouldPanic::No,
+        compile_fail: false,
+        no_run: false,
+        test_type: TestType::Unknown,
+    };
+
+    let mut out = JsonFormatter::new(OutputLocation::Raw(Vec::new()));
+    let st = console::ConsoleTestState::new(&TestOpts::new()).unwrap();
+
+    out.write_test_start(&desc).unwrap();
+    let mut test = CompletedTest::new(TestId(0), desc, TrFailed, None, b"out\nerr\n".to_vec());
+    test.stderr = Some(b"err\n".to_vec());
+    test.runner = Some(TestRunner::Process(42));
+    out.write_completed_test(&test, &st).unwrap();
+
+    let s = match out.output_location() {
+        &OutputLocation::Raw(ref m) => String::from_utf8_lossy(&m[..]).into_owned(),
+        &OutputLocation::Pretty(_) => unreachable!(),
+    };
+    assert_eq!(
+        s,
+        r#"{ "type": "test", "event": "started", "name": "a", "source_path": "src/a.rs", "start_line": 12, "start_col": 4 }
+{ "type": "test", "name": "a", "event": "failed", "stdout": "out\nerr\n", "source_path": "src/a.rs", "start_line": 12, "start_col": 4, "stderr": "err\n", "process_id": 42 }
+"#
+    );
+}
+
 #[test]
 #[cfg(not(target_os = "emscripten"))]
 fn test_dyn_bench_returning_err_fails_when_run_as_test() {
@@ -922,3 +1120,186 @@ fn f(_: &mut Bencher) -> Result<(), String> {
     let result = rx.recv().unwrap().result;
     assert_eq!(result, TrFailed);
 }
+
+#[test]
+fn retries_report_flaky_and_failing_tests() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static FLAKY_RUNS: AtomicUsize = AtomicUsize::new(0);
+    static FAILING_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+    fn flaky() -> Result<(), String> {
+        match FLAKY_RUNS.fetch_add(1, Ordering::SeqCst) {
+            0 => Err("first attempt fails".into()),
+            _ => Ok(()),
+        }
+    }
+    fn failing() -> Result<(), String> {
+        FAILING_RUNS.fetch_add(1, Ordering::SeqCst);
+        Err("always fails".into())
+    }
+
+    let test = |name, testfn| TestDescAndFn {
+        desc: TestDesc {
+            name: StaticTestName(name),
+            ignore: false,
+            ignore_message: None,
+            source_file: "",
+            start_line: 0,
+            start_col: 0,
+            end_line: 0,
+            end_col: 0,
+            should_panic: ShouldPanic::No,
+            compile_fail: false,
+            no_run: false,
+            test_type: TestType::Unknown,
+        },
+        testfn: StaticTestFn(testfn),
+    };
+
+    let (tx, rx) = channel();
+    let notify = move |event: TestEvent| {
+        if let TestEvent::TeResult(result) = event {
+            tx.send(result).unwrap();
+        }
+        Ok(())
+    };
+    let opts = TestOpts { run_tests: true, retries: 2, test_threads: Some(2), ..TestOpts::new() };
+    run_tests(&opts, vec![test("flaky", flaky), test("failing", failing)], notify).unwrap();
+
+    let mut results: Vec<_> = rx.iter().collect();
+    results.sort_by(|a, b| a.desc.name.as_slice().cmp(b.desc.name.as_slice()));
+    assert_eq!(results.len(), 2);
+
+    assert_eq!(results[0].result, TrFailed);
+    assert_eq!(FAILING_RUNS.load(Ordering::SeqCst), 3);
+    let output = String::from_utf8_lossy(&results[0].stdout);
+    assert!(output.contains("---- failing attempt 1 ----"));
+    assert!(output.contains("---- failing attempt 3 ----"));
+
+    assert_eq!(results[1].result, TrFlaky(2));
+    assert_eq!(FLAKY_RUNS.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn retry_does_not_inherit_timeout_of_earlier_attempt() {
+    let desc = typed_test_desc(TestType::UnitTest);
+    let (retried, other) = (TestId(0), TestId(1));
+    let mut timeout_queue = VecDeque::new();
+    let now = Instant::now();
+    timeout_queue.push_back(TimeoutEntry { id: retried, desc: desc.clone(), timeout: now });
+    timeout_queue.push_back(TimeoutEntry { id: other, desc: desc.clone(), timeout: now });
+
+    // The first attempt failed before its deadline and the test is retried
+    // under the same id, with a fresh deadline.
+    let timeout = now + Duration::from_secs(60);
+    requeue_timeout(&mut timeout_queue, TimeoutEntry { id: retried, desc, timeout });
+
+    let timed_out = get_timed_out_tests(|_| true, &mut timeout_queue);
+    assert_eq!(timed_out.len(), 1);
+    assert_eq!(timeout_queue.len(), 1);
+    assert_eq!(timeout_queue[0].id, retried);
+}
+
+#[test]
+fn rerun_failed_keeps_only_failed_tests() {
+    let path = env::temp_dir().join(format!("libtest-failed-state-{}", process::id()));
+    let names = |tests: &[TestDescAndFn]| -> Vec<String> {
+        tests.iter().map(|test| test.desc.name.as_slice().to_string()).collect()
+    };
+
+    // Nothing has been recorded yet, so every test runs.
+    let mut tests = sample_tests();
+    retain_failed_tests(&path, &mut tests).unwrap();
+    assert_eq!(names(&tests), names(&sample_tests()));
+
+    write_failed_tests(&path, ["isize::test_pow", "isize::gone"].into_iter()).unwrap();
+    let mut tests = sample_tests();
+    retain_failed_tests(&path, &mut tests).unwrap();
+    assert_eq!(names(&tests), ["isize::test_pow"]);
+
+    write_failed_tests(&path, [].into_iter()).unwrap();
+    let mut tests = sample_tests();
+    retain_failed_tests(&path, &mut tests).unwrap();
+    assert_eq!(tests.len(), sample_tests().len());
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn parse_rerun_failed_option() {
+    let parse = |args: &[&str]| {
+        let mut args: Vec<_> = args.iter().map(|arg| arg.to_string()).collect();
+        args.insert(0, "progname".to_string());
+        args.insert(1, "-Zunstable-options".to_string());
+        parse_opts(&args).unwrap().map(|opts| (opts.rerun_failed, opts.failed_state))
+    };
+
+    // The state file is only read when the tests are run, so it need not exist yet.
+    let path = "/nonexistent/libtest-failed-state";
+    assert_eq!(
+        parse(&["--rerun-failed", "--failed-state", path]),
+        Ok((true, Some(PathBuf::from(path))))
+    );
+    assert_eq!(parse(&["--failed-state", path]), Ok((false, Some(PathBuf::from(path)))));
+    assert_eq!(parse(&[]), Ok((false, None)));
+}
+
+#[test]
+fn parse_baseline_options() {
+    let parse = |option: &str, name: &str| {
+        let args = vec![
+            "progname".to_string(),
+            "-Zunstable-options".to_string(),
+            option.to_string(),
+            name.to_string(),
+        ];
+        parse_opts(&args).unwrap().map(|opts| (opts.save_baseline, opts.baseline))
+    };
+
+    assert_eq!(parse("--save-baseline", "main"), Ok((Some("main".to_string()), None)));
+    assert_eq!(parse("--baseline", "v1.2_x-y"), Ok((None, Some("v1.2_x-y".to_string()))));
+    assert!(parse("--baseline", "").is_err());
+    assert!(parse("--baseline", "../main").is_err());
+    assert!(parse("--save-baseline", ".hidden").is_err());
+}
+
+#[test]
+fn baseline_round_trip_and_compare() {
+    use crate::baseline::{decode, encode, Baseline, Verdict};
+    use crate::bench::BenchSamples;
+    use crate::stats::Summary;
+    use std::collections::BTreeMap;
+
+    let bench = |scale: f64| {
+        let samples: Vec<f64> = (0..50).map(|i| (1000.0 + (i % 7) as f64) * scale).collect();
+        BenchSamples { ns_iter_summ: Summary::new(&samples), ns_iter_samples: samples, mb_s: 3 }
+    };
+    let mut benches = BTreeMap::new();
+    benches.insert("bench \"quoted\"\n".to_string(), bench(1.0));
+    let mut no_iter = bench(1.0);
+    no_iter.ns_iter_summ.median_abs_dev_pct = f64::NAN;
+    no_iter.ns_iter_samples.clear();
+    benches.insert("no_iter".to_string(), no_iter);
+
+    let decoded = decode(&encode(&benches)).unwrap();
+    assert_eq!(decoded.len(), 2);
+    assert_eq!(decoded["bench \"quoted\"\n"], benches["bench \"quoted\"\n"]);
+    assert!(decoded["no_iter"].ns_iter_summ.median_abs_dev_pct.is_nan());
+    assert!(decode("{ \"benchmarks\": {} }").is_err());
+    assert!(decode("libtest baseline v1\nname\t0\t1 2 3\t1 2 3\n").is_err());
+    assert!(decode("libtest baseline v1\nname\\x\t0\t\t\n").is_err());
+
+    let baseline = Baseline { name: "main".to_string(), benches: decoded };
+    let name = "bench \"quoted\"\n";
+    let verdict = |scale| baseline.compare(name, &bench(scale)).unwrap().verdict;
+    assert_eq!(verdict(1.0), Verdict::NoChange);
+    assert_eq!(verdict(1.5), Verdict::Regressed);
+    assert_eq!(verdict(0.5), Verdict::Improved);
+    assert_eq!(baseline.compare("no_iter", &bench(1.0)), None);
+    assert_eq!(baseline.compare("missing", &bench(1.0)), None);
+
+    // A baseline which has not been saved yet has nothing to compare with.
+    let missing = Baseline::load(&format!("libtest-missing-{}", process::id())).unwrap();
+    assert!(missing.benches.is_empty());
+}
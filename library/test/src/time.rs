@@ -81,6 +81,19 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     }
 }
 
+/// When a test started and finished running.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TestTimestamps {
+    pub start: Instant,
+    pub end: Instant,
+}
+
+/// Returns whether `Instant` can be used on the target.
+pub fn is_instant_supported() -> bool {
+    // It's currently not supported for wasm targets.
+    !((cfg!(target_family = "wasm") && !cfg!(target_os = "wasi")) || cfg!(target_os = "zkvm"))
+}
+
 /// Structure denoting time limits for test execution.
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub struct TimeThreshold {
@@ -0,0 +1,24 @@
+// Check libtest's TAP output against a test crate with passing, failing,
+// should_panic and ignored tests.
+
+//@ ignore-cross-compile
+//@ needs-unwind contains should_panic test
+
+use run_make_support::{bin_name, diff, rustc, tmp_dir};
+use std::process::Command;
+
+fn main() {
+    rustc().arg("--test").input("f.rs").run();
+
+    let output = Command::new(tmp_dir().join(bin_name("f")))
+        .args(["-Zunstable-options", "--test-threads=1", "--format=tap"])
+        .env("RUST_BACKTRACE", "0")
+        .output()
+        .expect("failed to run test binary");
+
+    diff()
+        .expected_file("output-default.tap")
+        .actual_text("actual", output.stdout)
+        .normalize(r"finished in [0-9.]+s", "finished in $$TIME")
+        .run();
+}
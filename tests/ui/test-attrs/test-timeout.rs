@@ -0,0 +1,50 @@
+//@ no-prefer-dynamic
+//@ compile-flags: --test
+//@ run-flags: --test-threads=1 -Zunstable-options --test-timeout=1
+//@ run-fail
+//@ check-run-results
+//@ exec-env:RUST_BACKTRACE=0
+//@ normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+
+//@ ignore-android #120567
+//@ ignore-wasm no subprocess support
+//@ ignore-emscripten no subprocess support
+//@ ignore-sgx no subprocess support
+
+// Check that `--test-timeout` kills hung tests, and that running each test
+// in a child process doesn't change the behavior of tests that finish in time.
+
+#![cfg(test)]
+
+use std::env;
+use std::panic;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn it_works() {
+    assert_eq!(1 + 1, 2);
+}
+
+#[test]
+fn it_hangs() {
+    println!("about to hang");
+    loop {
+        thread::sleep(Duration::from_secs(1));
+    }
+}
+
+#[test]
+fn it_catches_its_own_panic() {
+    assert!(panic::catch_unwind(|| panic!("caught")).is_err());
+}
+
+#[test]
+fn no_residual_environment() {
+    for (key, _) in env::vars() {
+        // Look for keys like __RUST_TEST_INVOKE.
+        if key.contains("TEST_INVOKE") {
+            panic!("shouldn't have '{}' in environment", key);
+        }
+    }
+}